@@ -0,0 +1,148 @@
+use juniper::GraphQLEnum;
+use serde_derive::{Deserialize, Serialize};
+use strum_macros::Display;
+
+use crate::persistence;
+use crate::ps_move::controller::{Modulation, PsMoveController, MAX_EFFECT_SPEED, MIN_EFFECT_SPEED};
+
+const BINDINGS_FILE: &str = "bindings.json";
+
+#[derive(GraphQLEnum, Serialize, Deserialize, PartialEq, Copy, Clone, Debug, Display)]
+pub enum AnalogSource {
+    #[graphql(description = "From 0.0 (released) to 1.0 (fully pulled).")]
+    Trigger,
+    #[graphql(description = "In degrees, 90.0 with the sphere up, -90.0 with it down.")]
+    Pitch,
+    #[graphql(description = "In degrees, around the length of the controller.")]
+    Roll,
+    #[graphql(description = "In degrees per second, around any axis.")]
+    RotationSpeed,
+}
+
+impl AnalogSource {
+    pub fn read(&self, controller: &PsMoveController) -> f32 {
+        match self {
+            AnalogSource::Trigger => controller.trigger / 255.0,
+            AnalogSource::Pitch => controller.get_pitch(),
+            AnalogSource::Roll => controller.get_roll(),
+            AnalogSource::RotationSpeed => controller.get_rotation_speed(),
+        }
+    }
+
+    /// Range covering the usual readings
+    pub fn get_default_range(&self) -> (f32, f32) {
+        match self {
+            AnalogSource::Trigger => (0.0, 1.0),
+            AnalogSource::Pitch | AnalogSource::Roll => (-90.0, 90.0),
+            AnalogSource::RotationSpeed => (0.0, 720.0),
+        }
+    }
+}
+
+#[derive(GraphQLEnum, Serialize, Deserialize, PartialEq, Copy, Clone, Debug, Display)]
+pub enum BindingParameter {
+    #[graphql(description = "Multiplies the value of the led. (min 0.0, max 1.0)")]
+    Brightness,
+    #[graphql(description = "Replaces the hue of the led. (min 0.0, max 360.0)")]
+    Hue,
+    #[graphql(description = "Replaces the saturation of the led. (min 0.0, max 1.0)")]
+    Saturation,
+    #[graphql(description = "Replaces the speed of the effects. (min 0.1, max 10.0)")]
+    Speed,
+}
+
+impl BindingParameter {
+    /// Lowest and highest values it can take
+    pub fn get_range(&self) -> (f32, f32) {
+        match self {
+            BindingParameter::Brightness | BindingParameter::Saturation => (0.0, 1.0),
+            BindingParameter::Hue => (0.0, 360.0),
+            BindingParameter::Speed => (MIN_EFFECT_SPEED, MAX_EFFECT_SPEED),
+        }
+    }
+
+    pub fn set(&self, modulation: &mut Modulation, value: f32) {
+        match self {
+            BindingParameter::Brightness => modulation.brightness = Some(value),
+            BindingParameter::Hue => modulation.hue = Some(value),
+            BindingParameter::Saturation => modulation.saturation = Some(value),
+            BindingParameter::Speed => modulation.speed = Some(value),
+        }
+    }
+}
+
+/// Shapes how the source, scaled to 0.0-1.0, maps to the parameter
+#[derive(GraphQLEnum, Serialize, Deserialize, PartialEq, Copy, Clone, Debug, Display, Default)]
+pub enum ResponseCurve {
+    #[default]
+    Linear,
+    #[graphql(description = "Slow at first, for finer control of low values.")]
+    EaseIn,
+    #[graphql(description = "Fast at first, for finer control of high values.")]
+    EaseOut,
+    #[graphql(description = "Slow at both ends.")]
+    SmoothStep,
+}
+
+impl ResponseCurve {
+    pub fn apply(&self, x: f32) -> f32 {
+        match self {
+            ResponseCurve::Linear => x,
+            ResponseCurve::EaseIn => x * x,
+            ResponseCurve::EaseOut => 1.0 - (1.0 - x) * (1.0 - x),
+            ResponseCurve::SmoothStep => x * x * (3.0 - 2.0 * x),
+        }
+    }
+}
+
+/// Drives a parameter of the effects from an analog input, on every tick
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Binding {
+    pub name: String,
+    pub enabled: bool,
+    pub source: AnalogSource,
+    /// Each controller drives its own effects if `None`
+    pub controller: Option<String>,
+    /// The controller of the source if `None`
+    pub targets: Option<Vec<String>>,
+    pub parameter: BindingParameter,
+    pub curve: ResponseCurve,
+    /// Readings of the source mapping to `output_min` (and `output_max`), clamped to them
+    pub input_min: f32,
+    pub input_max: f32,
+    /// Can be higher than `output_max`, to invert the binding
+    pub output_min: f32,
+    pub output_max: f32,
+}
+
+impl Binding {
+    pub fn get_output(&self, input: f32) -> f32 {
+        let x = ((input - self.input_min) / (self.input_max - self.input_min)).clamp(0.0, 1.0);
+
+        self.output_min + (self.output_max - self.output_min) * self.curve.apply(x)
+    }
+
+    /// Addresses of the controllers whose effects are driven by `source`
+    pub fn get_targets<'a>(&'a self, source: &'a str) -> Vec<&'a str> {
+        match &self.targets {
+            Some(targets) => targets.iter().map(String::as_str).collect(),
+            None => vec![source],
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct Bindings {
+    pub bindings: Vec<Binding>,
+}
+
+impl Bindings {
+    pub fn load() -> Bindings {
+        persistence::load(BINDINGS_FILE)
+    }
+
+    pub fn save(&self) {
+        persistence::save(BINDINGS_FILE, self)
+    }
+}
@@ -0,0 +1,121 @@
+use std::fs;
+use std::fs::File;
+use std::io::BufWriter;
+use std::time::Duration;
+
+use crate::ps_move::render;
+use crate::ps_move::snapshot::{LedEffectSnapshot, RumbleEffectSnapshot};
+
+const RENDER_USAGE: &str = "Usage: rusty_controller render <led effect json file> \
+    [--rumble <rumble effect json file>] [--duration <ms, 5000 by default>] \
+    [--interval <ms, 50 by default>] [--seed <number, 0 by default>] \
+    [--format <csv (by default) or png>] [--output <file, stdout by default for csv>]";
+
+enum Format {
+    Csv,
+    Png,
+}
+
+struct RenderArgs {
+    led: LedEffectSnapshot,
+    rumble: RumbleEffectSnapshot,
+    duration: Duration,
+    interval: Duration,
+    seed: u64,
+    format: Format,
+    output: Option<String>,
+}
+
+/// Runs the command in `args` (without the program name), instead of the server
+///
+/// Returns `None` if there's no command, so the server should start.
+pub fn run(args: &[String]) -> Option<Result<(), String>> {
+    match args.first().map(String::as_str) {
+        Some("render") => Some(parse_render_args(&args[1..]).and_then(|args| run_render(&args))),
+        _ => None,
+    }
+}
+
+/// Renders the effects (as saved in scenes or rules) to a CSV timeline or a PNG strip
+fn run_render(args: &RenderArgs) -> Result<(), String> {
+    let led = args.led.to_kind().map_err(|err| format!("Invalid led effect. {err}"))?;
+    let rumble = args.rumble.to_kind().map_err(|err| format!("Invalid rumble effect. {err}"))?;
+    let frames = render::render(
+        &led,
+        &rumble,
+        args.duration,
+        args.interval,
+        args.seed,
+    );
+
+    match (&args.format, &args.output) {
+        (Format::Csv, None) => render::write_csv(&frames, std::io::stdout().lock())
+            .map_err(|err| format!("Couldn't write the frames. Caused by {err}")),
+        (Format::Csv, Some(path)) => create_file(path).and_then(|file| {
+            render::write_csv(&frames, file).map_err(|err| format!("Couldn't write '{path}'. Caused by {err}"))
+        }),
+        (Format::Png, Some(path)) => create_file(path).and_then(|file| {
+            render::write_png(&frames, file).map_err(|err| format!("Couldn't write '{path}'. Caused by {err}"))
+        }),
+        (Format::Png, None) => Err(String::from("A PNG needs an --output file!")),
+    }
+}
+
+fn parse_render_args(args: &[String]) -> Result<RenderArgs, String> {
+    let mut args = args.iter();
+    let led = match args.next() {
+        Some(path) if !path.starts_with("--") => read_json(path)?,
+        _ => return Err(String::from(RENDER_USAGE)),
+    };
+    let mut render_args = RenderArgs {
+        led,
+        rumble: RumbleEffectSnapshot::Off,
+        duration: Duration::from_secs(5),
+        interval: Duration::from_millis(50),
+        seed: 0,
+        format: Format::Csv,
+        output: None,
+    };
+
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("{flag} needs a value!\n{RENDER_USAGE}"))?;
+
+        match flag.as_str() {
+            "--rumble" => render_args.rumble = read_json(value)?,
+            "--duration" => render_args.duration = Duration::from_millis(parse_number(flag, value)?),
+            "--interval" => render_args.interval = Duration::from_millis(parse_number(flag, value)?),
+            "--seed" => render_args.seed = parse_number(flag, value)?,
+            "--format" => {
+                render_args.format = match value.as_str() {
+                    "csv" => Format::Csv,
+                    "png" => Format::Png,
+                    _ => return Err(format!("Unknown format '{value}'!\n{RENDER_USAGE}")),
+                }
+            }
+            "--output" => render_args.output = Some(value.clone()),
+            _ => return Err(format!("Unknown option '{flag}'!\n{RENDER_USAGE}")),
+        }
+    }
+
+    Ok(render_args)
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &str) -> Result<T, String> {
+    let json = fs::read_to_string(path).map_err(|err| format!("Couldn't read '{path}'. Caused by {err}"))?;
+
+    serde_json::from_str(&json).map_err(|err| format!("Couldn't parse '{path}'. Caused by {err}"))
+}
+
+fn parse_number(flag: &str, value: &str) -> Result<u64, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{flag} must be a positive number!"))
+}
+
+fn create_file(path: &str) -> Result<BufWriter<File>, String> {
+    File::create(path)
+        .map(BufWriter::new)
+        .map_err(|err| format!("Couldn't create '{path}'. Caused by {err}"))
+}
@@ -0,0 +1,928 @@
+use std::collections::HashSet;
+
+use juniper::{FieldError, FieldResult, Value};
+use lazy_static::lazy_static;
+
+use crate::graphql::schema_input::*;
+use crate::graphql::schema_response::{EffectCategory, EffectDescription, EffectParameter, EffectParameterType};
+use crate::ps_move::colors::ColorSpace;
+
+const COLOR_SPACES: [ColorSpace; 4] = [ColorSpace::Hsv, ColorSpace::LinearRgb, ColorSpace::Oklab, ColorSpace::Oklch];
+
+lazy_static! {
+    static ref EFFECT_CATALOG: Vec<EffectDescription> = build_catalog();
+}
+
+/// Every effect `applyEffect` can apply, led ones first
+pub(super) fn get_effect_catalog() -> &'static [EffectDescription] {
+    &EFFECT_CATALOG
+}
+
+pub(super) fn find_effect(category: EffectCategory, kind: &str) -> FieldResult<&'static EffectDescription> {
+    EFFECT_CATALOG
+        .iter()
+        .find(|effect| effect.category == category && effect.kind == kind)
+        .ok_or_else(|| FieldError::new("No effect with this kind!", Value::Null))
+}
+
+/// Checks the parameters exist, have the right type and are within range, and the required ones
+/// (or one of their alternatives) are there
+///
+/// Rules between parameters (like a min below a max, or which alternatives go together) are left
+/// to the builder of the effect.
+pub(super) fn validate_parameters(effect: &EffectDescription, parameters: &[EffectParameterInput]) -> FieldResult<()> {
+    let mut names = HashSet::new();
+
+    for input in parameters {
+        let Some(parameter) = effect.parameters.iter().find(|parameter| parameter.name == input.name) else {
+            return Err(FieldError::new(
+                format!("'{}' isn't a parameter of {}!", input.name, effect.kind),
+                Value::Null,
+            ));
+        };
+
+        if !names.insert(input.name.as_str()) {
+            return Err(FieldError::new(format!("'{}' is specified twice!", input.name), Value::Null));
+        }
+
+        validate_parameter(parameter, input)?;
+    }
+
+    let is_missing = |parameter: &&EffectParameter| {
+        let has_alternative = parameter
+            .alternatives
+            .iter()
+            .flatten()
+            .any(|alternative| names.contains(alternative.as_str()));
+
+        parameter.required && !names.contains(parameter.name.as_str()) && !has_alternative
+    };
+
+    match effect.parameters.iter().find(is_missing) {
+        Some(EffectParameter {
+            name,
+            alternatives: Some(alternatives),
+            ..
+        }) => Err(FieldError::new(
+            format!("'{name}' (or '{}') must be specified!", alternatives.join("', '")),
+            Value::Null,
+        )),
+        Some(parameter) => Err(FieldError::new(format!("'{}' must be specified!", parameter.name), Value::Null)),
+        None => Ok(()),
+    }
+}
+
+fn validate_parameter(parameter: &EffectParameter, input: &EffectParameterInput) -> FieldResult<()> {
+    let name = &parameter.name;
+    let is_set = match parameter.type_ {
+        EffectParameterType::Float | EffectParameterType::Int => input.number.is_some(),
+        EffectParameterType::Ints => input.numbers.is_some(),
+        EffectParameterType::Color => input.color.is_some(),
+        EffectParameterType::Colors => input.colors.is_some(),
+        EffectParameterType::Text | EffectParameterType::Choice => input.text.is_some(),
+    };
+    let value_count = [
+        input.number.is_some(),
+        input.numbers.is_some(),
+        input.color.is_some(),
+        input.colors.is_some(),
+        input.text.is_some(),
+    ]
+    .into_iter()
+    .filter(|is_set| *is_set)
+    .count();
+
+    if !is_set || value_count > 1 {
+        let expected = match parameter.type_ {
+            EffectParameterType::Float | EffectParameterType::Int => "a number",
+            EffectParameterType::Ints => "numbers",
+            EffectParameterType::Color => "a color",
+            EffectParameterType::Colors => "colors",
+            EffectParameterType::Text | EffectParameterType::Choice => "a text",
+        };
+
+        return Err(FieldError::new(format!("'{name}' must only be {expected}!"), Value::Null));
+    }
+
+    for number in input.number.iter().chain(input.numbers.iter().flatten()).copied() {
+        let is_int = matches!(parameter.type_, EffectParameterType::Int | EffectParameterType::Ints);
+
+        if is_int && number.fract() != 0.0 {
+            return Err(FieldError::new(format!("'{name}' must be an integer!"), Value::Null));
+        }
+
+        match (parameter.min, parameter.max) {
+            (Some(min), Some(max)) if !(min..=max).contains(&number) => {
+                return Err(FieldError::new(
+                    format!("'{name}' must be between {min:?} and {max:?}!"),
+                    Value::Null,
+                ));
+            }
+            (Some(min), None) if number < min => {
+                return Err(FieldError::new(format!("'{name}' must be at least {min:?}!"), Value::Null));
+            }
+            _ => {}
+        }
+    }
+
+    if let (Some(text), Some(options)) = (&input.text, &parameter.options) {
+        if !options.contains(text) {
+            return Err(FieldError::new(
+                format!("'{name}' must be one of {}!", options.join(", ")),
+                Value::Null,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Values of validated parameters, by name
+pub(super) struct EffectParameters<'a>(pub &'a [EffectParameterInput]);
+
+impl EffectParameters<'_> {
+    fn get(&self, name: &str) -> Option<&EffectParameterInput> {
+        self.0.iter().find(|parameter| parameter.name == name)
+    }
+
+    fn float(&self, name: &str) -> Option<f64> {
+        self.get(name).and_then(|parameter| parameter.number)
+    }
+
+    fn int(&self, name: &str) -> Option<i32> {
+        self.float(name).map(|number| number as i32)
+    }
+
+    fn ints(&self, name: &str) -> Option<Vec<i32>> {
+        self.get(name)
+            .and_then(|parameter| parameter.numbers.as_ref())
+            .map(|numbers| numbers.iter().map(|number| *number as i32).collect())
+    }
+
+    /// Of a required parameter, so it's always there once validated
+    fn required_float(&self, name: &str) -> f64 {
+        self.float(name).unwrap_or_default()
+    }
+
+    fn required_int(&self, name: &str) -> i32 {
+        self.int(name).unwrap_or_default()
+    }
+
+    fn color(&self, name: &str) -> Option<ColorInput> {
+        self.get(name).and_then(|parameter| parameter.color.clone())
+    }
+
+    fn colors(&self, name: &str) -> Option<Vec<ColorInput>> {
+        self.get(name).and_then(|parameter| parameter.colors.clone())
+    }
+
+    fn text(&self, name: &str) -> Option<String> {
+        self.get(name).and_then(|parameter| parameter.text.clone())
+    }
+
+    fn color_space(&self, name: &str) -> Option<ColorSpace> {
+        let text = self.text(name)?;
+
+        COLOR_SPACES.into_iter().find(|color_space| color_space.to_string() == text)
+    }
+}
+
+/// The led effects which aren't a group, as the input nested effects use
+pub(super) fn to_led_effect_input(kind: &str, parameters: &EffectParameters) -> LedEffectInput {
+    let p = parameters;
+    let mut input = LedEffectInput {
+        off: None,
+        static_: None,
+        breathing: None,
+        rainbow: None,
+        blink: None,
+        candle: None,
+        bounce: None,
+        fire: None,
+        lightning: None,
+        heartbeat: None,
+        strobe: None,
+        police: None,
+        sparkle: None,
+        noise: None,
+    };
+
+    match kind {
+        "off" => input.off = Some(true),
+        "static" => {
+            input.static_ = Some(StaticLedEffectInput {
+                controllers: None,
+                duration: None,
+                name: None,
+                transition_ms: None,
+                transition_color_space: None,
+                color: p.color("color"),
+                hue: p.int("hue"),
+                saturation: p.float("saturation"),
+                value: p.float("value"),
+            })
+        }
+        "breathing" => {
+            input.breathing = Some(BreathingLedEffectInput {
+                controllers: None,
+                duration: None,
+                name: None,
+                transition_ms: None,
+                transition_color_space: None,
+                color: p.color("color"),
+                hue: p.int("hue"),
+                saturation: p.float("saturation"),
+                initial_value: p.required_float("initialValue"),
+                time_to_peak: p.required_int("timeToPeak"),
+                peak: p.required_float("peak"),
+            })
+        }
+        "rainbow" => {
+            input.rainbow = Some(RainbowLedEffectInput {
+                controllers: None,
+                duration: None,
+                name: None,
+                transition_ms: None,
+                transition_color_space: None,
+                saturation: p.required_float("saturation"),
+                value: p.required_float("value"),
+                time_to_complete: p.required_float("timeToComplete"),
+                palette: p.text("palette"),
+            })
+        }
+        "blink" => {
+            input.blink = Some(BlinkLedEffectInput {
+                controllers: None,
+                duration: None,
+                name: None,
+                transition_ms: None,
+                transition_color_space: None,
+                color: p.color("color"),
+                hue: p.int("hue"),
+                saturation: p.float("saturation"),
+                value: p.float("value"),
+                interval: p.required_int("interval"),
+            })
+        }
+        "candle" => {
+            input.candle = Some(CandleLedEffectInput {
+                controllers: None,
+                duration: None,
+                name: None,
+                transition_ms: None,
+                transition_color_space: None,
+                color: p.color("color"),
+                hue: p.int("hue"),
+                saturation: p.float("saturation"),
+                palette: p.text("palette"),
+                min_value: p.required_float("minValue"),
+                max_value: p.required_float("maxValue"),
+                variability: p.required_float("variability"),
+                interval: p.int("interval"),
+            })
+        }
+        "bounce" => {
+            input.bounce = Some(BounceLedEffectInput {
+                controllers: None,
+                duration: None,
+                name: None,
+                transition_ms: None,
+                transition_color_space: None,
+                palette: p.text("palette"),
+                colors: p.colors("colors"),
+                hues: p.ints("hues"),
+                saturation: p.float("saturation"),
+                value: p.float("value"),
+                step: p.required_float("step"),
+                color_space: p.color_space("colorSpace"),
+            })
+        }
+        "fire" => {
+            input.fire = Some(FireLedEffectInput {
+                controllers: None,
+                duration: None,
+                name: None,
+                transition_ms: None,
+                transition_color_space: None,
+                color: p.color("color"),
+                hue: p.int("hue"),
+                saturation: p.float("saturation"),
+                min_value: p.required_float("minValue"),
+                max_value: p.required_float("maxValue"),
+            })
+        }
+        "lightning" => {
+            input.lightning = Some(LightningLedEffectInput {
+                controllers: None,
+                duration: None,
+                name: None,
+                transition_ms: None,
+                transition_color_space: None,
+                color: p.color("color"),
+                hue: p.int("hue"),
+                saturation: p.float("saturation"),
+                value: p.float("value"),
+                min_interval: p.required_int("minInterval"),
+                max_interval: p.required_int("maxInterval"),
+                afterglow: p.int("afterglow"),
+            })
+        }
+        "heartbeat" => {
+            input.heartbeat = Some(HeartbeatLedEffectInput {
+                controllers: None,
+                duration: None,
+                name: None,
+                transition_ms: None,
+                transition_color_space: None,
+                color: p.color("color"),
+                hue: p.int("hue"),
+                saturation: p.float("saturation"),
+                value: p.float("value"),
+                min_value: p.float("minValue"),
+                bpm: p.required_float("bpm"),
+            })
+        }
+        "strobe" => {
+            input.strobe = Some(StrobeLedEffectInput {
+                controllers: None,
+                duration: None,
+                name: None,
+                transition_ms: None,
+                transition_color_space: None,
+                color: p.color("color"),
+                hue: p.int("hue"),
+                saturation: p.float("saturation"),
+                value: p.float("value"),
+                frequency: p.required_float("frequency"),
+                duty_cycle: p.float("dutyCycle"),
+            })
+        }
+        "police" => {
+            input.police = Some(PoliceLedEffectInput {
+                controllers: None,
+                duration: None,
+                name: None,
+                transition_ms: None,
+                transition_color_space: None,
+                first: p.color("first"),
+                second: p.color("second"),
+                interval: p.required_int("interval"),
+                flashes: p.int("flashes"),
+            })
+        }
+        "sparkle" => {
+            input.sparkle = Some(SparkleLedEffectInput {
+                controllers: None,
+                duration: None,
+                name: None,
+                transition_ms: None,
+                transition_color_space: None,
+                color: p.color("color"),
+                hue: p.int("hue"),
+                saturation: p.float("saturation"),
+                value: p.float("value"),
+                sparkle_value: p.float("sparkleValue"),
+                rate: p.required_float("rate"),
+                sparkle_duration: p.int("sparkleDuration"),
+            })
+        }
+        "noise" => {
+            input.noise = Some(NoiseLedEffectInput {
+                controllers: None,
+                duration: None,
+                name: None,
+                transition_ms: None,
+                transition_color_space: None,
+                min_hue: p.required_int("minHue"),
+                max_hue: p.required_int("maxHue"),
+                saturation: p.required_float("saturation"),
+                min_value: p.required_float("minValue"),
+                max_value: p.required_float("maxValue"),
+                speed: p.required_float("speed"),
+                octaves: p.int("octaves"),
+                seed: p.int("seed"),
+            })
+        }
+        _ => {}
+    }
+
+    input
+}
+
+pub(super) fn to_wave_led_effect_input(input: &ApplyEffectInput, parameters: &EffectParameters) -> WaveLedEffectInput {
+    let p = parameters;
+
+    WaveLedEffectInput {
+        controllers: input.controllers.clone(),
+        duration: input.duration,
+        name: input.name.clone(),
+        transition_ms: input.transition_ms,
+        transition_color_space: input.transition_color_space,
+        color: p.color("color"),
+        hue: p.int("hue"),
+        saturation: p.float("saturation"),
+        min_value: p.required_float("minValue"),
+        max_value: p.required_float("maxValue"),
+        period: p.required_int("period"),
+        spread: p.required_float("spread"),
+    }
+}
+
+pub(super) fn to_chase_led_effect_input(input: &ApplyEffectInput, parameters: &EffectParameters) -> ChaseLedEffectInput {
+    let p = parameters;
+
+    ChaseLedEffectInput {
+        controllers: input.controllers.clone(),
+        duration: input.duration,
+        name: input.name.clone(),
+        transition_ms: input.transition_ms,
+        transition_color_space: input.transition_color_space,
+        color: p.color("color"),
+        hue: p.int("hue"),
+        saturation: p.float("saturation"),
+        value: p.float("value"),
+        background_value: p.float("backgroundValue"),
+        interval: p.required_int("interval"),
+    }
+}
+
+pub(super) fn to_rumble_effect_input(kind: &str, parameters: &EffectParameters) -> RumbleEffectInput {
+    let p = parameters;
+    let mut input = RumbleEffectInput {
+        off: None,
+        static_: None,
+        breathing: None,
+        blink: None,
+        follow_led: None,
+    };
+
+    match kind {
+        "off" => input.off = Some(true),
+        "static" => {
+            input.static_ = Some(StaticRumbleEffectInput {
+                controllers: None,
+                duration: None,
+                strength: p.required_float("strength"),
+            })
+        }
+        "breathing" => {
+            input.breathing = Some(BreathingRumbleEffectInput {
+                controllers: None,
+                duration: None,
+                initial_strength: p.required_float("initialStrength"),
+                step: p.required_float("step"),
+                peak: p.required_float("peak"),
+            })
+        }
+        "blink" => {
+            input.blink = Some(BlinkRumbleEffectInput {
+                controllers: None,
+                duration: None,
+                strength: p.required_float("strength"),
+                interval: p.required_int("interval"),
+            })
+        }
+        "followLed" => {
+            input.follow_led = Some(FollowLedRumbleEffectInput {
+                controllers: None,
+                duration: None,
+                min_strength: p.float("minStrength"),
+                max_strength: p.required_float("maxStrength"),
+            })
+        }
+        _ => {}
+    }
+
+    input
+}
+
+fn build_catalog() -> Vec<EffectDescription> {
+    let led = |kind: &str, description: &str, parameters: Vec<EffectParameter>| EffectDescription {
+        kind: String::from(kind),
+        category: EffectCategory::Led,
+        description: String::from(description),
+        group: false,
+        parameters,
+    };
+    let rumble = |kind: &str, description: &str, parameters: Vec<EffectParameter>| EffectDescription {
+        kind: String::from(kind),
+        category: EffectCategory::Rumble,
+        description: String::from(description),
+        group: false,
+        parameters,
+    };
+    // either `color`, or the hue, saturation (and value) the effects also take
+    let color = |description: &str| {
+        vec![
+            parameter("color", EffectParameterType::Color, description).unless(&["hue", "saturation"]),
+            parameter("hue", EffectParameterType::Int, "Hue, instead of `color`.").range(0.0, 360.0).optional(),
+            unit("saturation", "Saturation, instead of `color`.").optional(),
+        ]
+    };
+    let color_value = |description: &str| {
+        let mut parameters = color(description);
+        parameters.push(unit("value", "Value/brightness, instead of `color`.").optional());
+        parameters
+    };
+
+    vec![
+        led("off", "Turns the led off.", vec![]),
+        led("static", "A constant color.", color_value("Color.")),
+        led(
+            "breathing",
+            "Brightness rising to a peak over time, then going back.",
+            [
+                color("Color. (its brightness isn't used)"),
+                vec![
+                    unit("initialValue", "Initial value/brightness, up to `peak`."),
+                    millis("timeToPeak", "Time the value takes to reach `peak`, in milliseconds."),
+                    unit("peak", "Highest value/brightness."),
+                ],
+            ]
+            .concat(),
+        ),
+        led(
+            "rainbow",
+            "Going through every hue, or the colors of a palette.",
+            vec![
+                unit("saturation", "Saturation."),
+                unit("value", "Value/brightness."),
+                parameter("timeToComplete", EffectParameterType::Float, "Time of a full cycle, in seconds.").min(0.0),
+                parameter("palette", EffectParameterType::Text, "Name of a palette to cycle through, instead of every hue.")
+                    .optional(),
+            ],
+        ),
+        led(
+            "blink",
+            "Alternating between the color and off.",
+            [color_value("Color."), vec![millis("interval", "Interval between blinks, in milliseconds.")]].concat(),
+        ),
+        led(
+            "candle",
+            "Flickering like a candle.",
+            vec![
+                parameter("color", EffectParameterType::Color, "Color. (its brightness isn't used)")
+                    .unless(&["hue", "saturation", "palette"]),
+                parameter("hue", EffectParameterType::Int, "Hue, instead of `color`.").range(0.0, 360.0).optional(),
+                unit("saturation", "Saturation, instead of `color`.").optional(),
+                parameter(
+                    "palette",
+                    EffectParameterType::Text,
+                    "Name of a palette to pick a color from on each change, instead of the color.",
+                )
+                .optional(),
+                unit("minValue", "Lowest value/brightness."),
+                    unit("maxValue", "Highest value/brightness."),
+                unit("variability", "Fraction of the range the value changes by."),
+                millis("interval", "Interval between changes, in milliseconds.").default(1.0),
+            ],
+        ),
+        led(
+            "bounce",
+            "Blending from one color to the next, and around.",
+            vec![
+                parameter("palette", EffectParameterType::Text, "Name of a palette to bounce, instead of `colors`.")
+                    .optional(),
+                parameter("colors", EffectParameterType::Colors, "Colors to bounce.").unless(&["palette", "hues"]),
+                parameter("hues", EffectParameterType::Ints, "Hues to bounce, instead of `colors`.")
+                    .range(0.0, 360.0)
+                    .optional(),
+                unit("saturation", "Saturation of the `hues`.").optional(),
+                unit("value", "Value/brightness of the `hues`.").optional(),
+                unit("step", "Progress from one color to the next per update. (every 10ms)"),
+                choice("colorSpace", &COLOR_SPACES, "Color space the colors are blended in. (Oklab by default)"),
+            ],
+        ),
+        led(
+            "fire",
+            "Flickering and flaring like a fire.",
+            [
+                color("Color of the embers, shifting towards yellow as the fire flares. (its brightness isn't used)"),
+                vec![unit("minValue", "Lowest value/brightness."), unit("maxValue", "Highest value/brightness.")],
+            ]
+            .concat(),
+        ),
+        led(
+            "lightning",
+            "Bursts of flashes at random times, fading out.",
+            [
+                color_value("Color."),
+                vec![
+                    millis("minInterval", "Shortest time between strikes, in milliseconds. (above 0)"),
+                    millis("maxInterval", "Longest time between strikes, in milliseconds."),
+                    millis("afterglow", "Time the glow takes to fade out after the flashes, in milliseconds.")
+                        .default(300.0),
+                ],
+            ]
+            .concat(),
+        ),
+        led(
+            "heartbeat",
+            "Beating twice, like a heart.",
+            [
+                color_value("Color."),
+                vec![
+                    unit("minValue", "Value/brightness between beats.").default(0.0),
+                    parameter("bpm", EffectParameterType::Float, "Beats per minute.").range(1.0, 300.0),
+                ],
+            ]
+            .concat(),
+        ),
+        led(
+            "strobe",
+            "Short flashes at a fixed rate.",
+            [
+                color_value("Color."),
+                vec![
+                    parameter("frequency", EffectParameterType::Float, "Flashes per second. (above 0.0)").range(0.0, 50.0),
+                    unit("dutyCycle", "Fraction of each period the led is lit. (above 0.0)").default(0.1),
+                ],
+            ]
+            .concat(),
+        ),
+        led(
+            "police",
+            "Flashing two colors in turns.",
+            vec![
+                parameter("first", EffectParameterType::Color, "First color. (red by default)").optional(),
+                parameter("second", EffectParameterType::Color, "Second color. (blue by default)").optional(),
+                millis("interval", "Time each color lasts, in milliseconds. (above 0)"),
+                parameter("flashes", EffectParameterType::Int, "Flashes of each color during its interval.")
+                    .range(1.0, 10.0)
+                    .default(2.0),
+            ],
+        ),
+        led(
+            "sparkle",
+            "White sparkles at random times, fading into the color.",
+            [
+                color_value("Base color."),
+                vec![
+                    unit("sparkleValue", "Brightness of the sparkles.").default(1.0),
+                    parameter("rate", EffectParameterType::Float, "Average sparkles per second. (above 0.0)")
+                        .range(0.0, 100.0),
+                    millis("sparkleDuration", "Time a sparkle takes to fade out, in milliseconds.").default(100.0),
+                ],
+            ]
+            .concat(),
+        ),
+        led(
+            "noise",
+            "Drifting smoothly through a range of hues and brightness.",
+            vec![
+                parameter("minHue", EffectParameterType::Int, "Start of the hue range.").range(0.0, 360.0),
+                parameter(
+                    "maxHue",
+                    EffectParameterType::Int,
+                    "End of the hue range, going up from `minHue`. (wrapping around 360)",
+                )
+                .range(0.0, 360.0),
+                unit("saturation", "Saturation."),
+                unit("minValue", "Lowest value/brightness."),
+                unit("maxValue", "Highest value/brightness."),
+                parameter("speed", EffectParameterType::Float, "Variations per second, roughly. (above 0.0)")
+                    .range(0.0, 20.0),
+                parameter("octaves", EffectParameterType::Int, "Layers of finer detail.")
+                    .range(1.0, 8.0)
+                    .default(3.0),
+                parameter("seed", EffectParameterType::Int, "Makes the effect reproducible, random if not specified.")
+                    .min(0.0)
+                    .optional(),
+            ],
+        ),
+        EffectDescription {
+            group: true,
+            ..led(
+                "wave",
+                "Breathing across the controllers in order, each one behind the previous.",
+                [
+                    color("Color. (its brightness isn't used)"),
+                    vec![
+                        unit("minValue", "Lowest value/brightness."),
+                        unit("maxValue", "Highest value/brightness."),
+                        millis("period", "Time of one full wave, in milliseconds. (above 0)"),
+                        unit("spread", "Fraction of the period spread across the controllers."),
+                    ],
+                ]
+                .concat(),
+            )
+        },
+        EffectDescription {
+            group: true,
+            ..led(
+                "chase",
+                "Lighting one controller at a time, moving along them in order.",
+                [
+                    color_value("Color of the lit controller."),
+                    vec![
+                        unit("backgroundValue", "Value/brightness of the other controllers.").default(0.0),
+                        millis("interval", "Time each controller stays lit, in milliseconds. (above 0)"),
+                    ],
+                ]
+                .concat(),
+            )
+        },
+        rumble("off", "Turns the rumble off.", vec![]),
+        rumble("static", "A constant rumble.", vec![unit("strength", "Strength.")]),
+        rumble(
+            "breathing",
+            "Strength rising to a peak over time, then going back.",
+            vec![
+                unit("initialStrength", "Initial strength, up to `peak`."),
+                unit("step", "Change of the strength per update, relative to `peak`."),
+                unit("peak", "Highest strength."),
+            ],
+        ),
+        rumble(
+            "blink",
+            "Alternating between rumbling and not.",
+            vec![unit("strength", "Strength."), millis("interval", "Interval between blinks, in milliseconds.")],
+        ),
+        rumble(
+            "followLed",
+            "Following the brightness of the led.",
+            vec![
+                unit("minStrength", "Strength while the led is off.").default(0.0),
+                unit("maxStrength", "Strength at the full brightness of the led."),
+            ],
+        ),
+    ]
+}
+
+fn parameter(name: &str, type_: EffectParameterType, description: &str) -> EffectParameter {
+    EffectParameter {
+        name: String::from(name),
+        type_,
+        required: true,
+        alternatives: None,
+        min: None,
+        max: None,
+        default: None,
+        options: None,
+        description: String::from(description),
+    }
+}
+
+/// From 0.0 to 1.0
+fn unit(name: &str, description: &str) -> EffectParameter {
+    parameter(name, EffectParameterType::Float, description).range(0.0, 1.0)
+}
+
+fn millis(name: &str, description: &str) -> EffectParameter {
+    parameter(name, EffectParameterType::Int, description).min(0.0)
+}
+
+fn choice<T: ToString>(name: &str, options: &[T], description: &str) -> EffectParameter {
+    EffectParameter {
+        options: Some(options.iter().map(ToString::to_string).collect()),
+        ..parameter(name, EffectParameterType::Choice, description).optional()
+    }
+}
+
+impl EffectParameter {
+    fn optional(self) -> EffectParameter {
+        EffectParameter {
+            required: false,
+            ..self
+        }
+    }
+
+    /// Still required, unless one of `alternatives` is specified
+    fn unless(self, alternatives: &[&str]) -> EffectParameter {
+        EffectParameter {
+            alternatives: Some(alternatives.iter().map(|name| String::from(*name)).collect()),
+            ..self
+        }
+    }
+
+    fn default(self, default: f64) -> EffectParameter {
+        EffectParameter {
+            default: Some(default),
+            ..self.optional()
+        }
+    }
+
+    fn min(self, min: f64) -> EffectParameter {
+        EffectParameter { min: Some(min), ..self }
+    }
+
+    fn range(self, min: f64, max: f64) -> EffectParameter {
+        EffectParameter {
+            max: Some(max),
+            ..self.min(min)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use juniper::FieldResult;
+
+    use crate::graphql::schema_mutation::{
+        build_chase_led_effects, build_led_effect, build_rumble_effect, build_wave_led_effects,
+    };
+    use crate::palettes::Palettes;
+
+    use super::*;
+
+    const CONTROLLERS: [&str; 2] = ["00:00:00:00:00:01", "00:00:00:00:00:02"];
+
+    /// Within its range, so any parameter taking a number is valid
+    fn get_number(parameter: &EffectParameter) -> f64 {
+        parameter.default.or(parameter.max).unwrap_or(parameter.min.unwrap_or_default() + 1.0)
+    }
+
+    fn to_input(parameter: &EffectParameter) -> EffectParameterInput {
+        let color = ColorInput {
+            hsv: None,
+            rgb: None,
+            hex: Some(String::from("#ff8800")),
+            name: None,
+            kelvin: None,
+        };
+        let mut input = EffectParameterInput {
+            name: parameter.name.clone(),
+            number: None,
+            numbers: None,
+            color: None,
+            colors: None,
+            text: None,
+        };
+
+        match parameter.type_ {
+            EffectParameterType::Float | EffectParameterType::Int => input.number = Some(get_number(parameter)),
+            EffectParameterType::Ints => input.numbers = Some(vec![get_number(parameter); 2]),
+            EffectParameterType::Color => input.color = Some(color),
+            EffectParameterType::Colors => input.colors = Some(vec![color.clone(), color]),
+            EffectParameterType::Text => input.text = Some(String::from("fire")),
+            EffectParameterType::Choice => input.text = parameter.options.as_ref().map(|options| options[0].clone()),
+        }
+        input
+    }
+
+    /// As `applyEffect` builds it
+    fn build(effect: &EffectDescription, parameters: &[EffectParameterInput]) -> FieldResult<()> {
+        validate_parameters(effect, parameters)?;
+
+        let parameters = EffectParameters(parameters);
+        let controllers = CONTROLLERS.map(String::from);
+        let input = ApplyEffectInput {
+            controllers: None,
+            duration: None,
+            name: None,
+            transition_ms: None,
+            transition_color_space: None,
+            category: effect.category,
+            kind: effect.kind.clone(),
+            parameters: None,
+        };
+
+        match (effect.category, effect.kind.as_str()) {
+            (EffectCategory::Rumble, kind) => build_rumble_effect(&to_rumble_effect_input(kind, &parameters)).map(|_| ()),
+            (EffectCategory::Led, "wave") => {
+                build_wave_led_effects(&to_wave_led_effect_input(&input, &parameters), &controllers).map(|_| ())
+            }
+            (EffectCategory::Led, "chase") => {
+                build_chase_led_effects(&to_chase_led_effect_input(&input, &parameters), &controllers).map(|_| ())
+            }
+            (EffectCategory::Led, kind) => {
+                build_led_effect(&to_led_effect_input(kind, &parameters), &Palettes::default()).map(|_| ())
+            }
+        }
+    }
+
+    #[test]
+    fn builds_every_effect_with_its_required_parameters() {
+        for effect in get_effect_catalog() {
+            let parameters = effect
+                .parameters
+                .iter()
+                .filter(|parameter| parameter.required)
+                .map(to_input)
+                .collect::<Vec<_>>();
+
+            if let Err(err) = build(effect, &parameters) {
+                panic!("{:?} {} doesn't build: {}", effect.category, effect.kind, err.message());
+            }
+        }
+    }
+
+    #[test]
+    fn builds_a_bounce_from_hues() {
+        let bounce = find_effect(EffectCategory::Led, "bounce").unwrap();
+        let get_parameters = |names: &[&str]| {
+            bounce
+                .parameters
+                .iter()
+                .filter(|parameter| names.contains(&parameter.name.as_str()))
+                .map(to_input)
+                .collect::<Vec<_>>()
+        };
+
+        assert!(build(bounce, &get_parameters(&["hues", "saturation", "value", "step"])).is_ok());
+        assert!(
+            build(bounce, &get_parameters(&["hues", "step"])).is_err(),
+            "built from hues without saturation and value"
+        );
+    }
+}
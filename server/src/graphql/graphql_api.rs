@@ -1,3 +1,4 @@
+use std::net::SocketAddr;
 use std::sync::Arc;
 
 use futures::FutureExt as _;
@@ -11,14 +12,29 @@ use warp::{Filter, http::Response};
 
 use crate::{ControllerChange, EffectChange};
 use crate::monitoring::metrics::metrics_handler;
+use crate::palettes::Palettes;
+use crate::bindings::Bindings;
+use crate::rules::Rules;
+use crate::scenes::Scenes;
 use crate::ps_move::controller::PsMoveController;
+use crate::schedules::Schedules;
+use crate::settings::Settings;
+use crate::tasks::scheduler::ScheduledEffects;
 
 use super::schema::{Context, create_schema};
 
+#[allow(clippy::too_many_arguments)]
 pub async fn start(
     effect_tx: Arc<Sender<EffectChange>>,
     ctrl_rx: Mutex<Receiver<ControllerChange>>,
     controllers: Arc<Mutex<Vec<PsMoveController>>>,
+    settings: Arc<Mutex<Settings>>,
+    scheduled_effects: Arc<Mutex<ScheduledEffects>>,
+    schedules: Arc<Mutex<Schedules>>,
+    palettes: Arc<Mutex<Palettes>>,
+    scenes: Arc<Mutex<Scenes>>,
+    rules: Arc<Mutex<Rules>>,
+    bindings: Arc<Mutex<Bindings>>,
 ) {
     let log = warp::log("warp_subscriptions");
     let ctrl_rx_arc = Arc::new(ctrl_rx);
@@ -26,6 +42,14 @@ pub async fn start(
         effect_tx: effect_tx.clone(),
         ctrl_rx: ctrl_rx_arc.clone(),
         controllers: controllers.clone(),
+        settings: settings.clone(),
+        scheduled_effects: scheduled_effects.clone(),
+        schedules: schedules.clone(),
+        palettes: palettes.clone(),
+        scenes: scenes.clone(),
+        rules: rules.clone(),
+        bindings: bindings.clone(),
+        client: String::new(),
     };
 
     let homepage = warp::path::end().map(|| {
@@ -35,7 +59,10 @@ pub async fn start(
     });
 
     let qm_schema = create_schema();
-    let qm_state = warp::any().map(move || qm_ctx.clone());
+    let qm_state = client_filter().map(move |client| Context {
+        client,
+        ..qm_ctx.clone()
+    });
     let qm_graphql_filter = juniper_warp::make_graphql_filter(qm_schema, qm_state.boxed());
 
     let root_node = Arc::new(create_schema());
@@ -44,12 +71,21 @@ pub async fn start(
 
     let routes = (warp::path("subscriptions")
         .and(warp::ws())
-        .map(move |ws: warp::ws::Ws| {
+        .and(client_filter())
+        .map(move |ws: warp::ws::Ws, client: String| {
             let root_node = root_node.clone();
             let ctx = Context {
                 effect_tx: effect_tx.clone(),
                 ctrl_rx: ctrl_rx_arc.clone(),
                 controllers: controllers.clone(),
+                settings: settings.clone(),
+                scheduled_effects: scheduled_effects.clone(),
+                schedules: schedules.clone(),
+                palettes: palettes.clone(),
+                scenes: scenes.clone(),
+                rules: rules.clone(),
+                bindings: bindings.clone(),
+                client,
             };
 
             ws.on_upgrade(move |websocket| async move {
@@ -75,3 +111,13 @@ pub async fn start(
 
     warp::serve(routes).run(([0, 0, 0, 0], 8080)).await
 }
+
+/// The `x-client-name` header if there is one, the remote address otherwise
+fn client_filter() -> impl Filter<Extract = (String,), Error = warp::Rejection> + Clone {
+    warp::header::optional::<String>("x-client-name")
+        .and(warp::addr::remote())
+        .map(|name: Option<String>, address: Option<SocketAddr>| {
+            name.or_else(|| address.map(|address| address.ip().to_string()))
+                .unwrap_or_else(|| String::from("unknown"))
+        })
+}
@@ -1,5 +1,6 @@
 pub mod graphql_api;
 
+mod effect_catalog;
 mod schema;
 mod schema_input;
 mod schema_query;
@@ -9,11 +9,27 @@ use crate::{ControllerChange, EffectChange, PsMoveController};
 use crate::graphql::schema_mutation::MutationRoot;
 use crate::graphql::schema_query::QueryRoot;
 use crate::graphql::schema_subscription::SubscriptionRoot;
+use crate::palettes::Palettes;
+use crate::bindings::Bindings;
+use crate::rules::Rules;
+use crate::scenes::Scenes;
+use crate::schedules::Schedules;
+use crate::settings::Settings;
+use crate::tasks::scheduler::ScheduledEffects;
 
 pub struct Context {
     pub effect_tx: Arc<Sender<EffectChange>>,
     pub ctrl_rx: Arc<Mutex<Receiver<ControllerChange>>>,
     pub controllers: Arc<Mutex<Vec<PsMoveController>>>,
+    pub settings: Arc<Mutex<Settings>>,
+    pub scheduled_effects: Arc<Mutex<ScheduledEffects>>,
+    pub schedules: Arc<Mutex<Schedules>>,
+    pub palettes: Arc<Mutex<Palettes>>,
+    pub scenes: Arc<Mutex<Scenes>>,
+    pub rules: Arc<Mutex<Rules>>,
+    pub bindings: Arc<Mutex<Bindings>>,
+    /// Who sent the request, recorded in the effect history
+    pub client: String,
 }
 
 impl juniper::Context for Context {}
@@ -24,6 +40,14 @@ impl Clone for Context {
             effect_tx: self.effect_tx.clone(),
             ctrl_rx: self.ctrl_rx.clone(),
             controllers: self.controllers.clone(),
+            settings: self.settings.clone(),
+            scheduled_effects: self.scheduled_effects.clone(),
+            schedules: self.schedules.clone(),
+            palettes: self.palettes.clone(),
+            scenes: self.scenes.clone(),
+            rules: self.rules.clone(),
+            bindings: self.bindings.clone(),
+            client: self.client.clone(),
         }
     }
 }
@@ -1,11 +1,23 @@
 use juniper::GraphQLInputObject;
 
+use crate::bindings::{AnalogSource, BindingParameter, ResponseCurve};
+use crate::graphql::schema_response::EffectCategory;
+use crate::ps_move::colors::ColorSpace;
+use crate::ps_move::models::BatteryLevel;
+use crate::rules::ButtonGesture;
+use crate::schedules::{SunEvent, Weekday};
+use crate::tasks::models::{Button, EffectPriority};
+
 #[derive(GraphQLInputObject, Debug)]
 pub(super) struct RevertEffectInput {
     #[graphql(
     description = "Applies the effect only on these controller addresses. (must not be empty)"
     )]
     pub controllers: Vec<String>,
+    #[graphql(description = "Time the led takes to blend into the last effect, in milliseconds, if specified.")]
+    pub transition_ms: Option<i32>,
+    #[graphql(description = "Color space the transition blends in. (Oklab by default)")]
+    pub transition_color_space: Option<ColorSpace>,
 }
 
 #[derive(GraphQLInputObject, Debug)]
@@ -14,6 +26,50 @@ pub(super) struct OffEffectInput {
     description = "Applies the effect only on these controller addresses. (must not be empty)"
     )]
     pub controllers: Vec<String>,
+    #[graphql(description = "Time the led takes to fade out, in milliseconds, if specified. (ignored for rumble)")]
+    pub transition_ms: Option<i32>,
+    #[graphql(description = "Color space the transition blends in. (Oklab by default)")]
+    pub transition_color_space: Option<ColorSpace>,
+}
+
+#[derive(GraphQLInputObject, Clone, Debug)]
+#[graphql(description = "A color, only one of the fields must be specified.")]
+pub(super) struct ColorInput {
+    pub hsv: Option<HsvColorInput>,
+    pub rgb: Option<RgbColorInput>,
+    #[graphql(description = "Hex RGB, like \"#ff8800\" or \"#f80\". (the # is optional)")]
+    pub hex: Option<String>,
+    #[graphql(description = "CSS color name, like \"orange\". (case insensitive)")]
+    pub name: Option<String>,
+    pub kelvin: Option<KelvinColorInput>,
+}
+
+#[derive(GraphQLInputObject, Clone, Debug)]
+pub(super) struct HsvColorInput {
+    #[graphql(description = "Hue (min 0, max 360)")]
+    pub hue: i32,
+    #[graphql(description = "Saturation (min 0.0, max 1.0)")]
+    pub saturation: f64,
+    #[graphql(description = "Value/brightness (min 0.0, max 1.0)")]
+    pub value: f64,
+}
+
+#[derive(GraphQLInputObject, Clone, Debug)]
+pub(super) struct RgbColorInput {
+    #[graphql(description = "Red (min 0, max 255)")]
+    pub red: i32,
+    #[graphql(description = "Green (min 0, max 255)")]
+    pub green: i32,
+    #[graphql(description = "Blue (min 0, max 255)")]
+    pub blue: i32,
+}
+
+#[derive(GraphQLInputObject, Clone, Debug)]
+pub(super) struct KelvinColorInput {
+    #[graphql(description = "Color temperature (min 1000, max 40000)")]
+    pub kelvin: i32,
+    #[graphql(description = "Brightness (min 0.0, max 1.0)")]
+    pub brightness: f64,
 }
 
 #[derive(GraphQLInputObject, Debug)]
@@ -26,12 +82,18 @@ pub(super) struct StaticLedEffectInput {
     pub duration: Option<i32>,
     #[graphql(description = "Name of the effect.")]
     pub name: Option<String>,
+    #[graphql(description = "Time the led takes to blend into this effect, in milliseconds, if specified.")]
+    pub transition_ms: Option<i32>,
+    #[graphql(description = "Color space the transition blends in. (Oklab by default)")]
+    pub transition_color_space: Option<ColorSpace>,
+    #[graphql(description = "Color, instead of `hue`, `saturation` and `value`.")]
+    pub color: Option<ColorInput>,
     #[graphql(description = "Hue/color (min 0, max 360)")]
-    pub hue: i32,
+    pub hue: Option<i32>,
     #[graphql(description = "Saturation (min 0.0, max 1.0)")]
-    pub saturation: f64,
+    pub saturation: Option<f64>,
     #[graphql(description = "Value/brightness (min 0.0, max 1.0)")]
-    pub value: f64,
+    pub value: Option<f64>,
 }
 
 #[derive(GraphQLInputObject, Debug)]
@@ -44,10 +106,16 @@ pub(super) struct BreathingLedEffectInput {
     pub duration: Option<i32>,
     #[graphql(description = "Name of the effect.")]
     pub name: Option<String>,
+    #[graphql(description = "Time the led takes to blend into this effect, in milliseconds, if specified.")]
+    pub transition_ms: Option<i32>,
+    #[graphql(description = "Color space the transition blends in. (Oklab by default)")]
+    pub transition_color_space: Option<ColorSpace>,
+    #[graphql(description = "Color, instead of `hue` and `saturation`. (its brightness isn't used)")]
+    pub color: Option<ColorInput>,
     #[graphql(description = "Hue/color (min 0, max 360)")]
-    pub hue: i32,
+    pub hue: Option<i32>,
     #[graphql(description = "Saturation (min 0.0, max 1.0)")]
-    pub saturation: f64,
+    pub saturation: Option<f64>,
     #[graphql(description = "Initial value/brightness. (min 0.0, max `peak`)")]
     pub initial_value: f64,
     #[graphql(
@@ -70,12 +138,20 @@ pub(super) struct RainbowLedEffectInput {
     pub duration: Option<i32>,
     #[graphql(description = "Name of the effect.")]
     pub name: Option<String>,
+    #[graphql(description = "Time the led takes to blend into this effect, in milliseconds, if specified.")]
+    pub transition_ms: Option<i32>,
+    #[graphql(description = "Color space the transition blends in. (Oklab by default)")]
+    pub transition_color_space: Option<ColorSpace>,
     #[graphql(description = "Saturation (min 0.0, max 1.0)")]
     pub saturation: f64,
     #[graphql(description = "Value/brightness (min 0.0, max 1.0)")]
     pub value: f64,
     #[graphql(description = "Time that the controller takes to reach go through the full color spectrum, in seconds")]
     pub time_to_complete: f64,
+    #[graphql(
+        description = "Name of a palette to cycle through (with `saturation` and `value`), instead of every hue."
+    )]
+    pub palette: Option<String>,
 }
 
 #[derive(GraphQLInputObject, Debug)]
@@ -88,12 +164,18 @@ pub(super) struct BlinkLedEffectInput {
     pub duration: Option<i32>,
     #[graphql(description = "Name of the effect.")]
     pub name: Option<String>,
+    #[graphql(description = "Time the led takes to blend into this effect, in milliseconds, if specified.")]
+    pub transition_ms: Option<i32>,
+    #[graphql(description = "Color space the transition blends in. (Oklab by default)")]
+    pub transition_color_space: Option<ColorSpace>,
+    #[graphql(description = "Color, instead of `hue`, `saturation` and `value`.")]
+    pub color: Option<ColorInput>,
     #[graphql(description = "Hue/color (min 0, max 360)")]
-    pub hue: i32,
+    pub hue: Option<i32>,
     #[graphql(description = "Saturation (min 0.0, max 1.0)")]
-    pub saturation: f64,
+    pub saturation: Option<f64>,
     #[graphql(description = "Value/brightness (min 0.0, max 1.0)")]
-    pub value: f64,
+    pub value: Option<f64>,
     #[graphql(description = "Interval between blinks, in ms.")]
     pub interval: i32,
 }
@@ -108,10 +190,20 @@ pub(super) struct CandleLedEffectInput {
     pub duration: Option<i32>,
     #[graphql(description = "Name of the effect.")]
     pub name: Option<String>,
+    #[graphql(description = "Time the led takes to blend into this effect, in milliseconds, if specified.")]
+    pub transition_ms: Option<i32>,
+    #[graphql(description = "Color space the transition blends in. (Oklab by default)")]
+    pub transition_color_space: Option<ColorSpace>,
+    #[graphql(description = "Color, instead of `hue` and `saturation`. (its brightness isn't used)")]
+    pub color: Option<ColorInput>,
     #[graphql(description = "Hue/color (min 0, max 360)")]
-    pub hue: i32,
+    pub hue: Option<i32>,
     #[graphql(description = "Saturation (min 0.0, max 1.0)")]
-    pub saturation: f64,
+    pub saturation: Option<f64>,
+    #[graphql(
+        description = "Name of a palette to pick a color from at random on each change, instead of `color` or `hue` and `saturation`."
+    )]
+    pub palette: Option<String>,
     #[graphql(description = "Min value (min 0.0, max 1.0)")]
     pub min_value: f64,
     #[graphql(description = "Max value (min 0.0, max 1.0)")]
@@ -132,14 +224,284 @@ pub(super) struct BounceLedEffectInput {
     pub duration: Option<i32>,
     #[graphql(description = "Name of the effect.")]
     pub name: Option<String>,
+    #[graphql(description = "Time the led takes to blend into this effect, in milliseconds, if specified.")]
+    pub transition_ms: Option<i32>,
+    #[graphql(description = "Color space the transition blends in. (Oklab by default)")]
+    pub transition_color_space: Option<ColorSpace>,
+    #[graphql(description = "Name of a palette to bounce, instead of `colors` or `hues`.")]
+    pub palette: Option<String>,
+    #[graphql(description = "Colors to bounce, instead of `hues`, `saturation` and `value`.")]
+    pub colors: Option<Vec<ColorInput>>,
     #[graphql(description = "Hue colors to bounce (each min 0, max 360)")]
-    pub hues: Vec<i32>,
+    pub hues: Option<Vec<i32>>,
     #[graphql(description = "To Saturation (min 0.0, max 1.0)")]
-    pub saturation: f64,
+    pub saturation: Option<f64>,
     #[graphql(description = "To value (min 0.0, max 1.0)")]
-    pub value: f64,
-    #[graphql(description = "Step ratio, percentage of change from one color to the other. (min 0.0=stay in from, max 1.0=stay in to)")]
-    pub step: f64
+    pub value: Option<f64>,
+    #[graphql(description = "Progress per update (every 10ms) from one color to the next. (min 0.0, max 1.0)")]
+    pub step: f64,
+    #[graphql(description = "Color space the colors are blended in. (Oklab by default)")]
+    pub color_space: Option<ColorSpace>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct FireLedEffectInput {
+    #[graphql(
+        description = "If specified, must not be empty, and applies the effect only on these controller addresses."
+    )]
+    pub controllers: Option<Vec<String>>,
+    #[graphql(description = "Duration of effect, in milliseconds, if specified.")]
+    pub duration: Option<i32>,
+    #[graphql(description = "Name of the effect.")]
+    pub name: Option<String>,
+    #[graphql(description = "Time the led takes to blend into this effect, in milliseconds, if specified.")]
+    pub transition_ms: Option<i32>,
+    #[graphql(description = "Color space the transition blends in. (Oklab by default)")]
+    pub transition_color_space: Option<ColorSpace>,
+    #[graphql(description = "Color of the embers, instead of `hue` and `saturation`. (its brightness isn't used)")]
+    pub color: Option<ColorInput>,
+    #[graphql(description = "Hue of the embers, shifting towards yellow as the fire flares (min 0, max 360)")]
+    pub hue: Option<i32>,
+    #[graphql(description = "Saturation (min 0.0, max 1.0)")]
+    pub saturation: Option<f64>,
+    #[graphql(description = "Min value (min 0.0, max 1.0)")]
+    pub min_value: f64,
+    #[graphql(description = "Max value (min 0.0, max 1.0)")]
+    pub max_value: f64,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct LightningLedEffectInput {
+    #[graphql(
+        description = "If specified, must not be empty, and applies the effect only on these controller addresses."
+    )]
+    pub controllers: Option<Vec<String>>,
+    #[graphql(description = "Duration of effect, in milliseconds, if specified.")]
+    pub duration: Option<i32>,
+    #[graphql(description = "Name of the effect.")]
+    pub name: Option<String>,
+    #[graphql(description = "Time the led takes to blend into this effect, in milliseconds, if specified.")]
+    pub transition_ms: Option<i32>,
+    #[graphql(description = "Color space the transition blends in. (Oklab by default)")]
+    pub transition_color_space: Option<ColorSpace>,
+    #[graphql(description = "Color, instead of `hue`, `saturation` and `value`.")]
+    pub color: Option<ColorInput>,
+    #[graphql(description = "Hue/color (min 0, max 360)")]
+    pub hue: Option<i32>,
+    #[graphql(description = "Saturation (min 0.0, max 1.0)")]
+    pub saturation: Option<f64>,
+    #[graphql(description = "Value/brightness (min 0.0, max 1.0)")]
+    pub value: Option<f64>,
+    #[graphql(description = "Min time between strikes, in milliseconds. (above 0)")]
+    pub min_interval: i32,
+    #[graphql(description = "Max time between strikes, in milliseconds.")]
+    pub max_interval: i32,
+    #[graphql(description = "Time the glow takes to fade out after the flashes, in milliseconds. (300ms is the default)")]
+    pub afterglow: Option<i32>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct HeartbeatLedEffectInput {
+    #[graphql(
+        description = "If specified, must not be empty, and applies the effect only on these controller addresses."
+    )]
+    pub controllers: Option<Vec<String>>,
+    #[graphql(description = "Duration of effect, in milliseconds, if specified.")]
+    pub duration: Option<i32>,
+    #[graphql(description = "Name of the effect.")]
+    pub name: Option<String>,
+    #[graphql(description = "Time the led takes to blend into this effect, in milliseconds, if specified.")]
+    pub transition_ms: Option<i32>,
+    #[graphql(description = "Color space the transition blends in. (Oklab by default)")]
+    pub transition_color_space: Option<ColorSpace>,
+    #[graphql(description = "Color, instead of `hue`, `saturation` and `value`.")]
+    pub color: Option<ColorInput>,
+    #[graphql(description = "Hue/color (min 0, max 360)")]
+    pub hue: Option<i32>,
+    #[graphql(description = "Saturation (min 0.0, max 1.0)")]
+    pub saturation: Option<f64>,
+    #[graphql(description = "Value/brightness (min 0.0, max 1.0)")]
+    pub value: Option<f64>,
+    #[graphql(description = "Value between beats (min 0.0, max 1.0, 0.0 is the default)")]
+    pub min_value: Option<f64>,
+    #[graphql(description = "Beats per minute. (min 1.0, max 300.0)")]
+    pub bpm: f64,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct StrobeLedEffectInput {
+    #[graphql(
+        description = "If specified, must not be empty, and applies the effect only on these controller addresses."
+    )]
+    pub controllers: Option<Vec<String>>,
+    #[graphql(description = "Duration of effect, in milliseconds, if specified.")]
+    pub duration: Option<i32>,
+    #[graphql(description = "Name of the effect.")]
+    pub name: Option<String>,
+    #[graphql(description = "Time the led takes to blend into this effect, in milliseconds, if specified.")]
+    pub transition_ms: Option<i32>,
+    #[graphql(description = "Color space the transition blends in. (Oklab by default)")]
+    pub transition_color_space: Option<ColorSpace>,
+    #[graphql(description = "Color, instead of `hue`, `saturation` and `value`.")]
+    pub color: Option<ColorInput>,
+    #[graphql(description = "Hue/color (min 0, max 360)")]
+    pub hue: Option<i32>,
+    #[graphql(description = "Saturation (min 0.0, max 1.0)")]
+    pub saturation: Option<f64>,
+    #[graphql(description = "Value/brightness (min 0.0, max 1.0)")]
+    pub value: Option<f64>,
+    #[graphql(description = "Flashes per second. (above 0.0, max 50.0)")]
+    pub frequency: f64,
+    #[graphql(description = "Fraction of each period the led is lit. (above 0.0, max 1.0, 0.1 is the default)")]
+    pub duty_cycle: Option<f64>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct PoliceLedEffectInput {
+    #[graphql(
+        description = "If specified, must not be empty, and applies the effect only on these controller addresses."
+    )]
+    pub controllers: Option<Vec<String>>,
+    #[graphql(description = "Duration of effect, in milliseconds, if specified.")]
+    pub duration: Option<i32>,
+    #[graphql(description = "Name of the effect.")]
+    pub name: Option<String>,
+    #[graphql(description = "Time the led takes to blend into this effect, in milliseconds, if specified.")]
+    pub transition_ms: Option<i32>,
+    #[graphql(description = "Color space the transition blends in. (Oklab by default)")]
+    pub transition_color_space: Option<ColorSpace>,
+    #[graphql(description = "First color. (red is the default)")]
+    pub first: Option<ColorInput>,
+    #[graphql(description = "Second color. (blue is the default)")]
+    pub second: Option<ColorInput>,
+    #[graphql(description = "Time each color lasts, in milliseconds. (above 0)")]
+    pub interval: i32,
+    #[graphql(description = "Flashes of each color during its interval. (min 1, max 10, 2 is the default)")]
+    pub flashes: Option<i32>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct NoiseLedEffectInput {
+    #[graphql(
+        description = "If specified, must not be empty, and applies the effect only on these controller addresses."
+    )]
+    pub controllers: Option<Vec<String>>,
+    #[graphql(description = "Duration of effect, in milliseconds, if specified.")]
+    pub duration: Option<i32>,
+    #[graphql(description = "Name of the effect.")]
+    pub name: Option<String>,
+    #[graphql(description = "Time the led takes to blend into this effect, in milliseconds, if specified.")]
+    pub transition_ms: Option<i32>,
+    #[graphql(description = "Color space the transition blends in. (Oklab by default)")]
+    pub transition_color_space: Option<ColorSpace>,
+    #[graphql(description = "Start of the hue range (min 0, max 360)")]
+    pub min_hue: i32,
+    #[graphql(
+        description = "End of the hue range, going up from `minHue` (wrapping around 360), equal to `minHue` for a fixed hue. (min 0, max 360)"
+    )]
+    pub max_hue: i32,
+    #[graphql(description = "Saturation (min 0.0, max 1.0)")]
+    pub saturation: f64,
+    #[graphql(description = "Min value (min 0.0, max 1.0)")]
+    pub min_value: f64,
+    #[graphql(description = "Max value (min 0.0, max 1.0), equal to `minValue` for a fixed value.")]
+    pub max_value: f64,
+    #[graphql(description = "How fast the color drifts, roughly in variations per second. (above 0.0, max 20.0)")]
+    pub speed: f64,
+    #[graphql(description = "Layers of finer detail. (min 1, max 8, 3 is the default)")]
+    pub octaves: Option<i32>,
+    #[graphql(description = "Makes the effect reproducible, random if not specified. (min 0)")]
+    pub seed: Option<i32>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct SparkleLedEffectInput {
+    #[graphql(
+        description = "If specified, must not be empty, and applies the effect only on these controller addresses."
+    )]
+    pub controllers: Option<Vec<String>>,
+    #[graphql(description = "Duration of effect, in milliseconds, if specified.")]
+    pub duration: Option<i32>,
+    #[graphql(description = "Name of the effect.")]
+    pub name: Option<String>,
+    #[graphql(description = "Time the led takes to blend into this effect, in milliseconds, if specified.")]
+    pub transition_ms: Option<i32>,
+    #[graphql(description = "Color space the transition blends in. (Oklab by default)")]
+    pub transition_color_space: Option<ColorSpace>,
+    #[graphql(description = "Base color, instead of `hue`, `saturation` and `value`.")]
+    pub color: Option<ColorInput>,
+    #[graphql(description = "Hue/color (min 0, max 360)")]
+    pub hue: Option<i32>,
+    #[graphql(description = "Saturation (min 0.0, max 1.0)")]
+    pub saturation: Option<f64>,
+    #[graphql(description = "Value/brightness (min 0.0, max 1.0)")]
+    pub value: Option<f64>,
+    #[graphql(description = "Brightness of the white sparkles. (min 0.0, max 1.0, 1.0 is the default)")]
+    pub sparkle_value: Option<f64>,
+    #[graphql(description = "Average sparkles per second. (above 0.0, max 100.0)")]
+    pub rate: f64,
+    #[graphql(description = "Time a sparkle takes to fade into the base color, in milliseconds. (100ms is the default)")]
+    pub sparkle_duration: Option<i32>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct WaveLedEffectInput {
+    #[graphql(
+        description = "If specified, must not be empty, the wave travels across these controller addresses in order. (otherwise across all, in connection order)"
+    )]
+    pub controllers: Option<Vec<String>>,
+    #[graphql(description = "Duration of effect, in milliseconds, if specified.")]
+    pub duration: Option<i32>,
+    #[graphql(description = "Name of the effect.")]
+    pub name: Option<String>,
+    #[graphql(description = "Time the led takes to blend into this effect, in milliseconds, if specified.")]
+    pub transition_ms: Option<i32>,
+    #[graphql(description = "Color space the transition blends in. (Oklab by default)")]
+    pub transition_color_space: Option<ColorSpace>,
+    #[graphql(description = "Color, instead of `hue` and `saturation`. (its brightness isn't used)")]
+    pub color: Option<ColorInput>,
+    #[graphql(description = "Hue (min 0, max 360)")]
+    pub hue: Option<i32>,
+    #[graphql(description = "Saturation (min 0.0, max 1.0)")]
+    pub saturation: Option<f64>,
+    #[graphql(description = "Lowest value (min 0.0, max 1.0)")]
+    pub min_value: f64,
+    #[graphql(description = "Highest value (min 0.0, max 1.0)")]
+    pub max_value: f64,
+    #[graphql(description = "Time of one full wave, in milliseconds. (above 0)")]
+    pub period: i32,
+    #[graphql(
+        description = "Fraction of the period spread across the controllers, 0.0 breathes in unison, 1.0 spreads them evenly over a whole period. (min 0.0, max 1.0)"
+    )]
+    pub spread: f64,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct ChaseLedEffectInput {
+    #[graphql(
+        description = "If specified, must not be empty, the lit led moves along these controller addresses in order. (otherwise along all, in connection order)"
+    )]
+    pub controllers: Option<Vec<String>>,
+    #[graphql(description = "Duration of effect, in milliseconds, if specified.")]
+    pub duration: Option<i32>,
+    #[graphql(description = "Name of the effect.")]
+    pub name: Option<String>,
+    #[graphql(description = "Time the led takes to blend into this effect, in milliseconds, if specified.")]
+    pub transition_ms: Option<i32>,
+    #[graphql(description = "Color space the transition blends in. (Oklab by default)")]
+    pub transition_color_space: Option<ColorSpace>,
+    #[graphql(description = "Color, instead of `hue`, `saturation` and `value`.")]
+    pub color: Option<ColorInput>,
+    #[graphql(description = "Hue (min 0, max 360)")]
+    pub hue: Option<i32>,
+    #[graphql(description = "Saturation (min 0.0, max 1.0)")]
+    pub saturation: Option<f64>,
+    #[graphql(description = "Value of the lit controller (min 0.0, max 1.0)")]
+    pub value: Option<f64>,
+    #[graphql(description = "Value of the other controllers, off by default. (min 0.0, max 1.0)")]
+    pub background_value: Option<f64>,
+    #[graphql(description = "Time each controller stays lit, in milliseconds. (above 0)")]
+    pub interval: i32,
 }
 
 #[derive(GraphQLInputObject, Debug)]
@@ -187,3 +549,646 @@ pub(super) struct BlinkRumbleEffectInput {
     #[graphql(description = "Interval between blinks, in ms.")]
     pub interval: i32,
 }
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct FollowLedRumbleEffectInput {
+    #[graphql(
+    description = "If specified, must not be empty, and applies the effect only on these controller addresses."
+    )]
+    pub controllers: Option<Vec<String>>,
+    #[graphql(description = "Duration of effect, in milliseconds, if specified.")]
+    pub duration: Option<i32>,
+    #[graphql(description = "Rumble strength while the led is off. (min 0.0, max 1.0, 0.0 is the default)")]
+    pub min_strength: Option<f64>,
+    #[graphql(description = "Rumble strength at the led's full brightness. (min 0.0, max 1.0)")]
+    pub max_strength: f64,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct CombinedEffectInput {
+    #[graphql(
+    description = "If specified, must not be empty, and applies the effect only on these controller addresses."
+    )]
+    pub controllers: Option<Vec<String>>,
+    #[graphql(description = "Duration of both led and rumble, in milliseconds, if specified.")]
+    pub duration: Option<i32>,
+    #[graphql(description = "Name of the effect.")]
+    pub name: Option<String>,
+    #[graphql(description = "Time the led takes to blend into this effect, in milliseconds, if specified.")]
+    pub transition_ms: Option<i32>,
+    #[graphql(description = "Color space the transition blends in. (Oklab by default)")]
+    pub transition_color_space: Option<ColorSpace>,
+    pub led: LedEffectInput,
+    #[graphql(description = "Use `followLed` for a rumble in step with the led, e.g. pulsing on each blink.")]
+    pub rumble: RumbleEffectInput,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+#[graphql(
+description = "A led effect, only one of the fields must be specified. (the effects must not have `controllers`, `duration` nor `transitionMs`)"
+)]
+pub(super) struct LedEffectInput {
+    #[graphql(description = "Turns the led off, if true.")]
+    pub off: Option<bool>,
+    #[graphql(name = "static")]
+    pub static_: Option<StaticLedEffectInput>,
+    pub breathing: Option<BreathingLedEffectInput>,
+    pub rainbow: Option<RainbowLedEffectInput>,
+    pub blink: Option<BlinkLedEffectInput>,
+    pub candle: Option<CandleLedEffectInput>,
+    pub bounce: Option<BounceLedEffectInput>,
+    pub fire: Option<FireLedEffectInput>,
+    pub lightning: Option<LightningLedEffectInput>,
+    pub heartbeat: Option<HeartbeatLedEffectInput>,
+    pub strobe: Option<StrobeLedEffectInput>,
+    pub police: Option<PoliceLedEffectInput>,
+    pub sparkle: Option<SparkleLedEffectInput>,
+    pub noise: Option<NoiseLedEffectInput>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+#[graphql(
+description = "A rumble effect, only one of the fields must be specified. (the effects must not have `controllers` nor `duration`)"
+)]
+pub(super) struct RumbleEffectInput {
+    #[graphql(description = "Turns the rumble off, if true.")]
+    pub off: Option<bool>,
+    #[graphql(name = "static")]
+    pub static_: Option<StaticRumbleEffectInput>,
+    pub breathing: Option<BreathingRumbleEffectInput>,
+    pub blink: Option<BlinkRumbleEffectInput>,
+    pub follow_led: Option<FollowLedRumbleEffectInput>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct SequenceItemInput {
+    #[graphql(description = "Duration of the item, in milliseconds.")]
+    pub duration: i32,
+    #[graphql(description = "Led effect of the item, the led goes off if not specified.")]
+    pub led: Option<LedEffectInput>,
+    #[graphql(description = "Rumble effect of the item, the rumble goes off if not specified.")]
+    pub rumble: Option<RumbleEffectInput>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct SequenceInput {
+    #[graphql(
+    description = "If specified, must not be empty, and applies the sequence only on these controller addresses."
+    )]
+    pub controllers: Option<Vec<String>>,
+    #[graphql(description = "Name of the sequence.")]
+    pub name: Option<String>,
+    #[graphql(description = "Items played one after another. (must not be empty)")]
+    pub items: Vec<SequenceItemInput>,
+    #[graphql(description = "How many times the sequence plays, loops forever if not specified. (min 1)")]
+    pub loops: Option<i32>,
+    #[graphql(description = "Time the led takes to blend from one item into the next, in milliseconds.")]
+    pub crossfade: Option<i32>,
+    #[graphql(description = "Color space the crossfade blends in. (Oklab by default)")]
+    pub crossfade_color_space: Option<ColorSpace>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct MorseInput {
+    #[graphql(
+    description = "If specified, must not be empty, and applies the message only on these controller addresses."
+    )]
+    pub controllers: Option<Vec<String>>,
+    #[graphql(description = "Letters, digits, spaces and common punctuation. (must not be empty)")]
+    pub text: String,
+    #[graphql(description = "Length of a dot, in milliseconds. (min 50, max 2000, 150 is the default)")]
+    pub unit: Option<i32>,
+    #[graphql(description = "Led color of the signals, the led doesn't signal if not specified.")]
+    pub color: Option<ColorInput>,
+    #[graphql(description = "Led color between signals, off if not specified.")]
+    pub background: Option<ColorInput>,
+    #[graphql(description = "Rumble strength of the signals, the rumble doesn't signal if not specified. (min 0.0, max 1.0)")]
+    pub rumble: Option<f64>,
+    #[graphql(description = "How many times the message plays, once if not specified, 0 repeats it forever. (min 0)")]
+    pub repeat: Option<i32>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct SequenceControlInput {
+    #[graphql(
+    description = "Applies only on these controller addresses. (must not be empty)"
+    )]
+    pub controllers: Vec<String>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct ReleasePriorityInput {
+    #[graphql(
+    description = "If specified, must not be empty, and applies only on these controller addresses."
+    )]
+    pub controllers: Option<Vec<String>>,
+    pub priority: EffectPriority,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+#[graphql(
+description = "Parameters to change on the running led effects, at least one must be specified. (the ones an effect doesn't have are ignored)"
+)]
+pub(super) struct LedEffectPatchInput {
+    #[graphql(
+    description = "If specified, must not be empty, and applies only on these controller addresses."
+    )]
+    pub controllers: Option<Vec<String>>,
+    #[graphql(description = "Hue/color, the start of the range for noise. (min 0, max 360)")]
+    pub hue: Option<i32>,
+    #[graphql(description = "Saturation (min 0.0, max 1.0)")]
+    pub saturation: Option<f64>,
+    #[graphql(description = "Highest value/brightness, the only one for single level effects. (min 0.0, max 1.0)")]
+    pub peak: Option<f64>,
+    #[graphql(
+    description = "Length of a cycle, in milliseconds: interval, period, beat, time to peak (breathing) or to complete (rainbow, bounce). (min 1)"
+    )]
+    pub interval: Option<i32>,
+    #[graphql(description = "Noise units travelled per second, for noise. (above 0.0, max 20.0)")]
+    pub speed: Option<f64>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct TimelineControlInput {
+    #[graphql(
+    description = "If specified, must not be empty, and applies only on these controller addresses."
+    )]
+    pub controllers: Option<Vec<String>>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct EffectSpeedInput {
+    #[graphql(
+    description = "If specified, must not be empty, and applies only on these controller addresses."
+    )]
+    pub controllers: Option<Vec<String>>,
+    #[graphql(description = "How fast the effects run, 1.0 being real time. (min 0.1, max 10.0)")]
+    pub speed: f64,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct UndoInput {
+    #[graphql(
+    description = "If specified, must not be empty, and applies only on these controller addresses."
+    )]
+    pub controllers: Option<Vec<String>>,
+    #[graphql(description = "How many effects to go back. (default 1, min 1)")]
+    pub steps: Option<i32>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct JumpToHistoryInput {
+    pub controller: String,
+    #[graphql(description = "Id of an entry in the controller's effect history.")]
+    pub id: i32,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct ColorOutputInput {
+    #[graphql(description = "Gamma applied to every channel, 1.0 means no correction. (min 0.1, max 5.0)")]
+    pub gamma: Option<f64>,
+    #[graphql(description = "Max brightness of any channel, for every controller. (min 0.0, max 1.0)")]
+    pub brightness_cap: Option<f64>,
+    #[graphql(description = "Alternates between the closest brightness levels, for smoother low brightness.")]
+    pub dithering: Option<bool>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct ChannelCalibrationInput {
+    #[graphql(description = "Multiplier of the channel. (min 0.0, max 2.0)")]
+    pub gain: f64,
+    #[graphql(description = "Added to the channel when it's lit. (min -1.0, max 1.0)")]
+    pub offset: f64,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct ColorCalibrationInput {
+    #[graphql(
+    description = "Calibrates these controller addresses, even if not connected. (must not be empty)"
+    )]
+    pub controllers: Vec<String>,
+    #[graphql(description = "Red channel calibration, stays the same if not specified.")]
+    pub red: Option<ChannelCalibrationInput>,
+    #[graphql(description = "Green channel calibration, stays the same if not specified.")]
+    pub green: Option<ChannelCalibrationInput>,
+    #[graphql(description = "Blue channel calibration, stays the same if not specified.")]
+    pub blue: Option<ChannelCalibrationInput>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct ResetColorCalibrationInput {
+    #[graphql(
+    description = "Resets the calibration of these controller addresses. (must not be empty)"
+    )]
+    pub controllers: Vec<String>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct LedPwmFrequencyInput {
+    #[graphql(
+    description = "Applies on these controller addresses, even if not connected. (must not be empty)"
+    )]
+    pub controllers: Vec<String>,
+    #[graphql(
+    description = "Frequency in Hz, persisted and reapplied on reconnect. (min 733, max 9446) If not specified, it's no longer reapplied."
+    )]
+    pub frequency: Option<i32>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+#[graphql(
+description = "When a scheduled effect starts, only one of the fields must be specified. (the effect's duration counts from its start)"
+)]
+pub(super) struct StartAtInput {
+    #[graphql(
+        description = "Wall-clock time, in milliseconds since the Unix epoch. (up to a minute in the past, the effects then begin where they would be by now)"
+    )]
+    pub time: Option<f64>,
+    #[graphql(description = "Delay from now, in milliseconds.")]
+    pub delay: Option<i32>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct CancelScheduledEffectInput {
+    pub id: i32,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+#[graphql(
+description = "Sets a static color at a time of the day, either `hour` and `minute`, or `sunEvent`."
+)]
+pub(super) struct ScheduleInput {
+    #[graphql(description = "Unique name, replacing the schedule with the same name.")]
+    pub name: String,
+    #[graphql(description = "True by default.")]
+    pub enabled: Option<bool>,
+    #[graphql(description = "Local hour (min 0, max 23)")]
+    pub hour: Option<i32>,
+    #[graphql(description = "Local minute (min 0, max 59)")]
+    pub minute: Option<i32>,
+    #[graphql(description = "Fires relative to sunrise/sunset instead, needs a location.")]
+    pub sun_event: Option<SunEvent>,
+    #[graphql(description = "Minutes after the sun event, negative for before. (0 by default)")]
+    pub offset_minutes: Option<i32>,
+    #[graphql(description = "Days it fires on, every day if not specified.")]
+    pub days: Option<Vec<Weekday>>,
+    #[graphql(
+        description = "If specified, must not be empty, and applies the effect only on these controller addresses."
+    )]
+    pub controllers: Option<Vec<String>>,
+    #[graphql(description = "Color, instead of `hue`, `saturation` and `value`.")]
+    pub color: Option<ColorInput>,
+    #[graphql(description = "Hue (min 0, max 360)")]
+    pub hue: Option<i32>,
+    #[graphql(description = "Saturation (min 0.0, max 1.0)")]
+    pub saturation: Option<f64>,
+    #[graphql(description = "Value, the led goes off at 0.0 (min 0.0, max 1.0)")]
+    pub value: Option<f64>,
+    #[graphql(description = "Time the led takes to blend into the color, in milliseconds, if specified. (e.g. a sunrise ramp)")]
+    pub transition_ms: Option<i32>,
+    #[graphql(description = "Duration of effect, in milliseconds, if specified.")]
+    pub duration: Option<i32>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct DeleteScheduleInput {
+    pub name: String,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct LocationInput {
+    #[graphql(description = "Degrees, north is positive. (min -90.0, max 90.0)")]
+    pub latitude: f64,
+    #[graphql(description = "Degrees, east is positive. (min -180.0, max 180.0)")]
+    pub longitude: f64,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+#[graphql(
+description = "Replaces the circadian profile, which shifts color temperature and brightness by time of day. (overrides other led effects while enabled)"
+)]
+pub(super) struct CircadianInput {
+    pub enabled: bool,
+    #[graphql(
+        description = "If specified, must not be empty, and applies only on these controller addresses."
+    )]
+    pub controllers: Option<Vec<String>>,
+    #[graphql(description = "Color temperature during the day. (min 1000, max 40000, 5500 by default)")]
+    pub day_kelvin: Option<i32>,
+    #[graphql(description = "Color temperature during the night. (min 1000, max 40000, 2200 by default)")]
+    pub night_kelvin: Option<i32>,
+    #[graphql(description = "Brightness during the day. (min 0.0, max 1.0, 0.8 by default)")]
+    pub day_brightness: Option<f64>,
+    #[graphql(description = "Brightness during the night. (min 0.0, max 1.0, 0.1 by default)")]
+    pub night_brightness: Option<f64>,
+    #[graphql(description = "Minutes the shift takes, centered on sunrise/sunset. (min 1, max 720, 60 by default)")]
+    pub twilight_minutes: Option<i32>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct PaletteInput {
+    #[graphql(description = "Unique name, replacing the user-defined palette with the same name.")]
+    pub name: String,
+    pub colors: Vec<ColorInput>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct DeletePaletteInput {
+    pub name: String,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct ThemeInput {
+    #[graphql(description = "Unique name, replacing the theme with the same name.")]
+    pub name: String,
+    #[graphql(description = "Name of the palette.")]
+    pub palette: String,
+    #[graphql(description = "Multiplies the brightness of every controller. (min 0.0, max 1.0)")]
+    pub brightness: f64,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct DeleteThemeInput {
+    pub name: String,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct ActivateThemeInput {
+    pub name: String,
+    #[graphql(description = "Time the led takes to blend into the new colors, in milliseconds, if specified.")]
+    pub transition_ms: Option<i32>,
+    #[graphql(description = "Color space the transition blends in. (Oklab by default)")]
+    pub transition_color_space: Option<ColorSpace>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct SceneControllerInput {
+    pub address: String,
+    #[graphql(description = "Led effect, it must not have `controllers` nor `duration`.")]
+    pub led: LedEffectInput,
+    #[graphql(description = "Rumble effect, it must not have `controllers` nor `duration`. (off if not specified)")]
+    pub rumble: Option<RumbleEffectInput>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct SceneInput {
+    #[graphql(description = "Unique name, replacing the scene with the same name.")]
+    pub name: String,
+    #[graphql(description = "Effects of each controller. (must not be empty, one per address)")]
+    pub controllers: Vec<SceneControllerInput>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct SaveSceneInput {
+    #[graphql(description = "Unique name, replacing the scene with the same name.")]
+    pub name: String,
+    #[graphql(
+    description = "If specified, must not be empty, and saves only these controller addresses. (all connected ones otherwise)"
+    )]
+    pub controllers: Option<Vec<String>>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct ApplySceneInput {
+    pub name: String,
+    #[graphql(
+    description = "If specified, must not be empty, and applies the scene only on these controller addresses."
+    )]
+    pub controllers: Option<Vec<String>>,
+    #[graphql(description = "Time the led takes to blend into the scene, in milliseconds, if specified.")]
+    pub transition_ms: Option<i32>,
+    #[graphql(description = "Color space the transition blends in. (Oklab by default)")]
+    pub transition_color_space: Option<ColorSpace>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct DeleteSceneInput {
+    pub name: String,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+#[graphql(description = "Event a rule reacts to, only one of the fields must be specified.")]
+pub(super) struct RuleEventInput {
+    pub button: Option<ButtonRuleEventInput>,
+    #[graphql(description = "The trigger is pulled past this threshold. (min 0.0, max 1.0)")]
+    pub trigger_threshold: Option<f64>,
+    #[graphql(description = "The controller is moved harder than this threshold, in g. (min 0.1, max 8.0)")]
+    pub motion_threshold: Option<f64>,
+    #[graphql(description = "The controller connects, if true.")]
+    pub connect: Option<bool>,
+    #[graphql(description = "The controller disconnects, if true.")]
+    pub disconnect: Option<bool>,
+    pub battery: Option<BatteryRuleEventInput>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct ButtonRuleEventInput {
+    pub button: Button,
+    pub gesture: ButtonGesture,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct BatteryRuleEventInput {
+    #[graphql(description = "Any change of the level if not specified.")]
+    pub level: Option<BatteryLevel>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+#[graphql(description = "Action of a rule, only one of the fields must be specified.")]
+pub(super) struct RuleActionInput {
+    pub led: Option<LedRuleActionInput>,
+    pub rumble: Option<RumbleRuleActionInput>,
+    pub scene: Option<SceneRuleActionInput>,
+    pub toggle_scene: Option<ToggleSceneRuleActionInput>,
+    pub webhook: Option<WebhookRuleActionInput>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct LedRuleActionInput {
+    #[graphql(
+    description = "If specified, must not be empty, and applies the effect on these controller addresses. (the one of the event otherwise)"
+    )]
+    pub controllers: Option<Vec<String>>,
+    pub effect: LedEffectInput,
+    #[graphql(description = "Duration of effect, in milliseconds, if specified.")]
+    pub duration: Option<i32>,
+    #[graphql(description = "Time the led takes to blend into the effect, in milliseconds, if specified.")]
+    pub transition_ms: Option<i32>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct RumbleRuleActionInput {
+    #[graphql(
+    description = "If specified, must not be empty, and applies the effect on these controller addresses. (the one of the event otherwise)"
+    )]
+    pub controllers: Option<Vec<String>>,
+    pub effect: RumbleEffectInput,
+    #[graphql(description = "Duration of effect, in milliseconds, if specified.")]
+    pub duration: Option<i32>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct SceneRuleActionInput {
+    pub name: String,
+    #[graphql(description = "Time the led takes to blend into the scene, in milliseconds, if specified.")]
+    pub transition_ms: Option<i32>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+#[graphql(
+description = "Applies the scene, or if one of its controllers shows it already, `otherwise` (turns its controllers off if not specified)."
+)]
+pub(super) struct ToggleSceneRuleActionInput {
+    pub name: String,
+    pub otherwise: Option<String>,
+    #[graphql(description = "Time the led takes to blend into the scene, in milliseconds, if specified.")]
+    pub transition_ms: Option<i32>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+#[graphql(description = "Posts the rule name, controller address and event as JSON.")]
+pub(super) struct WebhookRuleActionInput {
+    #[graphql(description = "HTTP or HTTPS URL.")]
+    pub url: String,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+#[graphql(description = "Runs actions when an event happens on a controller, if the conditions are met.")]
+pub(super) struct RuleInput {
+    #[graphql(description = "Unique name, replacing the rule with the same name.")]
+    pub name: String,
+    #[graphql(description = "True by default.")]
+    pub enabled: Option<bool>,
+    pub event: RuleEventInput,
+    #[graphql(
+    description = "If specified, must not be empty, and the rule only reacts to events of these controller addresses."
+    )]
+    pub controllers: Option<Vec<String>>,
+    #[graphql(
+    description = "Local time the rule becomes active, with `toHour` and `toMinute`. (hour min 0, max 23, minute min 0, max 59)"
+    )]
+    pub from_hour: Option<i32>,
+    pub from_minute: Option<i32>,
+    #[graphql(description = "Local time the rule stops being active, going past midnight if before the start.")]
+    pub to_hour: Option<i32>,
+    pub to_minute: Option<i32>,
+    #[graphql(description = "Days the rule is active, every day if not specified.")]
+    pub days: Option<Vec<Weekday>>,
+    #[graphql(description = "Only reacts while the controller of the event shows this scene, if specified.")]
+    pub scene: Option<String>,
+    #[graphql(description = "Priority of the effects the actions apply. (Normal by default)")]
+    pub priority: Option<EffectPriority>,
+    #[graphql(description = "Run in order. (must not be empty)")]
+    pub actions: Vec<RuleActionInput>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct EnableRuleInput {
+    pub name: String,
+    pub enabled: bool,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct DeleteRuleInput {
+    pub name: String,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+#[graphql(description = "Drives a parameter of the effects from an analog input, without going through the API.")]
+pub(super) struct BindingInput {
+    #[graphql(description = "Unique name, replacing the binding with the same name.")]
+    pub name: String,
+    #[graphql(description = "True by default.")]
+    pub enabled: Option<bool>,
+    pub source: AnalogSource,
+    #[graphql(description = "Address of the controller the source is read from, each controller drives its own effects if not specified.")]
+    pub controller: Option<String>,
+    #[graphql(
+    description = "If specified, must not be empty, and drives the effects of these controller addresses. (needs `controller`)"
+    )]
+    pub targets: Option<Vec<String>>,
+    pub parameter: BindingParameter,
+    #[graphql(description = "Linear by default.")]
+    pub curve: Option<ResponseCurve>,
+    #[graphql(description = "Reading of the source mapping to `outputMin`, the usual lowest by default.")]
+    pub input_min: Option<f64>,
+    #[graphql(description = "Reading of the source mapping to `outputMax`, the usual highest by default.")]
+    pub input_max: Option<f64>,
+    #[graphql(description = "Lowest value of the parameter by default, can be higher than `outputMax` to invert.")]
+    pub output_min: Option<f64>,
+    #[graphql(description = "Highest value of the parameter by default.")]
+    pub output_max: Option<f64>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+pub(super) struct DeleteBindingInput {
+    pub name: String,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+#[graphql(description = "Led effect the server shows by itself, on startup or when a controller connects.")]
+pub(super) struct LifecycleEffectInput {
+    #[graphql(description = "Disables the effect if not specified.")]
+    pub led: Option<LedEffectInput>,
+    #[graphql(description = "Duration of effect, in milliseconds, never expires if not specified.")]
+    pub duration: Option<i32>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+#[graphql(description = "Led effect of specific controllers when they connect, like each player's own color.")]
+pub(super) struct ControllerConnectEffectInput {
+    #[graphql(description = "Controller addresses. (must not be empty)")]
+    pub controllers: Vec<String>,
+    #[graphql(description = "Goes back to the shared on-connect effect if not specified.")]
+    pub led: Option<LedEffectInput>,
+    #[graphql(description = "Duration of effect, in milliseconds, never expires if not specified.")]
+    pub duration: Option<i32>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+#[graphql(description = "Effects to render without any controller, like they would run on one.")]
+pub(super) struct PreviewEffectInput {
+    #[graphql(description = "The led is off if not specified.")]
+    pub led: Option<LedEffectInput>,
+    #[graphql(description = "The rumble is off if not specified.")]
+    pub rumble: Option<RumbleEffectInput>,
+    #[graphql(description = "Time rendered, in milliseconds. (max 60000)")]
+    pub duration: i32,
+    #[graphql(description = "Time between frames, in milliseconds. (min 10, 50 by default)")]
+    pub interval: Option<i32>,
+    #[graphql(description = "Seeds the random effects, the same input always renders the same frames. (0 by default)")]
+    pub seed: Option<i32>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+#[graphql(description = "Any effect of the catalog (`effectCatalog`), with its parameters.")]
+pub(super) struct ApplyEffectInput {
+    #[graphql(
+        description = "If specified, must not be empty, and applies the effect only on these controller addresses."
+    )]
+    pub controllers: Option<Vec<String>>,
+    #[graphql(description = "Duration of effect, in milliseconds, if specified.")]
+    pub duration: Option<i32>,
+    #[graphql(description = "Name of the effect.")]
+    pub name: Option<String>,
+    #[graphql(description = "Time the led takes to blend into this effect, in milliseconds, if specified. (led only)")]
+    pub transition_ms: Option<i32>,
+    #[graphql(description = "Color space the transition blends in. (Oklab by default)")]
+    pub transition_color_space: Option<ColorSpace>,
+    pub category: EffectCategory,
+    #[graphql(description = "Kind of the effect, like \"fire\".")]
+    pub kind: String,
+    #[graphql(description = "Values of the parameters, the others take their default.")]
+    pub parameters: Option<Vec<EffectParameterInput>>,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+#[graphql(description = "Value of a parameter, only the field matching its type must be specified.")]
+pub(super) struct EffectParameterInput {
+    pub name: String,
+    pub number: Option<f64>,
+    pub numbers: Option<Vec<f64>>,
+    pub color: Option<ColorInput>,
+    pub colors: Option<Vec<ColorInput>>,
+    pub text: Option<String>,
+}
@@ -1,55 +1,105 @@
-use std::time::Duration;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use juniper::{FieldError, FieldResult, Value};
+use palette::{FromColor, Hsv, Srgb};
 use tokio::time::Instant;
 
 use crate::{EffectChange, EffectChangeType, EffectTarget, LedEffectKind};
+use crate::graphql::effect_catalog::{self, EffectParameters};
 use crate::graphql::schema::Context;
 use crate::graphql::schema_input::*;
-use crate::graphql::schema_response::MutationResponse;
+use crate::graphql::schema_response::{EffectCategory, MutationResponse};
+use crate::tasks::models::EffectPriority;
+use crate::bindings::Binding;
+use crate::palettes::{Palette, PaletteColor, Palettes, Theme};
+use crate::rules::{Rule, RuleAction, RuleEvent, TimeWindow};
+use crate::scenes::{Scene, SceneController};
 use crate::ps_move::api::build_hsv;
-use crate::ps_move::effects::{LedEffect, RumbleEffect, RumbleEffectKind};
-use crate::tasks::models::EffectChangeType::RevertLed;
+use crate::ps_move::colors::{kelvin_to_hsv, ColorSpace, MAX_KELVIN, MIN_KELVIN};
+use crate::ps_move::controller::{MAX_EFFECT_SPEED, MAX_LED_PWM_FREQUENCY, MIN_EFFECT_SPEED, MIN_LED_PWM_FREQUENCY};
+use crate::ps_move::effects::{LedEffect, LedEffectPatch, RumbleEffect, RumbleEffectKind, Transition};
+use crate::ps_move::morse;
+use crate::ps_move::morse::MorseSignal;
+use crate::ps_move::output::{ChannelCalibration, OutputSettings};
+use crate::ps_move::sequence::{EffectSequence, SequenceItem};
+use crate::ps_move::snapshot::{LedEffectSnapshot, RumbleEffectSnapshot};
+use crate::schedules::{CircadianProfile, Location, Schedule, ScheduleTime};
+use crate::settings::LifecycleEffect;
+
+/// How late a start can be, the effects then begin where they would be by now
+const MAX_PAST_START: Duration = Duration::from_secs(60);
 
 pub struct MutationRoot;
 
 #[juniper::graphql_object(Context = Context)]
 impl MutationRoot {
     #[graphql(description = "Revert the led to the last effect. (if it's expired, it goes off)")]
-    fn revert_led(
+    async fn revert_led(
         ctx: &Context,
         input: Option<RevertEffectInput>,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
     ) -> FieldResult<MutationResponse> {
         tracing::info!("Received led to last effect");
         tracing::debug!("Effect input: {input:?}");
 
-        let controllers = input.map(|input| input.controllers);
-
-        let target = match controller_to_effect_target(controllers) {
-            Ok(value) => value,
-            Err(value) => return value,
+        let (controllers, transition) = match input {
+            Some(input) => (
+                Some(input.controllers),
+                build_transition(input.transition_ms, input.transition_color_space)?,
+            ),
+            None => (None, None),
         };
 
-        match ctx.effect_tx.send(EffectChange {
-            effect: RevertLed,
-            target,
-        }) {
-            Ok(_) => Ok(MutationResponse::Success),
-            Err(_) => Ok(MutationResponse::ServerError),
-        }
+        process_effect_mutation(
+            ctx,
+            EffectChangeType::RevertLed { transition },
+            controllers,
+            start_at,
+            priority,
+        )
+        .await
     }
 
     #[graphql(description = "Turn the led off.")]
-    fn set_led_off(ctx: &Context, input: Option<OffEffectInput>) -> FieldResult<MutationResponse> {
+    async fn set_led_off(
+        ctx: &Context,
+        input: Option<OffEffectInput>,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
+    ) -> FieldResult<MutationResponse> {
         tracing::info!("Received led off effect");
         tracing::debug!("Effect input: {input:?}");
 
-        let controllers = input.map(|input| input.controllers);
-        process_led_effect_mutation(ctx, LedEffect::off(), controllers)
+        let (controllers, transition) = match input {
+            Some(input) => (
+                Some(input.controllers),
+                build_transition(input.transition_ms, input.transition_color_space)?,
+            ),
+            None => (None, None),
+        };
+
+        process_led_effect_mutation(
+            ctx,
+            LedEffect::off(),
+            transition,
+            controllers,
+            start_at,
+            priority,
+        )
+        .await
     }
 
     #[graphql(description = "Set a constant color.")]
-    fn set_led_static(ctx: &Context, input: StaticLedEffectInput) -> FieldResult<MutationResponse> {
+    async fn set_led_static(
+        ctx: &Context,
+        input: StaticLedEffectInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
+    ) -> FieldResult<MutationResponse> {
         tracing::info!(
             "Received led static effect ({})",
             input
@@ -59,52 +109,36 @@ impl MutationRoot {
         );
         tracing::debug!("Effect input: {input:?}");
 
-        if input.name.is_some_and(|name| name.is_empty()) {
+        if input.name.as_ref().is_some_and(|name| name.is_empty()) {
             return Err(FieldError::new("Name can't be empty!", Value::Null));
         }
 
-        if input.hue < 0 || input.hue > 360 {
-            return Err(FieldError::new(
-                "Hue must be between 0 and 360!",
-                Value::Null,
-            ));
-        }
-
-        if input.saturation < 0.0 || input.saturation > 1.0 {
-            return Err(FieldError::new(
-                "Saturation must be between 0.0 and 1.0!",
-                Value::Null,
-            ));
-        }
-
-        if input.value < 0.0 || input.value > 1.0 {
-            return Err(FieldError::new(
-                "Value must be between 0.0 and 1.0!",
-                Value::Null,
-            ));
-        }
-
         if input.duration.filter(|duration| *duration < 0).is_some() {
             return Err(FieldError::new("Duration must be positive!", Value::Null));
         }
 
-        let effect = LedEffectKind::Static {
-            hsv: build_hsv(input.hue as f64, input.saturation, input.value),
-        };
+        let effect = build_static_led_effect(&input)?;
+        let transition = build_transition(input.transition_ms, input.transition_color_space)?;
 
         process_led_effect_mutation(
             ctx,
             LedEffect::from(effect, input.duration),
+            transition,
             input.controllers,
+            start_at,
+            priority,
         )
+        .await
     }
 
     #[graphql(
         description = "Increase brightness of a color over time, reaching a peak, then reverting."
     )]
-    fn set_led_breathing(
+    async fn set_led_breathing(
         ctx: &Context,
         input: BreathingLedEffectInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
     ) -> FieldResult<MutationResponse> {
         tracing::info!(
             "Received led breathing effect ({})",
@@ -115,70 +149,34 @@ impl MutationRoot {
         );
         tracing::debug!("Effect input: {input:?}");
 
-        if input.name.is_some_and(|name| name.is_empty()) {
+        if input.name.as_ref().is_some_and(|name| name.is_empty()) {
             return Err(FieldError::new("Name can't be empty!", Value::Null));
         }
 
-        if input.time_to_peak < 0 {
-            return Err(FieldError::new("Step must be positive!", Value::Null));
-        }
-
-        if input.initial_value > input.peak {
-            return Err(FieldError::new(
-                "Initial value can't be higher than peak!",
-                Value::Null,
-            ));
-        }
-
-        if input.hue < 0 || input.hue > 360 {
-            return Err(FieldError::new(
-                "Hue must be between 0.0 and 360.0!",
-                Value::Null,
-            ));
-        }
-
-        if input.saturation < 0.0 || input.saturation > 1.0 {
-            return Err(FieldError::new(
-                "Saturation must be between 0.0 and 1.0!",
-                Value::Null,
-            ));
-        }
-
-        if input.initial_value < 0.0 || input.initial_value > 1.0 {
-            return Err(FieldError::new(
-                "Initial value must be between 0.0 and 1.0!",
-                Value::Null,
-            ));
-        }
-
         if input.duration.filter(|duration| *duration < 0).is_some() {
             return Err(FieldError::new("Duration must be positive!", Value::Null));
         }
 
-        if input.peak < 0.0 || input.peak > 1.0 {
-            return Err(FieldError::new(
-                "Peak must be between 0.0 and 1.0!",
-                Value::Null,
-            ));
-        }
-
-        let effect = LedEffectKind::new_timed_breathing(
-            build_hsv(input.hue as f64, input.saturation, input.initial_value),
-            Duration::from_millis(input.time_to_peak as u64),
-            input.peak as f32,
-        );
+        let effect = build_breathing_led_effect(&input)?;
+        let transition = build_transition(input.transition_ms, input.transition_color_space)?;
 
         process_led_effect_mutation(
             ctx,
             LedEffect::from(effect, input.duration),
+            transition,
             input.controllers,
+            start_at,
+            priority,
         )
+        .await
     }
 
     #[graphql(description = "Cycle through colors.")]
-    fn set_led_rainbow(
+    async fn set_led_rainbow(
         ctx: &Context,
         input: RainbowLedEffectInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
     ) -> FieldResult<MutationResponse> {
         tracing::info!(
             "Received led rainbow effect ({})",
@@ -189,47 +187,35 @@ impl MutationRoot {
         );
         tracing::debug!("Effect input: {input:?}");
 
-        if input.name.is_some_and(|name| name.is_empty()) {
+        if input.name.as_ref().is_some_and(|name| name.is_empty()) {
             return Err(FieldError::new("Name can't be empty!", Value::Null));
         }
 
-        if input.time_to_complete < 0.0 {
-            return Err(FieldError::new("Step must be positive!", Value::Null));
-        }
-
-        if input.saturation < 0.0 || input.saturation > 1.0 {
-            return Err(FieldError::new(
-                "Saturation must be between 0.0 and 1.0!",
-                Value::Null,
-            ));
-        }
-
-        if input.value < 0.0 || input.value > 1.0 {
-            return Err(FieldError::new(
-                "Value must be between 0.0 and 1.0!",
-                Value::Null,
-            ));
-        }
-
         if input.duration.filter(|duration| *duration < 0).is_some() {
             return Err(FieldError::new("Duration must be positive!", Value::Null));
         }
 
-        let effect = LedEffectKind::new_timed_rainbow(
-            input.saturation as f32,
-            input.value as f32,
-            Duration::from_secs_f64(input.time_to_complete),
-        );
+        let effect = build_rainbow_led_effect(&input, &*ctx.palettes.lock().await)?;
+        let transition = build_transition(input.transition_ms, input.transition_color_space)?;
 
         process_led_effect_mutation(
             ctx,
             LedEffect::from(effect, input.duration),
+            transition,
             input.controllers,
+            start_at,
+            priority,
         )
+        .await
     }
 
     #[graphql(description = "Alternate between color and off.")]
-    fn set_led_blink(ctx: &Context, input: BlinkLedEffectInput) -> FieldResult<MutationResponse> {
+    async fn set_led_blink(
+        ctx: &Context,
+        input: BlinkLedEffectInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
+    ) -> FieldResult<MutationResponse> {
         tracing::info!(
             "Received led blink effect ({})",
             input
@@ -239,56 +225,37 @@ impl MutationRoot {
         );
         tracing::debug!("Effect input: {input:?}");
 
-        if input.name.is_some_and(|name| name.is_empty()) {
+        if input.name.as_ref().is_some_and(|name| name.is_empty()) {
             return Err(FieldError::new("Name can't be empty!", Value::Null));
         }
 
-        if !(0..=360).contains(&input.hue) {
-            return Err(FieldError::new(
-                "Hue must be between 0 and 360!",
-                Value::Null,
-            ));
-        }
-
-        if !(0.0..=1.0).contains(&input.saturation) {
-            return Err(FieldError::new(
-                "Saturation must be between 0.0 and 1.0!",
-                Value::Null,
-            ));
-        }
-
-        if input.value <= 0.0 || input.value > 1.0 {
-            return Err(FieldError::new(
-                "Value must be above 0.0 and equal or below 1.0!",
-                Value::Null,
-            ));
-        }
-
-        if input.interval < 0 {
-            return Err(FieldError::new("Interval must be positive!", Value::Null));
-        }
-
         if input.duration.filter(|duration| *duration < 0).is_some() {
             return Err(FieldError::new("Duration must be positive!", Value::Null));
         }
 
-        let effect = LedEffectKind::Blink {
-            hsv: build_hsv(input.hue as f64, input.saturation, input.value),
-            interval: Duration::from_millis(input.interval as u64),
-            last_blink: Instant::now(),
-        };
+        let effect = build_blink_led_effect(&input)?;
+        let transition = build_transition(input.transition_ms, input.transition_color_space)?;
 
         process_led_effect_mutation(
             ctx,
             LedEffect::from(effect, input.duration),
+            transition,
             input.controllers,
+            start_at,
+            priority,
         )
+        .await
     }
 
     #[graphql(
         description = "Randomly set brightness between min value and max value, simulating a candle/flame."
     )]
-    fn set_led_candle(ctx: &Context, input: CandleLedEffectInput) -> FieldResult<MutationResponse> {
+    async fn set_led_candle(
+        ctx: &Context,
+        input: CandleLedEffectInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
+    ) -> FieldResult<MutationResponse> {
         tracing::info!(
             "Received led candle effect ({})",
             input
@@ -298,75 +265,37 @@ impl MutationRoot {
         );
         tracing::debug!("Effect input: {input:?}");
 
-        if input.name.is_some_and(|name| name.is_empty()) {
+        if input.name.as_ref().is_some_and(|name| name.is_empty()) {
             return Err(FieldError::new("Name can't be empty!", Value::Null));
         }
 
-        if !(0..=360).contains(&input.hue) {
-            return Err(FieldError::new(
-                "Hue must be between 0 and 360!",
-                Value::Null,
-            ));
-        }
-
-        if !(0.0..=1.0).contains(&input.saturation) {
-            return Err(FieldError::new(
-                "Saturation must be between 0.0 and 1.0!",
-                Value::Null,
-            ));
-        }
-
-        if !(0.0..=1.0).contains(&input.min_value) {
-            return Err(FieldError::new(
-                "Min value must between 0.0 and 1.0!",
-                Value::Null,
-            ));
-        }
-
-        if !(0.0..=1.0).contains(&input.max_value) {
-            return Err(FieldError::new(
-                "Max value must be between 0.0 and 1.0!",
-                Value::Null,
-            ));
-        }
-
-        if !(0.0..=1.0).contains(&input.variability) {
-            return Err(FieldError::new(
-                "Variability must be between 0.0 and 1.0!",
-                Value::Null,
-            ));
-        }
-
         if input.duration.filter(|duration| *duration < 0).is_some() {
             return Err(FieldError::new("Duration must be positive!", Value::Null));
         }
 
-        let hue = input.hue as f32;
-        let saturation = input.saturation as f32;
-        let min_value = input.min_value as f32;
-        let max_value = input.max_value as f32;
-        let variability = input.variability as f32;
-
-        let effect = LedEffectKind::new_candle(
-            hue,
-            saturation,
-            min_value,
-            max_value,
-            variability,
-            input.interval,
-        );
+        let effect = build_candle_led_effect(&input, &*ctx.palettes.lock().await)?;
+        let transition = build_transition(input.transition_ms, input.transition_color_space)?;
 
         process_led_effect_mutation(
             ctx,
             LedEffect::from(effect, input.duration),
+            transition,
             input.controllers,
+            start_at,
+            priority,
         )
+        .await
     }
 
     #[graphql(
         description = "Bounces from one color to the other."
     )]
-    fn set_led_bounce(ctx: &Context, input: BounceLedEffectInput) -> FieldResult<MutationResponse> {
+    async fn set_led_bounce(
+        ctx: &Context,
+        input: BounceLedEffectInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
+    ) -> FieldResult<MutationResponse> {
         tracing::info!(
             "Received led bounce effect ({})",
             input
@@ -376,213 +305,3509 @@ impl MutationRoot {
         );
         tracing::debug!("Effect input: {input:?}");
 
-        if input.name.is_some_and(|name| name.is_empty()) {
+        if input.name.as_ref().is_some_and(|name| name.is_empty()) {
             return Err(FieldError::new("Name can't be empty!", Value::Null));
         }
 
-        if !input.hues.iter().all(|hue| (0..=360).contains(hue)) {
-            return Err(FieldError::new(
-                "Hue must be between 0 and 360!",
-                Value::Null,
-            ));
-        }
-
-        if !(0.0..=1.0).contains(&input.saturation) {
-            return Err(FieldError::new(
-                "Saturation must be between 0.0 and 1.0!",
-                Value::Null,
-            ));
-        }
-
-        if !(0.0..=1.0).contains(&input.value) {
-            return Err(FieldError::new(
-                "Min value must between 0.0 and 1.0!",
-                Value::Null,
-            ));
-        }
-
-        let effect = LedEffectKind::new_bounce(input.hues.iter().map(|hue| *hue as f32).collect(), input.saturation as f32, input.value as f32, input.step as f32);
+        let effect = build_bounce_led_effect(&input, &*ctx.palettes.lock().await)?;
+        let transition = build_transition(input.transition_ms, input.transition_color_space)?;
 
         process_led_effect_mutation(
             ctx,
             LedEffect::from(effect, input.duration),
+            transition,
             input.controllers,
+            start_at,
+            priority,
         )
+        .await
     }
 
-    #[graphql(description = "Turn rumble off.")]
-    fn set_rumble_off(
+    #[graphql(description = "Flicker like a fire, from layers of noise.")]
+    async fn set_led_fire(
         ctx: &Context,
-        input: Option<OffEffectInput>,
+        input: FireLedEffectInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
     ) -> FieldResult<MutationResponse> {
-        tracing::debug!("Received rumble off effect (with {input:?})");
-
-        let controllers = input.map(|input| input.controllers);
-        process_rumble_effect_mutation(ctx, RumbleEffect::off(), controllers)
-    }
+        tracing::info!(
+            "Received led fire effect ({})",
+            input
+                .name
+                .clone()
+                .map_or(String::from("unnamed"), |name| format!("'{name}'"))
+        );
+        tracing::debug!("Effect input: {input:?}");
 
-    #[graphql(description = "Set a constant rumble.")]
-    fn set_rumble_static(
-        ctx: &Context,
-        input: StaticRumbleEffectInput,
-    ) -> FieldResult<MutationResponse> {
-        tracing::debug!("Received rumble static effect (with {input:?})");
+        if input.name.as_ref().is_some_and(|name| name.is_empty()) {
+            return Err(FieldError::new("Name can't be empty!", Value::Null));
+        }
 
         if input.duration.filter(|duration| *duration < 0).is_some() {
             return Err(FieldError::new("Duration must be positive!", Value::Null));
         }
 
-        if input.strength < 0.0 || input.strength > 1.0 {
-            return Err(FieldError::new(
-                "Strength must be between 0.0 and 1.0!",
-                Value::Null,
-            ));
-        }
-
-        let kind = RumbleEffectKind::Static {
-            strength: input.strength as f32,
-        };
+        let effect = build_fire_led_effect(&input)?;
+        let transition = build_transition(input.transition_ms, input.transition_color_space)?;
 
-        process_rumble_effect_mutation(
+        process_led_effect_mutation(
             ctx,
-            RumbleEffect::from(kind, input.duration),
+            LedEffect::from(effect, input.duration),
+            transition,
             input.controllers,
+            start_at,
+            priority,
         )
+        .await
     }
 
-    #[graphql(description = "Increase rumble strength over time, reaching a peak, then reverting.")]
-    fn set_rumble_breathing(
+    #[graphql(description = "Random bursts of flashes, each one followed by a fading afterglow.")]
+    async fn set_led_lightning(
         ctx: &Context,
-        input: BreathingRumbleEffectInput,
+        input: LightningLedEffectInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
     ) -> FieldResult<MutationResponse> {
-        tracing::debug!("Received rumble breathing effect (with {input:?})");
+        tracing::info!(
+            "Received led lightning effect ({})",
+            input
+                .name
+                .clone()
+                .map_or(String::from("unnamed"), |name| format!("'{name}'"))
+        );
+        tracing::debug!("Effect input: {input:?}");
+
+        if input.name.as_ref().is_some_and(|name| name.is_empty()) {
+            return Err(FieldError::new("Name can't be empty!", Value::Null));
+        }
 
         if input.duration.filter(|duration| *duration < 0).is_some() {
             return Err(FieldError::new("Duration must be positive!", Value::Null));
         }
 
-        if input.step < 0.0 || input.step > 1.0 {
-            return Err(FieldError::new(
-                "Step must be between 0.0 and 1.0!",
-                Value::Null,
-            ));
-        }
+        let effect = build_lightning_led_effect(&input)?;
+        let transition = build_transition(input.transition_ms, input.transition_color_space)?;
 
-        if input.initial_strength > input.peak {
-            return Err(FieldError::new(
-                "Initial strength can't be higher than peak!",
-                Value::Null,
-            ));
-        }
+        process_led_effect_mutation(
+            ctx,
+            LedEffect::from(effect, input.duration),
+            transition,
+            input.controllers,
+            start_at,
+            priority,
+        )
+        .await
+    }
 
-        if input.initial_strength < 0.0 || input.initial_strength > 1.0 {
-            return Err(FieldError::new(
-                "Initial strength must be between 0.0 and 1.0!",
-                Value::Null,
-            ));
+    #[graphql(description = "Pulse twice (\"lub-dub\") every beat.")]
+    async fn set_led_heartbeat(
+        ctx: &Context,
+        input: HeartbeatLedEffectInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!(
+            "Received led heartbeat effect ({})",
+            input
+                .name
+                .clone()
+                .map_or(String::from("unnamed"), |name| format!("'{name}'"))
+        );
+        tracing::debug!("Effect input: {input:?}");
+
+        if input.name.as_ref().is_some_and(|name| name.is_empty()) {
+            return Err(FieldError::new("Name can't be empty!", Value::Null));
         }
 
-        if input.peak < 0.0 || input.peak > 1.0 {
-            return Err(FieldError::new(
-                "Peak must be between 0.0 and 1.0!",
-                Value::Null,
-            ));
+        if input.duration.filter(|duration| *duration < 0).is_some() {
+            return Err(FieldError::new("Duration must be positive!", Value::Null));
         }
 
-        let kind = RumbleEffectKind::Breathing {
-            initial_strength: input.initial_strength as f32,
-            step: input.step as f32,
-            peak: input.peak as f32,
-            inhaling: true,
-        };
+        let effect = build_heartbeat_led_effect(&input)?;
+        let transition = build_transition(input.transition_ms, input.transition_color_space)?;
 
-        process_rumble_effect_mutation(
+        process_led_effect_mutation(
             ctx,
-            RumbleEffect::from(kind, input.duration),
+            LedEffect::from(effect, input.duration),
+            transition,
             input.controllers,
+            start_at,
+            priority,
         )
+        .await
     }
 
-    #[graphql(description = "Alternate between rumble on and off.")]
-    fn set_rumble_blink(
+    #[graphql(description = "Flash at a fixed frequency, lit during a fraction of each period.")]
+    async fn set_led_strobe(
         ctx: &Context,
-        input: BlinkRumbleEffectInput,
+        input: StrobeLedEffectInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
     ) -> FieldResult<MutationResponse> {
-        tracing::info!("Received rumble blink effect");
+        tracing::info!(
+            "Received led strobe effect ({})",
+            input
+                .name
+                .clone()
+                .map_or(String::from("unnamed"), |name| format!("'{name}'"))
+        );
         tracing::debug!("Effect input: {input:?}");
 
-        if input.strength < 0.0 || input.strength > 1.0 {
-            return Err(FieldError::new(
-                "Strength must be between 0.0 and 1.0!",
-                Value::Null,
-            ));
-        }
-
-        if input.interval < 0 {
-            return Err(FieldError::new("Interval must be positive!", Value::Null));
+        if input.name.as_ref().is_some_and(|name| name.is_empty()) {
+            return Err(FieldError::new("Name can't be empty!", Value::Null));
         }
 
         if input.duration.filter(|duration| *duration < 0).is_some() {
             return Err(FieldError::new("Duration must be positive!", Value::Null));
         }
 
-        let kind = RumbleEffectKind::Blink {
-            strength: input.strength as f32,
-            interval: Duration::from_millis(input.interval as u64),
-            last_blink: Instant::now(),
-        };
+        let effect = build_strobe_led_effect(&input)?;
+        let transition = build_transition(input.transition_ms, input.transition_color_space)?;
 
-        process_rumble_effect_mutation(
+        process_led_effect_mutation(
             ctx,
-            RumbleEffect::from(kind, input.duration),
+            LedEffect::from(effect, input.duration),
+            transition,
             input.controllers,
+            start_at,
+            priority,
         )
+        .await
     }
-}
-
-fn process_led_effect_mutation(
-    ctx: &Context,
-    effect: LedEffect,
-    target: Option<Vec<String>>,
-) -> FieldResult<MutationResponse> {
-    process_effect_mutation(ctx, EffectChangeType::Led { effect }, target)
-}
 
-fn process_rumble_effect_mutation(
-    ctx: &Context,
+    #[graphql(description = "Alternate between two colors, flashing each one a few times.")]
+    async fn set_led_police(
+        ctx: &Context,
+        input: PoliceLedEffectInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!(
+            "Received led police effect ({})",
+            input
+                .name
+                .clone()
+                .map_or(String::from("unnamed"), |name| format!("'{name}'"))
+        );
+        tracing::debug!("Effect input: {input:?}");
+
+        if input.name.as_ref().is_some_and(|name| name.is_empty()) {
+            return Err(FieldError::new("Name can't be empty!", Value::Null));
+        }
+
+        if input.duration.filter(|duration| *duration < 0).is_some() {
+            return Err(FieldError::new("Duration must be positive!", Value::Null));
+        }
+
+        let effect = build_police_led_effect(&input)?;
+        let transition = build_transition(input.transition_ms, input.transition_color_space)?;
+
+        process_led_effect_mutation(
+            ctx,
+            LedEffect::from(effect, input.duration),
+            transition,
+            input.controllers,
+            start_at,
+            priority,
+        )
+        .await
+    }
+
+    #[graphql(description = "Random brief white flashes over a base color.")]
+    async fn set_led_sparkle(
+        ctx: &Context,
+        input: SparkleLedEffectInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!(
+            "Received led sparkle effect ({})",
+            input
+                .name
+                .clone()
+                .map_or(String::from("unnamed"), |name| format!("'{name}'"))
+        );
+        tracing::debug!("Effect input: {input:?}");
+
+        if input.name.as_ref().is_some_and(|name| name.is_empty()) {
+            return Err(FieldError::new("Name can't be empty!", Value::Null));
+        }
+
+        if input.duration.filter(|duration| *duration < 0).is_some() {
+            return Err(FieldError::new("Duration must be positive!", Value::Null));
+        }
+
+        let effect = build_sparkle_led_effect(&input)?;
+        let transition = build_transition(input.transition_ms, input.transition_color_space)?;
+
+        process_led_effect_mutation(
+            ctx,
+            LedEffect::from(effect, input.duration),
+            transition,
+            input.controllers,
+            start_at,
+            priority,
+        )
+        .await
+    }
+
+    #[graphql(
+        description = "Drift hue and/or value smoothly with coherent noise, never repeating. (e.g. an aurora with hues 120 to 280, or an ocean with hues 180 to 230)"
+    )]
+    async fn set_led_noise(
+        ctx: &Context,
+        input: NoiseLedEffectInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!(
+            "Received led noise effect ({})",
+            input
+                .name
+                .clone()
+                .map_or(String::from("unnamed"), |name| format!("'{name}'"))
+        );
+        tracing::debug!("Effect input: {input:?}");
+
+        if input.name.as_ref().is_some_and(|name| name.is_empty()) {
+            return Err(FieldError::new("Name can't be empty!", Value::Null));
+        }
+
+        if input.duration.filter(|duration| *duration < 0).is_some() {
+            return Err(FieldError::new("Duration must be positive!", Value::Null));
+        }
+
+        let effect = build_noise_led_effect(&input)?;
+        let transition = build_transition(input.transition_ms, input.transition_color_space)?;
+
+        process_led_effect_mutation(
+            ctx,
+            LedEffect::from(effect, input.duration),
+            transition,
+            input.controllers,
+            start_at,
+            priority,
+        )
+        .await
+    }
+
+    #[graphql(
+        description = "Breathe across controllers in order, each one behind the previous. (a spread of 0.0 breathes in unison)"
+    )]
+    async fn set_led_wave(
+        ctx: &Context,
+        input: WaveLedEffectInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!(
+            "Received led wave effect ({})",
+            input
+                .name
+                .clone()
+                .map_or(String::from("unnamed"), |name| format!("'{name}'"))
+        );
+        tracing::debug!("Effect input: {input:?}");
+
+        if input.name.as_ref().is_some_and(|name| name.is_empty()) {
+            return Err(FieldError::new("Name can't be empty!", Value::Null));
+        }
+
+        if input.duration.filter(|duration| *duration < 0).is_some() {
+            return Err(FieldError::new("Duration must be positive!", Value::Null));
+        }
+
+        let controllers = get_group_controllers(ctx, input.controllers.clone()).await?;
+        let effects = build_wave_led_effects(&input, &controllers)?;
+        let transition = build_transition(input.transition_ms, input.transition_color_space)?;
+
+        process_led_group_mutation(
+            ctx,
+            effects,
+            input.duration,
+            transition,
+            controllers,
+            start_at,
+            priority,
+        )
+        .await
+    }
+
+    #[graphql(description = "Light one controller at a time, moving along the controllers in order.")]
+    async fn set_led_chase(
+        ctx: &Context,
+        input: ChaseLedEffectInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!(
+            "Received led chase effect ({})",
+            input
+                .name
+                .clone()
+                .map_or(String::from("unnamed"), |name| format!("'{name}'"))
+        );
+        tracing::debug!("Effect input: {input:?}");
+
+        if input.name.as_ref().is_some_and(|name| name.is_empty()) {
+            return Err(FieldError::new("Name can't be empty!", Value::Null));
+        }
+
+        if input.duration.filter(|duration| *duration < 0).is_some() {
+            return Err(FieldError::new("Duration must be positive!", Value::Null));
+        }
+
+        let controllers = get_group_controllers(ctx, input.controllers.clone()).await?;
+        let effects = build_chase_led_effects(&input, &controllers)?;
+        let transition = build_transition(input.transition_ms, input.transition_color_space)?;
+
+        process_led_group_mutation(
+            ctx,
+            effects,
+            input.duration,
+            transition,
+            controllers,
+            start_at,
+            priority,
+        )
+        .await
+    }
+
+    #[graphql(description = "Turn rumble off.")]
+    async fn set_rumble_off(
+        ctx: &Context,
+        input: Option<OffEffectInput>,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
+    ) -> FieldResult<MutationResponse> {
+        tracing::debug!("Received rumble off effect (with {input:?})");
+
+        let controllers = input.map(|input| input.controllers);
+        process_rumble_effect_mutation(
+            ctx,
+            RumbleEffect::off(),
+            controllers,
+            start_at,
+            priority,
+        )
+        .await
+    }
+
+    #[graphql(description = "Set a constant rumble.")]
+    async fn set_rumble_static(
+        ctx: &Context,
+        input: StaticRumbleEffectInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
+    ) -> FieldResult<MutationResponse> {
+        tracing::debug!("Received rumble static effect (with {input:?})");
+
+        if input.duration.filter(|duration| *duration < 0).is_some() {
+            return Err(FieldError::new("Duration must be positive!", Value::Null));
+        }
+
+        let kind = build_static_rumble_effect(&input)?;
+
+        process_rumble_effect_mutation(
+            ctx,
+            RumbleEffect::from(kind, input.duration),
+            input.controllers,
+            start_at,
+            priority,
+        )
+        .await
+    }
+
+    #[graphql(description = "Increase rumble strength over time, reaching a peak, then reverting.")]
+    async fn set_rumble_breathing(
+        ctx: &Context,
+        input: BreathingRumbleEffectInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
+    ) -> FieldResult<MutationResponse> {
+        tracing::debug!("Received rumble breathing effect (with {input:?})");
+
+        if input.duration.filter(|duration| *duration < 0).is_some() {
+            return Err(FieldError::new("Duration must be positive!", Value::Null));
+        }
+
+        let kind = build_breathing_rumble_effect(&input)?;
+
+        process_rumble_effect_mutation(
+            ctx,
+            RumbleEffect::from(kind, input.duration),
+            input.controllers,
+            start_at,
+            priority,
+        )
+        .await
+    }
+
+    #[graphql(description = "Alternate between rumble on and off.")]
+    async fn set_rumble_blink(
+        ctx: &Context,
+        input: BlinkRumbleEffectInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!("Received rumble blink effect");
+        tracing::debug!("Effect input: {input:?}");
+
+        if input.duration.filter(|duration| *duration < 0).is_some() {
+            return Err(FieldError::new("Duration must be positive!", Value::Null));
+        }
+
+        let kind = build_blink_rumble_effect(&input)?;
+
+        process_rumble_effect_mutation(
+            ctx,
+            RumbleEffect::from(kind, input.duration),
+            input.controllers,
+            start_at,
+            priority,
+        )
+        .await
+    }
+
+    #[graphql(description = "Rumble following the led's brightness.")]
+    async fn set_rumble_follow_led(
+        ctx: &Context,
+        input: FollowLedRumbleEffectInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!("Received rumble follow led effect");
+        tracing::debug!("Effect input: {input:?}");
+
+        if input.duration.filter(|duration| *duration < 0).is_some() {
+            return Err(FieldError::new("Duration must be positive!", Value::Null));
+        }
+
+        let kind = build_follow_led_rumble_effect(&input)?;
+
+        process_rumble_effect_mutation(
+            ctx,
+            RumbleEffect::from(kind, input.duration),
+            input.controllers,
+            start_at,
+            priority,
+        )
+        .await
+    }
+
+    #[graphql(
+        description = "Apply any effect of the catalog (`effectCatalog`), by its kind and parameters, as its own mutation would."
+    )]
+    async fn apply_effect(
+        ctx: &Context,
+        input: ApplyEffectInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!(
+            "Received {:?} {} effect ({})",
+            input.category,
+            input.kind,
+            input
+                .name
+                .clone()
+                .map_or(String::from("unnamed"), |name| format!("'{name}'"))
+        );
+        tracing::debug!("Effect input: {input:?}");
+
+        if input.name.as_ref().is_some_and(|name| name.is_empty()) {
+            return Err(FieldError::new("Name can't be empty!", Value::Null));
+        }
+
+        if input.duration.filter(|duration| *duration < 0).is_some() {
+            return Err(FieldError::new("Duration must be positive!", Value::Null));
+        }
+
+        let effect = effect_catalog::find_effect(input.category, &input.kind)?;
+        let parameters = input.parameters.as_deref().unwrap_or_default();
+
+        effect_catalog::validate_parameters(effect, parameters)?;
+
+        let parameters = EffectParameters(parameters);
+
+        match (input.category, input.kind.as_str()) {
+            (EffectCategory::Rumble, kind) => {
+                if input.transition_ms.is_some() || input.transition_color_space.is_some() {
+                    return Err(FieldError::new("A rumble can't have a transition!", Value::Null));
+                }
+
+                let kind = build_rumble_effect(&effect_catalog::to_rumble_effect_input(kind, &parameters))?;
+
+                process_rumble_effect_mutation(
+                    ctx,
+                    RumbleEffect::from(kind, input.duration),
+                    input.controllers,
+                    start_at,
+                    priority,
+                )
+                .await
+            }
+            (EffectCategory::Led, "wave" | "chase") => {
+                let controllers = get_group_controllers(ctx, input.controllers.clone()).await?;
+                let effects = if input.kind == "wave" {
+                    build_wave_led_effects(&effect_catalog::to_wave_led_effect_input(&input, &parameters), &controllers)?
+                } else {
+                    build_chase_led_effects(&effect_catalog::to_chase_led_effect_input(&input, &parameters), &controllers)?
+                };
+                let transition = build_transition(input.transition_ms, input.transition_color_space)?;
+
+                process_led_group_mutation(
+                    ctx,
+                    effects,
+                    input.duration,
+                    transition,
+                    controllers,
+                    start_at,
+                    priority,
+                )
+                .await
+            }
+            (EffectCategory::Led, kind) => {
+                let effect = build_led_effect(
+                    &effect_catalog::to_led_effect_input(kind, &parameters),
+                    &*ctx.palettes.lock().await,
+                )?;
+                let transition = build_transition(input.transition_ms, input.transition_color_space)?;
+
+                process_led_effect_mutation(
+                    ctx,
+                    LedEffect::from(effect, input.duration),
+                    transition,
+                    input.controllers,
+                    start_at,
+                    priority,
+                )
+                .await
+            }
+        }
+    }
+
+    #[graphql(
+        description = "Set led and rumble together, on one timeline. (e.g. a blink with `followLed` rumble pulses on each blink)"
+    )]
+    async fn set_combined_effect(
+        ctx: &Context,
+        input: CombinedEffectInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!(
+            "Received combined effect ({})",
+            input
+                .name
+                .clone()
+                .map_or(String::from("unnamed"), |name| format!("'{name}'"))
+        );
+        tracing::debug!("Effect input: {input:?}");
+
+        if input.name.as_ref().is_some_and(|name| name.is_empty()) {
+            return Err(FieldError::new("Name can't be empty!", Value::Null));
+        }
+
+        if input.duration.filter(|duration| *duration < 0).is_some() {
+            return Err(FieldError::new("Duration must be positive!", Value::Null));
+        }
+
+        let led_kind = build_led_effect(&input.led, &*ctx.palettes.lock().await)?;
+        let rumble_kind = build_rumble_effect(&input.rumble)?;
+        let transition = build_transition(input.transition_ms, input.transition_color_space)?;
+
+        // both channels share the same start, so they expire together
+        let start = Instant::now();
+        let mut led = LedEffect::from(led_kind, input.duration);
+        let mut rumble = RumbleEffect::from(rumble_kind, input.duration);
+
+        led.start_at(start);
+        rumble.start_at(start);
+
+        process_effect_mutation(
+            ctx,
+            EffectChangeType::Combined {
+                led,
+                rumble,
+                transition,
+            },
+            input.controllers,
+            start_at,
+            priority,
+        )
+        .await
+    }
+
+    #[graphql(description = "Play led and/or rumble effects one after another.")]
+    async fn set_sequence(
+        ctx: &Context,
+        input: SequenceInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!(
+            "Received sequence ({})",
+            input
+                .name
+                .clone()
+                .map_or(String::from("unnamed"), |name| format!("'{name}'"))
+        );
+        tracing::debug!("Sequence input: {input:?}");
+
+        if input.name.as_ref().is_some_and(|name| name.is_empty()) {
+            return Err(FieldError::new("Name can't be empty!", Value::Null));
+        }
+
+        if input.items.is_empty() {
+            return Err(FieldError::new(
+                "A sequence must have items!",
+                Value::Null,
+            ));
+        }
+
+        if input.loops.filter(|loops| *loops < 1).is_some() {
+            return Err(FieldError::new("Loops must be at least 1!", Value::Null));
+        }
+
+        let items = {
+            let palettes = ctx.palettes.lock().await;
+
+            input
+                .items
+                .iter()
+                .map(|item| build_sequence_item(item, &palettes))
+                .collect::<FieldResult<Vec<SequenceItem>>>()?
+        };
+
+        let sequence = EffectSequence {
+            name: input.name,
+            items,
+            loops: input.loops.map(|loops| loops as u32),
+            crossfade: build_transition(input.crossfade, input.crossfade_color_space)?,
+        };
+
+        process_effect_mutation(
+            ctx,
+            EffectChangeType::Sequence {
+                sequence: Arc::new(sequence),
+                start: Instant::now(),
+            },
+            input.controllers,
+            start_at,
+            priority,
+        )
+        .await
+    }
+
+    #[graphql(description = "Signal a text in Morse code, through led blinks and/or rumble pulses.")]
+    async fn set_morse(
+        ctx: &Context,
+        input: MorseInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!("Received morse '{}'", input.text);
+        tracing::debug!("Morse input: {input:?}");
+
+        if input.text.trim().is_empty() {
+            return Err(FieldError::new("Text can't be empty!", Value::Null));
+        }
+
+        let unit = input.unit.unwrap_or(150);
+
+        if !(50..=2000).contains(&unit) {
+            return Err(FieldError::new(
+                "Unit must be between 50 and 2000!",
+                Value::Null,
+            ));
+        }
+
+        if input.rumble.filter(|rumble| !(0.0..=1.0).contains(rumble)).is_some() {
+            return Err(FieldError::new(
+                "Rumble must be between 0.0 and 1.0!",
+                Value::Null,
+            ));
+        }
+
+        if input.color.is_none() && input.rumble.is_none() {
+            return Err(FieldError::new(
+                "Either a color or a rumble must be specified!",
+                Value::Null,
+            ));
+        }
+
+        if input.repeat.filter(|repeat| *repeat < 0).is_some() {
+            return Err(FieldError::new("Repeat must be positive!", Value::Null));
+        }
+
+        let signal = MorseSignal {
+            unit: Duration::from_millis(unit as u64),
+            led: input.color.as_ref().map(build_color).transpose()?,
+            background: input.background.as_ref().map(build_color).transpose()?,
+            rumble: input.rumble.map(|rumble| rumble as f32),
+        };
+
+        // 0 repeats forever
+        let loops = match input.repeat.unwrap_or(1) {
+            0 => None,
+            repeat => Some(repeat as u32),
+        };
+
+        let items = morse::build_items(&input.text, &signal, loops != Some(1)).map_err(|character| {
+            FieldError::new(
+                format!("'{character}' can't be signalled in Morse code!"),
+                Value::Null,
+            )
+        })?;
+
+        let sequence = EffectSequence {
+            name: Some(format!("Morse '{}'", input.text.trim())),
+            items,
+            loops,
+            crossfade: None,
+        };
+
+        process_effect_mutation(
+            ctx,
+            EffectChangeType::Sequence {
+                sequence: Arc::new(sequence),
+                start: Instant::now(),
+            },
+            input.controllers,
+            start_at,
+            priority,
+        )
+        .await
+    }
+
+    #[graphql(description = "Stop the running sequence, leaving the current effects as they are.")]
+    async fn stop_sequence(
+        ctx: &Context,
+        input: Option<SequenceControlInput>,
+        start_at: Option<StartAtInput>,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!("Received stop sequence");
+        tracing::debug!("Sequence input: {input:?}");
+
+        let controllers = input.map(|input| input.controllers);
+        process_effect_mutation(ctx, EffectChangeType::StopSequence, controllers, start_at, None)
+            .await
+    }
+
+    #[graphql(description = "Skip to the next item of the running sequence.")]
+    async fn skip_sequence_item(
+        ctx: &Context,
+        input: Option<SequenceControlInput>,
+        start_at: Option<StartAtInput>,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!("Received skip sequence item");
+        tracing::debug!("Sequence input: {input:?}");
+
+        let controllers = input.map(|input| input.controllers);
+        process_effect_mutation(ctx, EffectChangeType::SkipSequenceItem, controllers, start_at, None)
+            .await
+    }
+
+    #[graphql(
+        description = "Drop the effects (and sequence) of a priority, resuming lower ones where they were showing."
+    )]
+    async fn release_priority(
+        ctx: &Context,
+        input: ReleasePriorityInput,
+        start_at: Option<StartAtInput>,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!("Received release of {} priority", input.priority);
+
+        process_effect_mutation(
+            ctx,
+            EffectChangeType::Release,
+            input.controllers,
+            start_at,
+            Some(input.priority),
+        )
+        .await
+    }
+
+    #[graphql(
+        description = "Change parameters of the running led effects in place, without restarting them nor replacing the last effect."
+    )]
+    async fn patch_led_effect(
+        ctx: &Context,
+        input: LedEffectPatchInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!("Received led effect patch");
+        tracing::debug!("Patch input: {input:?}");
+
+        let patch = build_led_effect_patch(&input)?;
+
+        process_effect_mutation(
+            ctx,
+            EffectChangeType::PatchLed { patch },
+            input.controllers,
+            start_at,
+            priority,
+        )
+        .await
+    }
+
+    #[graphql(
+        description = "Pause the effects (and sequence) of controllers, freezing the led and rumble as they are."
+    )]
+    async fn pause_effects(
+        ctx: &Context,
+        input: Option<TimelineControlInput>,
+        start_at: Option<StartAtInput>,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!("Received pause effects");
+        tracing::debug!("Timeline input: {input:?}");
+
+        let controllers = input.and_then(|input| input.controllers);
+        process_effect_mutation(ctx, EffectChangeType::Pause, controllers, start_at, None).await
+    }
+
+    #[graphql(description = "Resume paused effects from where they were paused.")]
+    async fn resume_effects(
+        ctx: &Context,
+        input: Option<TimelineControlInput>,
+        start_at: Option<StartAtInput>,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!("Received resume effects");
+        tracing::debug!("Timeline input: {input:?}");
+
+        let controllers = input.and_then(|input| input.controllers);
+        process_effect_mutation(ctx, EffectChangeType::Resume, controllers, start_at, None).await
+    }
+
+    #[graphql(
+        description = "Change how fast the effects (their expiry and sequences too) run on controllers."
+    )]
+    async fn set_effect_speed(
+        ctx: &Context,
+        input: EffectSpeedInput,
+        start_at: Option<StartAtInput>,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!("Received effect speed");
+        tracing::debug!("Speed input: {input:?}");
+
+        let speed = input.speed as f32;
+
+        if !(MIN_EFFECT_SPEED..=MAX_EFFECT_SPEED).contains(&speed) {
+            return Err(FieldError::new(
+                "Speed must be between 0.1 and 10.0!",
+                Value::Null,
+            ));
+        }
+
+        process_effect_mutation(
+            ctx,
+            EffectChangeType::Speed { speed },
+            input.controllers,
+            start_at,
+            None,
+        )
+        .await
+    }
+
+    #[graphql(
+        description = "Go back through the effects history of the controllers, restoring what they showed before."
+    )]
+    async fn undo_effects(
+        ctx: &Context,
+        input: Option<UndoInput>,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
+    ) -> FieldResult<MutationResponse> {
+        let (controllers, steps) = input
+            .map(|input| (input.controllers, input.steps))
+            .unwrap_or_default();
+        let steps = steps.unwrap_or(1);
+
+        if steps < 1 {
+            return Err(FieldError::new("Steps must be at least 1!", Value::Null));
+        }
+
+        tracing::info!("Received undo of {steps} effect(s)");
+
+        process_effect_mutation(
+            ctx,
+            EffectChangeType::Undo { steps: steps as usize },
+            controllers,
+            start_at,
+            priority,
+        )
+        .await
+    }
+
+    #[graphql(
+        description = "Restore the state of a controller at an entry of its effects history (see effectHistory)."
+    )]
+    async fn jump_to_history(
+        ctx: &Context,
+        input: JumpToHistoryInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!("Received jump to history entry {} of {}", input.id, input.controller);
+
+        let exists = ctx
+            .controllers
+            .lock()
+            .await
+            .iter()
+            .find(|controller| controller.bt_address == input.controller)
+            .map(|controller| {
+                controller
+                    .history
+                    .entries()
+                    .iter()
+                    .any(|entry| i64::from(entry.id) == i64::from(input.id))
+            });
+
+        match exists {
+            None => return Err(FieldError::new("No controller with this address!", Value::Null)),
+            Some(false) => return Err(FieldError::new("No history entry with this id!", Value::Null)),
+            Some(true) => {}
+        }
+
+        process_effect_mutation(
+            ctx,
+            EffectChangeType::JumpToHistory { id: input.id as u32 },
+            Some(vec![input.controller]),
+            start_at,
+            priority,
+        )
+        .await
+    }
+
+    #[graphql(description = "Cancel a pending scheduled effect.")]
+    async fn cancel_scheduled_effect(
+        ctx: &Context,
+        input: CancelScheduledEffectInput,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!("Received cancel of scheduled effect {}", input.id);
+
+        if !ctx.scheduled_effects.lock().await.cancel(input.id as u32) {
+            return Err(FieldError::new(
+                "No pending scheduled effect with this id!",
+                Value::Null,
+            ));
+        }
+
+        Ok(MutationResponse::Success)
+    }
+
+    #[graphql(description = "Create or replace (by name) a recurring schedule, persisted.")]
+    async fn set_schedule(ctx: &Context, input: ScheduleInput) -> FieldResult<MutationResponse> {
+        tracing::info!("Received schedule '{}'", input.name);
+        tracing::debug!("Schedule input: {input:?}");
+
+        let schedule = build_schedule(input)?;
+        let mut schedules = ctx.schedules.lock().await;
+
+        if matches!(schedule.time, ScheduleTime::Sun { .. }) && schedules.location.is_none() {
+            return Err(FieldError::new(
+                "A location must be set to use sun events!",
+                Value::Null,
+            ));
+        }
+
+        schedules.schedules.retain(|existing| existing.name != schedule.name);
+        schedules.schedules.push(schedule);
+        schedules.save();
+
+        Ok(MutationResponse::Success)
+    }
+
+    #[graphql(description = "Delete a recurring schedule.")]
+    async fn delete_schedule(
+        ctx: &Context,
+        input: DeleteScheduleInput,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!("Received schedule deletion of '{}'", input.name);
+
+        let mut schedules = ctx.schedules.lock().await;
+        let count = schedules.schedules.len();
+
+        schedules.schedules.retain(|schedule| schedule.name != input.name);
+
+        if schedules.schedules.len() == count {
+            return Err(FieldError::new(
+                "No schedule with this name!",
+                Value::Null,
+            ));
+        }
+
+        schedules.save();
+        Ok(MutationResponse::Success)
+    }
+
+    #[graphql(description = "Set the location used to compute sunrise and sunset, persisted.")]
+    async fn set_location(ctx: &Context, input: LocationInput) -> FieldResult<MutationResponse> {
+        tracing::info!("Received location");
+        tracing::debug!("Location input: {input:?}");
+
+        if !(-90.0..=90.0).contains(&input.latitude) {
+            return Err(FieldError::new(
+                "Latitude must be between -90.0 and 90.0!",
+                Value::Null,
+            ));
+        }
+
+        if !(-180.0..=180.0).contains(&input.longitude) {
+            return Err(FieldError::new(
+                "Longitude must be between -180.0 and 180.0!",
+                Value::Null,
+            ));
+        }
+
+        let mut schedules = ctx.schedules.lock().await;
+
+        schedules.location = Some(Location {
+            latitude: input.latitude,
+            longitude: input.longitude,
+        });
+        schedules.save();
+
+        Ok(MutationResponse::Success)
+    }
+
+    #[graphql(description = "Replace the circadian profile, persisted.")]
+    async fn set_circadian(ctx: &Context, input: CircadianInput) -> FieldResult<MutationResponse> {
+        tracing::info!("Received circadian profile");
+        tracing::debug!("Circadian input: {input:?}");
+
+        let circadian = build_circadian_profile(input)?;
+        let mut schedules = ctx.schedules.lock().await;
+
+        if circadian.enabled && schedules.location.is_none() {
+            return Err(FieldError::new(
+                "A location must be set to enable the circadian profile!",
+                Value::Null,
+            ));
+        }
+
+        schedules.circadian = circadian;
+        schedules.save();
+
+        Ok(MutationResponse::Success)
+    }
+
+    #[graphql(
+        description = "Create or replace (by name) a palette, persisted. Themes using it pick it up on their next activation."
+    )]
+    async fn set_palette(ctx: &Context, input: PaletteInput) -> FieldResult<MutationResponse> {
+        tracing::info!("Received palette '{}'", input.name);
+        tracing::debug!("Palette input: {input:?}");
+
+        if input.name.is_empty() {
+            return Err(FieldError::new("Name can't be empty!", Value::Null));
+        }
+
+        if Palettes::is_built_in(&input.name) {
+            return Err(FieldError::new(
+                "Built-in palettes can't be changed!",
+                Value::Null,
+            ));
+        }
+
+        if input.colors.is_empty() {
+            return Err(FieldError::new(
+                "You must specify at least one color!",
+                Value::Null,
+            ));
+        }
+
+        let colors = input
+            .colors
+            .iter()
+            .map(|color| build_color(color).map(PaletteColor::from))
+            .collect::<FieldResult<Vec<PaletteColor>>>()?;
+
+        let mut palettes = ctx.palettes.lock().await;
+
+        palettes.palettes.retain(|palette| palette.name != input.name);
+        palettes.palettes.push(Palette {
+            name: input.name,
+            colors,
+        });
+        palettes.save();
+
+        Ok(MutationResponse::Success)
+    }
+
+    #[graphql(description = "Delete a palette, unless a theme uses it.")]
+    async fn delete_palette(
+        ctx: &Context,
+        input: DeletePaletteInput,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!("Received deletion of palette '{}'", input.name);
+
+        if Palettes::is_built_in(&input.name) {
+            return Err(FieldError::new(
+                "Built-in palettes can't be changed!",
+                Value::Null,
+            ));
+        }
+
+        let mut palettes = ctx.palettes.lock().await;
+
+        if palettes.themes.iter().any(|theme| theme.palette == input.name) {
+            return Err(FieldError::new(
+                "This palette is used by a theme!",
+                Value::Null,
+            ));
+        }
+
+        let count = palettes.palettes.len();
+        palettes.palettes.retain(|palette| palette.name != input.name);
+
+        if palettes.palettes.len() == count {
+            return Err(FieldError::new("No palette with this name!", Value::Null));
+        }
+
+        palettes.save();
+        Ok(MutationResponse::Success)
+    }
+
+    #[graphql(
+        description = "Create or replace (by name) a theme, persisted. If it's active, changes apply on its next activation."
+    )]
+    async fn set_theme(ctx: &Context, input: ThemeInput) -> FieldResult<MutationResponse> {
+        tracing::info!("Received theme '{}'", input.name);
+        tracing::debug!("Theme input: {input:?}");
+
+        if input.name.is_empty() {
+            return Err(FieldError::new("Name can't be empty!", Value::Null));
+        }
+
+        if !(0.0..=1.0).contains(&input.brightness) {
+            return Err(FieldError::new(
+                "Brightness must be between 0.0 and 1.0!",
+                Value::Null,
+            ));
+        }
+
+        let mut palettes = ctx.palettes.lock().await;
+        get_palette(&palettes, &input.palette)?;
+
+        palettes.themes.retain(|theme| theme.name != input.name);
+        palettes.themes.push(Theme {
+            name: input.name,
+            palette: input.palette,
+            brightness: input.brightness as f32,
+        });
+        palettes.save();
+
+        Ok(MutationResponse::Success)
+    }
+
+    #[graphql(description = "Delete a theme, deactivating it if it's active.")]
+    async fn delete_theme(ctx: &Context, input: DeleteThemeInput) -> FieldResult<MutationResponse> {
+        tracing::info!("Received deletion of theme '{}'", input.name);
+
+        let was_active = {
+            let mut palettes = ctx.palettes.lock().await;
+
+            let count = palettes.themes.len();
+            palettes.themes.retain(|theme| theme.name != input.name);
+
+            if palettes.themes.len() == count {
+                return Err(FieldError::new("No theme with this name!", Value::Null));
+            }
+
+            palettes.save();
+            palettes.active_theme.as_ref() == Some(&input.name)
+        };
+
+        if was_active {
+            return apply_theme(ctx, None, None).await;
+        }
+
+        Ok(MutationResponse::Success)
+    }
+
+    #[graphql(
+        description = "Re-color every running effect with the theme's palette and apply its brightness."
+    )]
+    async fn activate_theme(
+        ctx: &Context,
+        input: ActivateThemeInput,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!("Received activation of theme '{}'", input.name);
+
+        let transition = build_transition(input.transition_ms, input.transition_color_space)?;
+        apply_theme(ctx, Some(input.name), transition).await
+    }
+
+    #[graphql(
+        description = "Restore the full brightness, leaving the colors of running effects as they are."
+    )]
+    async fn deactivate_theme(ctx: &Context) -> FieldResult<MutationResponse> {
+        tracing::info!("Received theme deactivation");
+
+        apply_theme(ctx, None, None).await
+    }
+
+    #[graphql(description = "Create or replace (by name) a scene from effects, persisted.")]
+    async fn set_scene(ctx: &Context, input: SceneInput) -> FieldResult<MutationResponse> {
+        tracing::info!("Received scene '{}'", input.name);
+        tracing::debug!("Scene input: {input:?}");
+
+        if input.name.is_empty() {
+            return Err(FieldError::new("Name can't be empty!", Value::Null));
+        }
+
+        if input.controllers.is_empty() {
+            return Err(FieldError::new("You must specify controllers!", Value::Null));
+        }
+
+        let controllers = {
+            let palettes = ctx.palettes.lock().await;
+
+            input
+                .controllers
+                .iter()
+                .map(|controller| build_scene_controller(controller, &palettes))
+                .collect::<FieldResult<Vec<SceneController>>>()?
+        };
+
+        if controllers
+            .iter()
+            .enumerate()
+            .any(|(index, controller)| {
+                controllers[..index]
+                    .iter()
+                    .any(|other| other.address == controller.address)
+            })
+        {
+            return Err(FieldError::new(
+                "Each controller must only be specified once!",
+                Value::Null,
+            ));
+        }
+
+        let mut scenes = ctx.scenes.lock().await;
+
+        scenes.scenes.retain(|scene| scene.name != input.name);
+        scenes.scenes.push(Scene {
+            name: input.name,
+            controllers,
+        });
+        scenes.save();
+
+        Ok(MutationResponse::Success)
+    }
+
+    #[graphql(
+        description = "Save the current led and rumble effects of controllers as a scene, replacing the one with the same name."
+    )]
+    async fn save_scene(ctx: &Context, input: SaveSceneInput) -> FieldResult<MutationResponse> {
+        tracing::info!("Received saving of scene '{}'", input.name);
+        tracing::debug!("Scene input: {input:?}");
+
+        if input.name.is_empty() {
+            return Err(FieldError::new("Name can't be empty!", Value::Null));
+        }
+
+        if input.controllers.as_ref().is_some_and(|controllers| controllers.is_empty()) {
+            return Err(FieldError::new("You must specify controllers!", Value::Null));
+        }
+
+        let controllers = ctx
+            .controllers
+            .lock()
+            .await
+            .iter()
+            .filter(|controller| {
+                input
+                    .controllers
+                    .as_ref()
+                    .is_none_or(|addresses| addresses.contains(&controller.bt_address))
+            })
+            .map(|controller| SceneController {
+                address: controller.bt_address.clone(),
+                led: LedEffectSnapshot::from(&controller.led_effect.kind),
+                rumble: RumbleEffectSnapshot::from(&controller.rumble_effect.kind),
+            })
+            .collect::<Vec<SceneController>>();
+
+        if controllers.is_empty() {
+            return Err(FieldError::new("No connected controllers to save!", Value::Null));
+        }
+
+        let mut scenes = ctx.scenes.lock().await;
+
+        scenes.scenes.retain(|scene| scene.name != input.name);
+        scenes.scenes.push(Scene {
+            name: input.name,
+            controllers,
+        });
+        scenes.save();
+
+        Ok(MutationResponse::Success)
+    }
+
+    #[graphql(description = "Restore the effects of a scene, all of them starting together.")]
+    async fn apply_scene(
+        ctx: &Context,
+        input: ApplySceneInput,
+        start_at: Option<StartAtInput>,
+        priority: Option<EffectPriority>,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!("Received scene '{}' to apply", input.name);
+        tracing::debug!("Scene input: {input:?}");
+
+        if input.controllers.as_ref().is_some_and(|controllers| controllers.is_empty()) {
+            return Err(FieldError::new("You must specify controllers!", Value::Null));
+        }
+
+        let transition = build_transition(input.transition_ms, input.transition_color_space)?;
+
+        let (controllers, effect) = {
+            let scenes = ctx.scenes.lock().await;
+            let scene = scenes
+                .get(&input.name)
+                .ok_or_else(|| FieldError::new("No scene with this name!", Value::Null))?;
+            let effects = scene.get_effects(input.controllers.as_deref());
+
+            if effects.is_empty() {
+                return Err(FieldError::new(
+                    "None of these controllers are in the scene!",
+                    Value::Null,
+                ));
+            }
+
+            (effects.keys().cloned().collect(), scene.to_effect_type(effects, transition))
+        };
+
+        process_effect_mutation(ctx, effect, Some(controllers), start_at, priority).await
+    }
+
+    #[graphql(description = "Delete a scene.")]
+    async fn delete_scene(ctx: &Context, input: DeleteSceneInput) -> FieldResult<MutationResponse> {
+        tracing::info!("Received deletion of scene '{}'", input.name);
+
+        let mut scenes = ctx.scenes.lock().await;
+
+        let count = scenes.scenes.len();
+        scenes.scenes.retain(|scene| scene.name != input.name);
+
+        if scenes.scenes.len() == count {
+            return Err(FieldError::new("No scene with this name!", Value::Null));
+        }
+
+        scenes.save();
+        Ok(MutationResponse::Success)
+    }
+
+    #[graphql(description = "Create or replace (by name) a rule, running actions on controller events, persisted.")]
+    async fn set_rule(ctx: &Context, input: RuleInput) -> FieldResult<MutationResponse> {
+        tracing::info!("Received rule '{}'", input.name);
+        tracing::debug!("Rule input: {input:?}");
+
+        let rule = {
+            let palettes = ctx.palettes.lock().await;
+            build_rule(input, &palettes)?
+        };
+
+        {
+            let scenes = ctx.scenes.lock().await;
+            let mut names = rule.actions.iter().flat_map(|action| match action {
+                RuleAction::Scene { name, .. } => vec![name],
+                RuleAction::ToggleScene { name, otherwise, .. } => {
+                    std::iter::once(name).chain(otherwise).collect()
+                }
+                _ => vec![],
+            });
+
+            if names.any(|name| scenes.get(name).is_none()) {
+                return Err(FieldError::new("No scene with this name!", Value::Null));
+            }
+        }
+
+        let mut rules = ctx.rules.lock().await;
+
+        rules.rules.retain(|existing| existing.name != rule.name);
+        rules.rules.push(rule);
+        rules.save();
+
+        Ok(MutationResponse::Success)
+    }
+
+    #[graphql(description = "Enable or disable a rule, keeping it.")]
+    async fn enable_rule(ctx: &Context, input: EnableRuleInput) -> FieldResult<MutationResponse> {
+        tracing::info!("Received rule '{}' to be enabled: {}", input.name, input.enabled);
+
+        let mut rules = ctx.rules.lock().await;
+        let rule = rules
+            .rules
+            .iter_mut()
+            .find(|rule| rule.name == input.name)
+            .ok_or_else(|| FieldError::new("No rule with this name!", Value::Null))?;
+
+        rule.enabled = input.enabled;
+        rules.save();
+
+        Ok(MutationResponse::Success)
+    }
+
+    #[graphql(description = "Delete a rule.")]
+    async fn delete_rule(ctx: &Context, input: DeleteRuleInput) -> FieldResult<MutationResponse> {
+        tracing::info!("Received deletion of rule '{}'", input.name);
+
+        let mut rules = ctx.rules.lock().await;
+
+        let count = rules.rules.len();
+        rules.rules.retain(|rule| rule.name != input.name);
+
+        if rules.rules.len() == count {
+            return Err(FieldError::new("No rule with this name!", Value::Null));
+        }
+
+        rules.save();
+        Ok(MutationResponse::Success)
+    }
+
+    #[graphql(description = "Create or replace (by name) an analog binding, persisted.")]
+    async fn set_binding(ctx: &Context, input: BindingInput) -> FieldResult<MutationResponse> {
+        tracing::info!("Received binding '{}'", input.name);
+        tracing::debug!("Binding input: {input:?}");
+
+        let binding = build_binding(input)?;
+        let mut bindings = ctx.bindings.lock().await;
+
+        bindings.bindings.retain(|existing| existing.name != binding.name);
+        bindings.bindings.push(binding);
+        bindings.save();
+
+        Ok(MutationResponse::Success)
+    }
+
+    #[graphql(description = "Delete an analog binding, its parameter goes back to the effects' own.")]
+    async fn delete_binding(ctx: &Context, input: DeleteBindingInput) -> FieldResult<MutationResponse> {
+        tracing::info!("Received deletion of binding '{}'", input.name);
+
+        let mut bindings = ctx.bindings.lock().await;
+
+        let count = bindings.bindings.len();
+        bindings.bindings.retain(|binding| binding.name != input.name);
+
+        if bindings.bindings.len() == count {
+            return Err(FieldError::new("No binding with this name!", Value::Null));
+        }
+
+        bindings.save();
+        Ok(MutationResponse::Success)
+    }
+
+    #[graphql(description = "Set the led effect of every controller when the server starts, persisted. (applies on the next start)")]
+    async fn set_startup_effect(
+        ctx: &Context,
+        input: LifecycleEffectInput,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!("Received startup effect");
+        tracing::debug!("Startup effect input: {input:?}");
+
+        let effect = {
+            let palettes = ctx.palettes.lock().await;
+            build_lifecycle_effect(&input.led, input.duration, &palettes)?
+        };
+
+        let mut settings = ctx.settings.lock().await;
+
+        settings.startup_effect = effect;
+        settings.save();
+
+        Ok(MutationResponse::Success)
+    }
+
+    #[graphql(
+        description = "Set the led effect of a controller connecting while there's no other effect to join, persisted."
+    )]
+    async fn set_connect_effect(
+        ctx: &Context,
+        input: LifecycleEffectInput,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!("Received on connected effect");
+        tracing::debug!("On connected effect input: {input:?}");
+
+        let effect = {
+            let palettes = ctx.palettes.lock().await;
+            build_lifecycle_effect(&input.led, input.duration, &palettes)?
+        };
+
+        let mut settings = ctx.settings.lock().await;
+
+        settings.connect_effect = effect;
+        settings.save();
+
+        Ok(MutationResponse::Success)
+    }
+
+    #[graphql(
+        description = "Set the led effect of controllers whenever they connect, instead of any other effect, persisted."
+    )]
+    async fn set_controller_connect_effect(
+        ctx: &Context,
+        input: ControllerConnectEffectInput,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!(
+            "Received on connected effect of {} controllers",
+            input.controllers.len()
+        );
+        tracing::debug!("On connected effect input: {input:?}");
+
+        if input.controllers.is_empty() {
+            return Err(FieldError::new("You must specify controllers!", Value::Null));
+        }
+
+        let effect = {
+            let palettes = ctx.palettes.lock().await;
+            build_lifecycle_effect(&input.led, input.duration, &palettes)?
+        };
+
+        let mut settings = ctx.settings.lock().await;
+
+        input.controllers.into_iter().for_each(|bt_address| match &effect {
+            Some(effect) => {
+                settings.controller_connect_effects.insert(bt_address, effect.clone());
+            }
+            None => {
+                settings.controller_connect_effects.remove(&bt_address);
+            }
+        });
+        settings.save();
+
+        Ok(MutationResponse::Success)
+    }
+
+    #[graphql(description = "Change the gamma, brightness cap and/or dithering of every controller.")]
+    async fn set_color_output(
+        ctx: &Context,
+        input: ColorOutputInput,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!("Received color output change");
+        tracing::debug!("Output input: {input:?}");
+
+        if input.gamma.filter(|gamma| !(0.1..=5.0).contains(gamma)).is_some() {
+            return Err(FieldError::new(
+                "Gamma must be between 0.1 and 5.0!",
+                Value::Null,
+            ));
+        }
+
+        if input
+            .brightness_cap
+            .filter(|cap| !(0.0..=1.0).contains(cap))
+            .is_some()
+        {
+            return Err(FieldError::new(
+                "Brightness cap must be between 0.0 and 1.0!",
+                Value::Null,
+            ));
+        }
+
+        let output = {
+            let mut settings = ctx.settings.lock().await;
+
+            if let Some(gamma) = input.gamma {
+                settings.output.gamma = gamma as f32;
+            }
+            if let Some(brightness_cap) = input.brightness_cap {
+                settings.output.brightness_cap = brightness_cap as f32;
+            }
+            if let Some(dithering) = input.dithering {
+                settings.output.dithering = dithering;
+            }
+
+            settings.save();
+            settings.output.clone()
+        };
+
+        apply_output_settings(ctx, &output).await;
+        Ok(MutationResponse::Success)
+    }
+
+    #[graphql(description = "Calibrate the color channels of controllers, persisted by address.")]
+    async fn set_color_calibration(
+        ctx: &Context,
+        input: ColorCalibrationInput,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!("Received color calibration");
+        tracing::debug!("Calibration input: {input:?}");
+
+        if input.controllers.is_empty() {
+            return Err(FieldError::new(
+                "You must specify controllers!",
+                Value::Null,
+            ));
+        }
+
+        let red = input.red.as_ref().map(build_channel_calibration).transpose()?;
+        let green = input.green.as_ref().map(build_channel_calibration).transpose()?;
+        let blue = input.blue.as_ref().map(build_channel_calibration).transpose()?;
+
+        let output = {
+            let mut settings = ctx.settings.lock().await;
+
+            input.controllers.iter().for_each(|bt_address| {
+                let calibration = settings
+                    .output
+                    .calibrations
+                    .entry(bt_address.clone())
+                    .or_default();
+
+                if let Some(red) = red {
+                    calibration.red = red;
+                }
+                if let Some(green) = green {
+                    calibration.green = green;
+                }
+                if let Some(blue) = blue {
+                    calibration.blue = blue;
+                }
+            });
+
+            settings.save();
+            settings.output.clone()
+        };
+
+        apply_output_settings(ctx, &output).await;
+        Ok(MutationResponse::Success)
+    }
+
+    #[graphql(description = "Remove the color calibration of controllers.")]
+    async fn reset_color_calibration(
+        ctx: &Context,
+        input: ResetColorCalibrationInput,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!("Received color calibration reset");
+        tracing::debug!("Calibration input: {input:?}");
+
+        if input.controllers.is_empty() {
+            return Err(FieldError::new(
+                "You must specify controllers!",
+                Value::Null,
+            ));
+        }
+
+        let output = {
+            let mut settings = ctx.settings.lock().await;
+
+            input.controllers.iter().for_each(|bt_address| {
+                settings.output.calibrations.remove(bt_address);
+            });
+
+            settings.save();
+            settings.output.clone()
+        };
+
+        apply_output_settings(ctx, &output).await;
+        Ok(MutationResponse::Success)
+    }
+
+    #[graphql(description = "Set the led PWM frequency of controllers, persisted by address.")]
+    async fn set_led_pwm_frequency(
+        ctx: &Context,
+        input: LedPwmFrequencyInput,
+    ) -> FieldResult<MutationResponse> {
+        tracing::info!("Received led PWM frequency");
+        tracing::debug!("Frequency input: {input:?}");
+
+        if input.controllers.is_empty() {
+            return Err(FieldError::new(
+                "You must specify controllers!",
+                Value::Null,
+            ));
+        }
+
+        let frequency = input.frequency.map(|frequency| frequency as u64);
+
+        if frequency
+            .filter(|frequency| !(MIN_LED_PWM_FREQUENCY..=MAX_LED_PWM_FREQUENCY).contains(frequency))
+            .is_some()
+        {
+            return Err(FieldError::new(
+                format!("Frequency must be between {MIN_LED_PWM_FREQUENCY} and {MAX_LED_PWM_FREQUENCY}!"),
+                Value::Null,
+            ));
+        }
+
+        {
+            let mut settings = ctx.settings.lock().await;
+
+            input.controllers.iter().for_each(|bt_address| match frequency {
+                Some(frequency) => {
+                    settings
+                        .led_pwm_frequencies
+                        .insert(bt_address.clone(), frequency);
+                }
+                None => {
+                    settings.led_pwm_frequencies.remove(bt_address);
+                }
+            });
+
+            settings.save();
+        }
+
+        let Some(frequency) = frequency else {
+            return Ok(MutationResponse::Success);
+        };
+
+        let mut controllers = ctx.controllers.lock().await;
+        let mut has_failed = false;
+
+        controllers
+            .iter_mut()
+            .filter(|controller| input.controllers.contains(&controller.bt_address))
+            .for_each(|controller| {
+                if let Err(err) = controller.set_led_pwm_frequency(frequency) {
+                    tracing::error!("Couldn't set led PWM frequency on '{}'. Caused by {err}", controller.bt_address);
+                    has_failed = true;
+                }
+            });
+
+        if has_failed {
+            Ok(MutationResponse::ServerError)
+        } else {
+            Ok(MutationResponse::Success)
+        }
+    }
+}
+
+fn build_schedule(input: ScheduleInput) -> FieldResult<Schedule> {
+    if input.name.is_empty() {
+        return Err(FieldError::new("Name can't be empty!", Value::Null));
+    }
+
+    let time = match (input.hour, input.minute, input.sun_event) {
+        (Some(hour), Some(minute), None) => {
+            if !(0..=23).contains(&hour) {
+                return Err(FieldError::new(
+                    "Hour must be between 0 and 23!",
+                    Value::Null,
+                ));
+            }
+
+            if !(0..=59).contains(&minute) {
+                return Err(FieldError::new(
+                    "Minute must be between 0 and 59!",
+                    Value::Null,
+                ));
+            }
+
+            if input.offset_minutes.is_some() {
+                return Err(FieldError::new(
+                    "An offset is only allowed with a sun event!",
+                    Value::Null,
+                ));
+            }
+
+            ScheduleTime::Clock {
+                hour: hour as u32,
+                minute: minute as u32,
+            }
+        }
+        (None, None, Some(event)) => {
+            let offset_minutes = input.offset_minutes.unwrap_or(0);
+
+            if !(-720..=720).contains(&offset_minutes) {
+                return Err(FieldError::new(
+                    "Offset must be between -720 and 720 minutes!",
+                    Value::Null,
+                ));
+            }
+
+            ScheduleTime::Sun {
+                event,
+                offset_minutes,
+            }
+        }
+        _ => {
+            return Err(FieldError::new(
+                "Either hour and minute, or a sun event must be specified!",
+                Value::Null,
+            ))
+        }
+    };
+
+    let hsv = build_effect_color(&input.color, input.hue, input.saturation, input.value)?;
+
+    if input.transition_ms.filter(|millis| *millis < 0).is_some() {
+        return Err(FieldError::new("Transition must be positive!", Value::Null));
+    }
+
+    if input.duration.filter(|duration| *duration < 0).is_some() {
+        return Err(FieldError::new("Duration must be positive!", Value::Null));
+    }
+
+    if input.controllers.as_ref().is_some_and(|controllers| controllers.is_empty()) {
+        return Err(FieldError::new(
+            "You must specify controllers!",
+            Value::Null,
+        ));
+    }
+
+    Ok(Schedule {
+        name: input.name,
+        enabled: input.enabled.unwrap_or(true),
+        time,
+        days: input.days.unwrap_or_default(),
+        controllers: input.controllers,
+        hue: hsv.hue.into_positive_degrees(),
+        saturation: hsv.saturation,
+        value: hsv.value,
+        transition_ms: input.transition_ms.map(|millis| millis as u64),
+        duration_ms: input.duration.map(|millis| millis as u64),
+    })
+}
+
+fn build_circadian_profile(input: CircadianInput) -> FieldResult<CircadianProfile> {
+    let default = CircadianProfile::default();
+
+    let day_kelvin = input.day_kelvin.map_or(default.day_kelvin, |kelvin| kelvin as f32);
+    let night_kelvin = input.night_kelvin.map_or(default.night_kelvin, |kelvin| kelvin as f32);
+    let day_brightness = input
+        .day_brightness
+        .map_or(default.day_brightness, |brightness| brightness as f32);
+    let night_brightness = input
+        .night_brightness
+        .map_or(default.night_brightness, |brightness| brightness as f32);
+    let twilight_minutes = input
+        .twilight_minutes
+        .unwrap_or(default.twilight_minutes as i32);
+
+    if ![day_kelvin, night_kelvin]
+        .iter()
+        .all(|kelvin| (MIN_KELVIN..=MAX_KELVIN).contains(kelvin))
+    {
+        return Err(FieldError::new(
+            format!("Color temperature must be between {MIN_KELVIN} and {MAX_KELVIN}!"),
+            Value::Null,
+        ));
+    }
+
+    if ![day_brightness, night_brightness]
+        .iter()
+        .all(|brightness| (0.0..=1.0).contains(brightness))
+    {
+        return Err(FieldError::new(
+            "Brightness must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    if !(1..=720).contains(&twilight_minutes) {
+        return Err(FieldError::new(
+            "Twilight must be between 1 and 720 minutes!",
+            Value::Null,
+        ));
+    }
+
+    if input.controllers.as_ref().is_some_and(|controllers| controllers.is_empty()) {
+        return Err(FieldError::new(
+            "You must specify controllers!",
+            Value::Null,
+        ));
+    }
+
+    Ok(CircadianProfile {
+        enabled: input.enabled,
+        controllers: input.controllers,
+        day_kelvin,
+        night_kelvin,
+        day_brightness,
+        night_brightness,
+        twilight_minutes: twilight_minutes as u32,
+    })
+}
+
+fn build_channel_calibration(input: &ChannelCalibrationInput) -> FieldResult<ChannelCalibration> {
+    if !(0.0..=2.0).contains(&input.gain) {
+        return Err(FieldError::new(
+            "Gain must be between 0.0 and 2.0!",
+            Value::Null,
+        ));
+    }
+
+    if !(-1.0..=1.0).contains(&input.offset) {
+        return Err(FieldError::new(
+            "Offset must be between -1.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    Ok(ChannelCalibration {
+        gain: input.gain as f32,
+        offset: input.offset as f32,
+    })
+}
+
+/// Applies the settings on connected controllers
+/// (settings must not be locked, since controllers are locked before them elsewhere)
+async fn apply_output_settings(ctx: &Context, output: &OutputSettings) {
+    let mut controllers = ctx.controllers.lock().await;
+
+    controllers.iter_mut().for_each(|controller| {
+        controller.color_output = output.for_controller(&controller.bt_address);
+    });
+}
+
+/// Validates and converts any kind of [`ColorInput`]
+fn build_color(input: &ColorInput) -> FieldResult<Hsv> {
+    let mut colors = Vec::<FieldResult<Hsv>>::new();
+
+    if let Some(hsv) = &input.hsv {
+        colors.push(build_effect_color(&None, Some(hsv.hue), Some(hsv.saturation), Some(hsv.value)));
+    }
+    if let Some(rgb) = &input.rgb {
+        colors.push(build_rgb_color(rgb));
+    }
+    if let Some(hex) = &input.hex {
+        colors.push(Srgb::<u8>::from_str(hex.trim())
+            .map(to_hsv)
+            .map_err(|_| FieldError::new(
+                "Hex color must be like #ff8800 or #f80!",
+                Value::Null,
+            )));
+    }
+    if let Some(name) = &input.name {
+        colors.push(palette::named::from_str(&name.trim().to_lowercase())
+            .map(to_hsv)
+            .ok_or_else(|| FieldError::new(
+                format!("Unknown color name '{name}'!"),
+                Value::Null,
+            )));
+    }
+    if let Some(kelvin) = &input.kelvin {
+        colors.push(build_kelvin_color(kelvin));
+    }
+
+    match colors.pop() {
+        Some(color) if colors.is_empty() => color,
+        _ => Err(FieldError::new(
+            "Exactly one kind of color must be specified!",
+            Value::Null,
+        )),
+    }
+}
+
+fn build_rgb_color(input: &RgbColorInput) -> FieldResult<Hsv> {
+    if ![input.red, input.green, input.blue]
+        .iter()
+        .all(|channel| (0..=255).contains(channel))
+    {
+        return Err(FieldError::new(
+            "Red, green and blue must be between 0 and 255!",
+            Value::Null,
+        ));
+    }
+
+    Ok(to_hsv(Srgb::new(input.red as u8, input.green as u8, input.blue as u8)))
+}
+
+fn build_kelvin_color(input: &KelvinColorInput) -> FieldResult<Hsv> {
+    let kelvin = input.kelvin as f32;
+
+    if !(MIN_KELVIN..=MAX_KELVIN).contains(&kelvin) {
+        return Err(FieldError::new(
+            format!("Color temperature must be between {MIN_KELVIN} and {MAX_KELVIN}!"),
+            Value::Null,
+        ));
+    }
+
+    if !(0.0..=1.0).contains(&input.brightness) {
+        return Err(FieldError::new(
+            "Brightness must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    Ok(kelvin_to_hsv(kelvin, input.brightness as f32))
+}
+
+fn to_hsv(rgb: Srgb<u8>) -> Hsv {
+    Hsv::from_color(rgb.into_format::<f32>())
+}
+
+/// The color of an effect, either `color`, or `hue`, `saturation` and `value`
+fn build_effect_color(
+    color: &Option<ColorInput>,
+    hue: Option<i32>,
+    saturation: Option<f64>,
+    value: Option<f64>,
+) -> FieldResult<Hsv> {
+    match (color, hue, saturation, value) {
+        (Some(color), None, None, None) => build_color(color),
+        (None, Some(hue), Some(saturation), Some(value)) => {
+            if !(0..=360).contains(&hue) {
+                return Err(FieldError::new(
+                    "Hue must be between 0 and 360!",
+                    Value::Null,
+                ));
+            }
+
+            if !(0.0..=1.0).contains(&saturation) {
+                return Err(FieldError::new(
+                    "Saturation must be between 0.0 and 1.0!",
+                    Value::Null,
+                ));
+            }
+
+            if !(0.0..=1.0).contains(&value) {
+                return Err(FieldError::new(
+                    "Value must be between 0.0 and 1.0!",
+                    Value::Null,
+                ));
+            }
+
+            Ok(build_hsv(hue as f64, saturation, value))
+        }
+        _ => Err(FieldError::new(
+            "Either a color, or hue, saturation and value must be specified!",
+            Value::Null,
+        )),
+    }
+}
+
+/// Hue and saturation of an effect whose brightness is specified separately,
+/// either from `color`, or `hue` and `saturation`
+fn build_effect_hue(
+    color: &Option<ColorInput>,
+    hue: Option<i32>,
+    saturation: Option<f64>,
+) -> FieldResult<(f32, f32)> {
+    let hsv = match (color, hue, saturation) {
+        (Some(color), None, None) => build_color(color)?,
+        (None, Some(hue), Some(saturation)) => {
+            build_effect_color(&None, Some(hue), Some(saturation), Some(1.0))?
+        }
+        _ => {
+            return Err(FieldError::new(
+                "Either a color, or hue and saturation must be specified!",
+                Value::Null,
+            ))
+        }
+    };
+
+    Ok((hsv.hue.into_positive_degrees(), hsv.saturation))
+}
+
+fn build_static_led_effect(input: &StaticLedEffectInput) -> FieldResult<LedEffectKind> {
+    Ok(LedEffectKind::Static {
+        hsv: build_effect_color(&input.color, input.hue, input.saturation, input.value)?,
+    })
+}
+
+fn build_breathing_led_effect(input: &BreathingLedEffectInput) -> FieldResult<LedEffectKind> {
+    if input.time_to_peak < 0 {
+        return Err(FieldError::new("Step must be positive!", Value::Null));
+    }
+
+    if input.initial_value > input.peak {
+        return Err(FieldError::new(
+            "Initial value can't be higher than peak!",
+            Value::Null,
+        ));
+    }
+
+    let (hue, saturation) = build_effect_hue(&input.color, input.hue, input.saturation)?;
+
+    if input.initial_value < 0.0 || input.initial_value > 1.0 {
+        return Err(FieldError::new(
+            "Initial value must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    if input.peak < 0.0 || input.peak > 1.0 {
+        return Err(FieldError::new(
+            "Peak must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    Ok(LedEffectKind::new_timed_breathing(
+        Hsv::new(hue, saturation, input.initial_value as f32),
+        Duration::from_millis(input.time_to_peak as u64),
+        input.peak as f32,
+    ))
+}
+
+fn build_rainbow_led_effect(
+    input: &RainbowLedEffectInput,
+    palettes: &Palettes,
+) -> FieldResult<LedEffectKind> {
+    if input.time_to_complete < 0.0 {
+        return Err(FieldError::new("Step must be positive!", Value::Null));
+    }
+
+    if input.saturation < 0.0 || input.saturation > 1.0 {
+        return Err(FieldError::new(
+            "Saturation must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    if input.value < 0.0 || input.value > 1.0 {
+        return Err(FieldError::new(
+            "Value must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    let time_to_complete = Duration::from_secs_f64(input.time_to_complete);
+
+    match &input.palette {
+        Some(name) => {
+            if time_to_complete.is_zero() {
+                return Err(FieldError::new(
+                    "Time to complete must be above 0!",
+                    Value::Null,
+                ));
+            }
+
+            let colors = get_palette(palettes, name)?
+                .to_hsv()
+                .into_iter()
+                .map(|hsv| Hsv::new(hsv.hue, input.saturation as f32, input.value as f32))
+                .collect();
+
+            Ok(LedEffectKind::new_timed_bounce(
+                colors,
+                time_to_complete,
+                ColorSpace::Oklch,
+            ))
+        }
+        None => Ok(LedEffectKind::new_timed_rainbow(
+            input.saturation as f32,
+            input.value as f32,
+            time_to_complete,
+        )),
+    }
+}
+
+fn build_blink_led_effect(input: &BlinkLedEffectInput) -> FieldResult<LedEffectKind> {
+    let hsv = build_effect_color(&input.color, input.hue, input.saturation, input.value)?;
+
+    if hsv.value <= 0.0 {
+        return Err(FieldError::new(
+            "Value must be above 0.0 and equal or below 1.0!",
+            Value::Null,
+        ));
+    }
+
+    if input.interval < 0 {
+        return Err(FieldError::new("Interval must be positive!", Value::Null));
+    }
+
+    Ok(LedEffectKind::Blink {
+        hsv,
+        interval: Duration::from_millis(input.interval as u64),
+        last_blink: Instant::now(),
+    })
+}
+
+fn build_candle_led_effect(
+    input: &CandleLedEffectInput,
+    palettes: &Palettes,
+) -> FieldResult<LedEffectKind> {
+    let colors = match (&input.palette, &input.color, input.hue, input.saturation) {
+        (Some(name), None, None, None) => Some(get_palette(palettes, name)?.to_hsv()),
+        (Some(_), ..) => {
+            return Err(FieldError::new(
+                "Either a palette, a color, or hue and saturation must be specified!",
+                Value::Null,
+            ))
+        }
+        _ => None,
+    };
+
+    if !(0.0..=1.0).contains(&input.min_value) {
+        return Err(FieldError::new(
+            "Min value must between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    if !(0.0..=1.0).contains(&input.max_value) {
+        return Err(FieldError::new(
+            "Max value must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    if !(0.0..=1.0).contains(&input.variability) {
+        return Err(FieldError::new(
+            "Variability must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    let min_value = input.min_value as f32;
+    let max_value = input.max_value as f32;
+    let variability = input.variability as f32;
+
+    match colors {
+        Some(colors) => Ok(LedEffectKind::new_palette_candle(
+            colors,
+            min_value,
+            max_value,
+            variability,
+            input.interval,
+        )),
+        None => {
+            let (hue, saturation) = build_effect_hue(&input.color, input.hue, input.saturation)?;
+
+            Ok(LedEffectKind::new_candle(
+                hue,
+                saturation,
+                min_value,
+                max_value,
+                variability,
+                input.interval,
+            ))
+        }
+    }
+}
+
+fn build_bounce_led_effect(
+    input: &BounceLedEffectInput,
+    palettes: &Palettes,
+) -> FieldResult<LedEffectKind> {
+    let colors = match (
+        &input.palette,
+        &input.colors,
+        &input.hues,
+        input.saturation,
+        input.value,
+    ) {
+        (Some(name), None, None, None, None) => get_palette(palettes, name)?.to_hsv(),
+        (None, Some(colors), None, None, None) => colors
+            .iter()
+            .map(build_color)
+            .collect::<FieldResult<Vec<Hsv>>>()?,
+        (None, None, Some(hues), Some(saturation), Some(value)) => hues
+            .iter()
+            .map(|hue| build_effect_color(&None, Some(*hue), Some(saturation), Some(value)))
+            .collect::<FieldResult<Vec<Hsv>>>()?,
+        _ => {
+            return Err(FieldError::new(
+                "Either a palette, colors, or hues, saturation and value must be specified!",
+                Value::Null,
+            ))
+        }
+    };
+
+    if colors.is_empty() {
+        return Err(FieldError::new(
+            "You must specify at least one color!",
+            Value::Null,
+        ));
+    }
+
+    if !(0.0..=1.0).contains(&input.step) {
+        return Err(FieldError::new(
+            "Step must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    Ok(LedEffectKind::new_bounce(
+        colors,
+        input.step as f32,
+        input.color_space.unwrap_or_default(),
+    ))
+}
+
+fn build_fire_led_effect(input: &FireLedEffectInput) -> FieldResult<LedEffectKind> {
+    let (hue, saturation) = build_effect_hue(&input.color, input.hue, input.saturation)?;
+
+    if !(0.0..=1.0).contains(&input.min_value) {
+        return Err(FieldError::new(
+            "Min value must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    if !(0.0..=1.0).contains(&input.max_value) {
+        return Err(FieldError::new(
+            "Max value must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    if input.min_value > input.max_value {
+        return Err(FieldError::new(
+            "Min value can't be higher than max value!",
+            Value::Null,
+        ));
+    }
+
+    Ok(LedEffectKind::new_fire(
+        hue,
+        saturation,
+        input.min_value as f32,
+        input.max_value as f32,
+    ))
+}
+
+fn build_lightning_led_effect(input: &LightningLedEffectInput) -> FieldResult<LedEffectKind> {
+    let hsv = build_effect_color(&input.color, input.hue, input.saturation, input.value)?;
+
+    if input.min_interval <= 0 {
+        return Err(FieldError::new("Min interval must be above 0!", Value::Null));
+    }
+
+    if input.min_interval > input.max_interval {
+        return Err(FieldError::new(
+            "Min interval can't be higher than max interval!",
+            Value::Null,
+        ));
+    }
+
+    let afterglow = input.afterglow.unwrap_or(300);
+
+    if afterglow < 0 {
+        return Err(FieldError::new("Afterglow must be positive!", Value::Null));
+    }
+
+    Ok(LedEffectKind::new_lightning(
+        hsv,
+        Duration::from_millis(input.min_interval as u64),
+        Duration::from_millis(input.max_interval as u64),
+        Duration::from_millis(afterglow as u64),
+    ))
+}
+
+fn build_heartbeat_led_effect(input: &HeartbeatLedEffectInput) -> FieldResult<LedEffectKind> {
+    let hsv = build_effect_color(&input.color, input.hue, input.saturation, input.value)?;
+    let min_value = input.min_value.unwrap_or(0.0);
+
+    if !(0.0..=1.0).contains(&min_value) {
+        return Err(FieldError::new(
+            "Min value must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    if min_value > hsv.value as f64 {
+        return Err(FieldError::new(
+            "Min value can't be higher than value!",
+            Value::Null,
+        ));
+    }
+
+    if !(1.0..=300.0).contains(&input.bpm) {
+        return Err(FieldError::new(
+            "BPM must be between 1.0 and 300.0!",
+            Value::Null,
+        ));
+    }
+
+    Ok(LedEffectKind::new_heartbeat(
+        hsv,
+        min_value as f32,
+        input.bpm as f32,
+    ))
+}
+
+fn build_strobe_led_effect(input: &StrobeLedEffectInput) -> FieldResult<LedEffectKind> {
+    let hsv = build_effect_color(&input.color, input.hue, input.saturation, input.value)?;
+    let duty_cycle = input.duty_cycle.unwrap_or(0.1);
+
+    if input.frequency <= 0.0 || input.frequency > 50.0 {
+        return Err(FieldError::new(
+            "Frequency must be above 0.0 and equal or below 50.0!",
+            Value::Null,
+        ));
+    }
+
+    if duty_cycle <= 0.0 || duty_cycle > 1.0 {
+        return Err(FieldError::new(
+            "Duty cycle must be above 0.0 and equal or below 1.0!",
+            Value::Null,
+        ));
+    }
+
+    Ok(LedEffectKind::new_strobe(
+        hsv,
+        input.frequency as f32,
+        duty_cycle as f32,
+    ))
+}
+
+fn build_police_led_effect(input: &PoliceLedEffectInput) -> FieldResult<LedEffectKind> {
+    let first = input
+        .first
+        .as_ref()
+        .map_or(Ok(Hsv::new(0.0, 1.0, 1.0)), build_color)?;
+    let second = input
+        .second
+        .as_ref()
+        .map_or(Ok(Hsv::new(240.0, 1.0, 1.0)), build_color)?;
+    let flashes = input.flashes.unwrap_or(2);
+
+    if input.interval <= 0 {
+        return Err(FieldError::new("Interval must be above 0!", Value::Null));
+    }
+
+    if !(1..=10).contains(&flashes) {
+        return Err(FieldError::new(
+            "Flashes must be between 1 and 10!",
+            Value::Null,
+        ));
+    }
+
+    Ok(LedEffectKind::new_police(
+        first,
+        second,
+        Duration::from_millis(input.interval as u64),
+        flashes as u32,
+    ))
+}
+
+fn build_sparkle_led_effect(input: &SparkleLedEffectInput) -> FieldResult<LedEffectKind> {
+    let base = build_effect_color(&input.color, input.hue, input.saturation, input.value)?;
+    let sparkle_value = input.sparkle_value.unwrap_or(1.0);
+    let sparkle_duration = input.sparkle_duration.unwrap_or(100);
+
+    if !(0.0..=1.0).contains(&sparkle_value) {
+        return Err(FieldError::new(
+            "Sparkle value must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    if input.rate <= 0.0 || input.rate > 100.0 {
+        return Err(FieldError::new(
+            "Rate must be above 0.0 and equal or below 100.0!",
+            Value::Null,
+        ));
+    }
+
+    if sparkle_duration <= 0 {
+        return Err(FieldError::new(
+            "Sparkle duration must be above 0!",
+            Value::Null,
+        ));
+    }
+
+    Ok(LedEffectKind::new_sparkle(
+        base,
+        Hsv::new(0.0, 0.0, sparkle_value as f32),
+        input.rate as f32,
+        Duration::from_millis(sparkle_duration as u64),
+    ))
+}
+
+fn build_noise_led_effect(input: &NoiseLedEffectInput) -> FieldResult<LedEffectKind> {
+    if !(0..=360).contains(&input.min_hue) || !(0..=360).contains(&input.max_hue) {
+        return Err(FieldError::new(
+            "Hues must be between 0 and 360!",
+            Value::Null,
+        ));
+    }
+
+    if !(0.0..=1.0).contains(&input.saturation) {
+        return Err(FieldError::new(
+            "Saturation must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    if !(0.0..=1.0).contains(&input.min_value) {
+        return Err(FieldError::new(
+            "Min value must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    if !(0.0..=1.0).contains(&input.max_value) {
+        return Err(FieldError::new(
+            "Max value must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    if input.min_value > input.max_value {
+        return Err(FieldError::new(
+            "Min value can't be higher than max value!",
+            Value::Null,
+        ));
+    }
+
+    if input.speed <= 0.0 || input.speed > 20.0 {
+        return Err(FieldError::new(
+            "Speed must be above 0.0 and equal or below 20.0!",
+            Value::Null,
+        ));
+    }
+
+    let octaves = input.octaves.unwrap_or(3);
+
+    if !(1..=8).contains(&octaves) {
+        return Err(FieldError::new(
+            "Octaves must be between 1 and 8!",
+            Value::Null,
+        ));
+    }
+
+    if input.seed.filter(|seed| *seed < 0).is_some() {
+        return Err(FieldError::new("Seed must be positive!", Value::Null));
+    }
+
+    // going up from the min hue, so a range can wrap around 360
+    let hue_range = if input.max_hue >= input.min_hue {
+        input.max_hue - input.min_hue
+    } else {
+        input.max_hue + 360 - input.min_hue
+    };
+
+    Ok(LedEffectKind::new_noise(
+        input.min_hue as f32,
+        hue_range as f32,
+        input.saturation as f32,
+        input.min_value as f32,
+        input.max_value as f32,
+        input.speed as f32,
+        octaves as u32,
+        input.seed.map(|seed| seed as u32),
+    ))
+}
+
+/// Builds the effect of each controller, all sharing the same clock
+pub(super) fn build_wave_led_effects(
+    input: &WaveLedEffectInput,
+    controllers: &[String],
+) -> FieldResult<Vec<LedEffectKind>> {
+    let (hue, saturation) = build_effect_hue(&input.color, input.hue, input.saturation)?;
+
+    if !(0.0..=1.0).contains(&input.min_value) {
+        return Err(FieldError::new(
+            "Min value must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    if !(0.0..=1.0).contains(&input.max_value) {
+        return Err(FieldError::new(
+            "Max value must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    if input.min_value > input.max_value {
+        return Err(FieldError::new(
+            "Min value can't be higher than max value!",
+            Value::Null,
+        ));
+    }
+
+    if input.period <= 0 {
+        return Err(FieldError::new("Period must be above 0!", Value::Null));
+    }
+
+    if !(0.0..=1.0).contains(&input.spread) {
+        return Err(FieldError::new(
+            "Spread must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    let epoch = Instant::now();
+    let count = controllers.len() as f32;
+
+    Ok((0..controllers.len())
+        .map(|position| LedEffectKind::Wave {
+            hsv: Hsv::new(hue, saturation, input.max_value as f32),
+            min_value: input.min_value as f32,
+            period: Duration::from_millis(input.period as u64),
+            phase: input.spread as f32 * position as f32 / count,
+            epoch,
+        })
+        .collect())
+}
+
+/// Builds the effect of each controller, all sharing the same clock
+pub(super) fn build_chase_led_effects(
+    input: &ChaseLedEffectInput,
+    controllers: &[String],
+) -> FieldResult<Vec<LedEffectKind>> {
+    let hsv = build_effect_color(&input.color, input.hue, input.saturation, input.value)?;
+    let background_value = input.background_value.unwrap_or(0.0);
+
+    if !(0.0..=1.0).contains(&background_value) {
+        return Err(FieldError::new(
+            "Background value must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    if input.interval <= 0 {
+        return Err(FieldError::new("Interval must be above 0!", Value::Null));
+    }
+
+    let epoch = Instant::now();
+
+    Ok((0..controllers.len())
+        .map(|position| LedEffectKind::Chase {
+            hsv,
+            background: Hsv::new(hsv.hue, hsv.saturation, background_value as f32),
+            interval: Duration::from_millis(input.interval as u64),
+            position,
+            count: controllers.len(),
+            epoch,
+        })
+        .collect())
+}
+
+/// The controllers of a group effect, in order (all connected ones if not specified)
+async fn get_group_controllers(
+    ctx: &Context,
+    controllers: Option<Vec<String>>,
+) -> FieldResult<Vec<String>> {
+    let controllers = match controllers {
+        Some(controllers) => controllers,
+        None => ctx
+            .controllers
+            .lock()
+            .await
+            .iter()
+            .map(|controller| controller.bt_address.clone())
+            .collect(),
+    };
+
+    if controllers.is_empty() {
+        return Err(FieldError::new(
+            "You must specify controllers!",
+            Value::Null,
+        ));
+    }
+
+    let mut unique = controllers.clone();
+    unique.sort();
+    unique.dedup();
+
+    if unique.len() != controllers.len() {
+        return Err(FieldError::new(
+            "A controller can't be in a group twice!",
+            Value::Null,
+        ));
+    }
+
+    Ok(controllers)
+}
+
+fn build_static_rumble_effect(input: &StaticRumbleEffectInput) -> FieldResult<RumbleEffectKind> {
+    if input.strength < 0.0 || input.strength > 1.0 {
+        return Err(FieldError::new(
+            "Strength must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    Ok(RumbleEffectKind::Static {
+        strength: input.strength as f32,
+    })
+}
+
+fn build_breathing_rumble_effect(
+    input: &BreathingRumbleEffectInput,
+) -> FieldResult<RumbleEffectKind> {
+    if input.step < 0.0 || input.step > 1.0 {
+        return Err(FieldError::new(
+            "Step must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    if input.initial_strength > input.peak {
+        return Err(FieldError::new(
+            "Initial strength can't be higher than peak!",
+            Value::Null,
+        ));
+    }
+
+    if input.initial_strength < 0.0 || input.initial_strength > 1.0 {
+        return Err(FieldError::new(
+            "Initial strength must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    if input.peak < 0.0 || input.peak > 1.0 {
+        return Err(FieldError::new(
+            "Peak must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    Ok(RumbleEffectKind::Breathing {
+        initial_strength: input.initial_strength as f32,
+        step: input.step as f32,
+        peak: input.peak as f32,
+        inhaling: true,
+    })
+}
+
+fn build_blink_rumble_effect(input: &BlinkRumbleEffectInput) -> FieldResult<RumbleEffectKind> {
+    if input.strength < 0.0 || input.strength > 1.0 {
+        return Err(FieldError::new(
+            "Strength must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    if input.interval < 0 {
+        return Err(FieldError::new("Interval must be positive!", Value::Null));
+    }
+
+    Ok(RumbleEffectKind::Blink {
+        strength: input.strength as f32,
+        interval: Duration::from_millis(input.interval as u64),
+        last_blink: Instant::now(),
+    })
+}
+
+fn build_follow_led_rumble_effect(
+    input: &FollowLedRumbleEffectInput,
+) -> FieldResult<RumbleEffectKind> {
+    let min_strength = input.min_strength.unwrap_or(0.0);
+
+    if !(0.0..=1.0).contains(&min_strength) {
+        return Err(FieldError::new(
+            "Min strength must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    if !(0.0..=1.0).contains(&input.max_strength) {
+        return Err(FieldError::new(
+            "Max strength must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    Ok(RumbleEffectKind::FollowLed {
+        min_strength: min_strength as f32,
+        max_strength: input.max_strength as f32,
+    })
+}
+
+/// Builds the only specified effect of a [`LedEffectInput`]
+pub(super) fn build_led_effect(input: &LedEffectInput, palettes: &Palettes) -> FieldResult<LedEffectKind> {
+    let mut effects = Vec::<FieldResult<LedEffectKind>>::new();
+
+    if input.off == Some(true) {
+        effects.push(Ok(LedEffectKind::Off));
+    }
+    if let Some(effect) = &input.static_ {
+        effects.push(ensure_untargeted(&effect.controllers, effect.duration)
+            .and_then(|_| ensure_without_transition(effect.transition_ms, effect.transition_color_space))
+            .and_then(|_| build_static_led_effect(effect)));
+    }
+    if let Some(effect) = &input.breathing {
+        effects.push(ensure_untargeted(&effect.controllers, effect.duration)
+            .and_then(|_| ensure_without_transition(effect.transition_ms, effect.transition_color_space))
+            .and_then(|_| build_breathing_led_effect(effect)));
+    }
+    if let Some(effect) = &input.rainbow {
+        effects.push(ensure_untargeted(&effect.controllers, effect.duration)
+            .and_then(|_| ensure_without_transition(effect.transition_ms, effect.transition_color_space))
+            .and_then(|_| build_rainbow_led_effect(effect, palettes)));
+    }
+    if let Some(effect) = &input.blink {
+        effects.push(ensure_untargeted(&effect.controllers, effect.duration)
+            .and_then(|_| ensure_without_transition(effect.transition_ms, effect.transition_color_space))
+            .and_then(|_| build_blink_led_effect(effect)));
+    }
+    if let Some(effect) = &input.candle {
+        effects.push(ensure_untargeted(&effect.controllers, effect.duration)
+            .and_then(|_| ensure_without_transition(effect.transition_ms, effect.transition_color_space))
+            .and_then(|_| build_candle_led_effect(effect, palettes)));
+    }
+    if let Some(effect) = &input.bounce {
+        effects.push(ensure_untargeted(&effect.controllers, effect.duration)
+            .and_then(|_| ensure_without_transition(effect.transition_ms, effect.transition_color_space))
+            .and_then(|_| build_bounce_led_effect(effect, palettes)));
+    }
+    if let Some(effect) = &input.fire {
+        effects.push(ensure_untargeted(&effect.controllers, effect.duration)
+            .and_then(|_| ensure_without_transition(effect.transition_ms, effect.transition_color_space))
+            .and_then(|_| build_fire_led_effect(effect)));
+    }
+    if let Some(effect) = &input.lightning {
+        effects.push(ensure_untargeted(&effect.controllers, effect.duration)
+            .and_then(|_| ensure_without_transition(effect.transition_ms, effect.transition_color_space))
+            .and_then(|_| build_lightning_led_effect(effect)));
+    }
+    if let Some(effect) = &input.heartbeat {
+        effects.push(ensure_untargeted(&effect.controllers, effect.duration)
+            .and_then(|_| ensure_without_transition(effect.transition_ms, effect.transition_color_space))
+            .and_then(|_| build_heartbeat_led_effect(effect)));
+    }
+    if let Some(effect) = &input.strobe {
+        effects.push(ensure_untargeted(&effect.controllers, effect.duration)
+            .and_then(|_| ensure_without_transition(effect.transition_ms, effect.transition_color_space))
+            .and_then(|_| build_strobe_led_effect(effect)));
+    }
+    if let Some(effect) = &input.police {
+        effects.push(ensure_untargeted(&effect.controllers, effect.duration)
+            .and_then(|_| ensure_without_transition(effect.transition_ms, effect.transition_color_space))
+            .and_then(|_| build_police_led_effect(effect)));
+    }
+    if let Some(effect) = &input.sparkle {
+        effects.push(ensure_untargeted(&effect.controllers, effect.duration)
+            .and_then(|_| ensure_without_transition(effect.transition_ms, effect.transition_color_space))
+            .and_then(|_| build_sparkle_led_effect(effect)));
+    }
+    if let Some(effect) = &input.noise {
+        effects.push(ensure_untargeted(&effect.controllers, effect.duration)
+            .and_then(|_| ensure_without_transition(effect.transition_ms, effect.transition_color_space))
+            .and_then(|_| build_noise_led_effect(effect)));
+    }
+
+    match effects.pop() {
+        Some(effect) if effects.is_empty() => effect,
+        _ => Err(FieldError::new(
+            "Exactly one led effect must be specified!",
+            Value::Null,
+        )),
+    }
+}
+
+/// Builds the only specified effect of a [`RumbleEffectInput`]
+pub(super) fn build_rumble_effect(input: &RumbleEffectInput) -> FieldResult<RumbleEffectKind> {
+    let mut effects = Vec::<FieldResult<RumbleEffectKind>>::new();
+
+    if input.off == Some(true) {
+        effects.push(Ok(RumbleEffectKind::Off));
+    }
+    if let Some(effect) = &input.static_ {
+        effects.push(ensure_untargeted(&effect.controllers, effect.duration)
+            .and_then(|_| build_static_rumble_effect(effect)));
+    }
+    if let Some(effect) = &input.breathing {
+        effects.push(ensure_untargeted(&effect.controllers, effect.duration)
+            .and_then(|_| build_breathing_rumble_effect(effect)));
+    }
+    if let Some(effect) = &input.blink {
+        effects.push(ensure_untargeted(&effect.controllers, effect.duration)
+            .and_then(|_| build_blink_rumble_effect(effect)));
+    }
+    if let Some(effect) = &input.follow_led {
+        effects.push(ensure_untargeted(&effect.controllers, effect.duration)
+            .and_then(|_| build_follow_led_rumble_effect(effect)));
+    }
+
+    match effects.pop() {
+        Some(effect) if effects.is_empty() => effect,
+        _ => Err(FieldError::new(
+            "Exactly one rumble effect must be specified!",
+            Value::Null,
+        )),
+    }
+}
+
+/// Nested effects are targeted and timed by whatever contains them
+fn ensure_untargeted(controllers: &Option<Vec<String>>, duration: Option<i32>) -> FieldResult<()> {
+    if controllers.is_some() {
+        return Err(FieldError::new(
+            "Nested effects can't specify controllers!",
+            Value::Null,
+        ));
+    }
+
+    if duration.is_some() {
+        return Err(FieldError::new(
+            "Nested effects can't specify a duration!",
+            Value::Null,
+        ));
+    }
+
+    Ok(())
+}
+
+fn build_transition(
+    transition_ms: Option<i32>,
+    color_space: Option<ColorSpace>,
+) -> FieldResult<Option<Transition>> {
+    match transition_ms {
+        Some(millis) if millis < 0 => {
+            Err(FieldError::new("Transition must be positive!", Value::Null))
+        }
+        millis => Ok(millis.map(|millis| Transition {
+            duration: Duration::from_millis(millis as u64),
+            color_space: color_space.unwrap_or_default(),
+        })),
+    }
+}
+
+fn ensure_without_transition(
+    transition_ms: Option<i32>,
+    color_space: Option<ColorSpace>,
+) -> FieldResult<()> {
+    if transition_ms.is_some() || color_space.is_some() {
+        return Err(FieldError::new(
+            "Nested effects can't specify a transition!",
+            Value::Null,
+        ));
+    }
+
+    Ok(())
+}
+
+fn build_sequence_item(input: &SequenceItemInput, palettes: &Palettes) -> FieldResult<SequenceItem> {
+    if input.duration <= 0 {
+        return Err(FieldError::new(
+            "Item duration must be above 0!",
+            Value::Null,
+        ));
+    }
+
+    Ok(SequenceItem {
+        led: input
+            .led
+            .as_ref()
+            .map(|led| build_led_effect(led, palettes))
+            .transpose()?,
+        rumble: input.rumble.as_ref().map(build_rumble_effect).transpose()?,
+        duration: Duration::from_millis(input.duration as u64),
+    })
+}
+
+fn build_scene_controller(
+    input: &SceneControllerInput,
+    palettes: &Palettes,
+) -> FieldResult<SceneController> {
+    let led = build_led_effect(&input.led, palettes)?;
+    let rumble = input
+        .rumble
+        .as_ref()
+        .map(build_rumble_effect)
+        .transpose()?
+        .unwrap_or(RumbleEffectKind::Off);
+
+    Ok(SceneController {
+        address: input.address.clone(),
+        led: LedEffectSnapshot::from(&led),
+        rumble: RumbleEffectSnapshot::from(&rumble),
+    })
+}
+
+fn build_rule(input: RuleInput, palettes: &Palettes) -> FieldResult<Rule> {
+    if input.name.is_empty() {
+        return Err(FieldError::new("Name can't be empty!", Value::Null));
+    }
+
+    ensure_controllers(&input.controllers)?;
+
+    let time_window = match (input.from_hour, input.from_minute, input.to_hour, input.to_minute) {
+        (None, None, None, None) => None,
+        (Some(from_hour), Some(from_minute), Some(to_hour), Some(to_minute)) => Some(TimeWindow {
+            from_minute: build_minute_of_day(from_hour, from_minute)?,
+            to_minute: build_minute_of_day(to_hour, to_minute)?,
+        }),
+        _ => {
+            return Err(FieldError::new(
+                "Either all of the hours and minutes must be specified, or none!",
+                Value::Null,
+            ))
+        }
+    };
+
+    if input.actions.is_empty() {
+        return Err(FieldError::new("You must specify actions!", Value::Null));
+    }
+
+    Ok(Rule {
+        name: input.name,
+        enabled: input.enabled.unwrap_or(true),
+        event: build_rule_event(&input.event)?,
+        controllers: input.controllers,
+        time_window,
+        days: input.days.unwrap_or_default(),
+        scene: input.scene,
+        priority: input.priority.unwrap_or_default(),
+        actions: input
+            .actions
+            .iter()
+            .map(|action| build_rule_action(action, palettes))
+            .collect::<FieldResult<Vec<RuleAction>>>()?,
+    })
+}
+
+fn build_binding(input: BindingInput) -> FieldResult<Binding> {
+    if input.name.is_empty() {
+        return Err(FieldError::new("Name can't be empty!", Value::Null));
+    }
+
+    ensure_controllers(&input.targets)?;
+
+    if input.targets.is_some() && input.controller.is_none() {
+        return Err(FieldError::new(
+            "Targets need the controller of the source!",
+            Value::Null,
+        ));
+    }
+
+    let (default_input_min, default_input_max) = input.source.get_default_range();
+    let input_min = input.input_min.map_or(default_input_min, |min| min as f32);
+    let input_max = input.input_max.map_or(default_input_max, |max| max as f32);
+
+    if input_min >= input_max {
+        return Err(FieldError::new(
+            "Input min must be lower than input max!",
+            Value::Null,
+        ));
+    }
+
+    let (lowest, highest) = input.parameter.get_range();
+    let output_min = input.output_min.map_or(lowest, |min| min as f32);
+    let output_max = input.output_max.map_or(highest, |max| max as f32);
+
+    if [output_min, output_max].iter().any(|output| !(lowest..=highest).contains(output)) {
+        return Err(FieldError::new(
+            format!("{} must be between {lowest:.1} and {highest:.1}!", input.parameter),
+            Value::Null,
+        ));
+    }
+
+    Ok(Binding {
+        name: input.name,
+        enabled: input.enabled.unwrap_or(true),
+        source: input.source,
+        controller: input.controller,
+        targets: input.targets,
+        parameter: input.parameter,
+        curve: input.curve.unwrap_or_default(),
+        input_min,
+        input_max,
+        output_min,
+        output_max,
+    })
+}
+
+/// `None` if there's no effect, which disables it
+fn build_lifecycle_effect(
+    led: &Option<LedEffectInput>,
+    duration: Option<i32>,
+    palettes: &Palettes,
+) -> FieldResult<Option<LifecycleEffect>> {
+    let duration_ms = build_duration_ms(duration)?;
+
+    match led {
+        Some(led) => Ok(Some(LifecycleEffect {
+            led: LedEffectSnapshot::from(&build_led_effect(led, palettes)?),
+            duration_ms,
+        })),
+        None if duration_ms.is_some() => {
+            Err(FieldError::new("A duration needs an effect!", Value::Null))
+        }
+        None => Ok(None),
+    }
+}
+
+fn build_minute_of_day(hour: i32, minute: i32) -> FieldResult<u32> {
+    if !(0..=23).contains(&hour) {
+        return Err(FieldError::new("Hour must be between 0 and 23!", Value::Null));
+    }
+
+    if !(0..=59).contains(&minute) {
+        return Err(FieldError::new("Minute must be between 0 and 59!", Value::Null));
+    }
+
+    Ok((hour * 60 + minute) as u32)
+}
+
+fn build_rule_event(input: &RuleEventInput) -> FieldResult<RuleEvent> {
+    let mut events = Vec::<FieldResult<RuleEvent>>::new();
+
+    if let Some(button) = &input.button {
+        events.push(Ok(RuleEvent::Button {
+            button: button.button,
+            gesture: button.gesture,
+        }));
+    }
+    if let Some(threshold) = input.trigger_threshold {
+        events.push(if (0.0..=1.0).contains(&threshold) {
+            Ok(RuleEvent::Trigger { threshold: threshold as f32 })
+        } else {
+            Err(FieldError::new("Trigger threshold must be between 0.0 and 1.0!", Value::Null))
+        });
+    }
+    if let Some(threshold) = input.motion_threshold {
+        events.push(if (0.1..=8.0).contains(&threshold) {
+            Ok(RuleEvent::Motion { threshold: threshold as f32 })
+        } else {
+            Err(FieldError::new("Motion threshold must be between 0.1 and 8.0!", Value::Null))
+        });
+    }
+    if input.connect == Some(true) {
+        events.push(Ok(RuleEvent::Connect));
+    }
+    if input.disconnect == Some(true) {
+        events.push(Ok(RuleEvent::Disconnect));
+    }
+    if let Some(battery) = &input.battery {
+        events.push(Ok(RuleEvent::Battery { level: battery.level }));
+    }
+
+    match events.pop() {
+        Some(event) if events.is_empty() => event,
+        _ => Err(FieldError::new(
+            "Exactly one event must be specified!",
+            Value::Null,
+        )),
+    }
+}
+
+fn build_rule_action(input: &RuleActionInput, palettes: &Palettes) -> FieldResult<RuleAction> {
+    let mut actions = Vec::<FieldResult<RuleAction>>::new();
+
+    if let Some(action) = &input.led {
+        actions.push(
+            ensure_controllers(&action.controllers)
+                .and_then(|_| build_duration_ms(action.duration))
+                .and_then(|duration_ms| {
+                    Ok(RuleAction::Led {
+                        controllers: action.controllers.clone(),
+                        effect: LedEffectSnapshot::from(&build_led_effect(&action.effect, palettes)?),
+                        duration_ms,
+                        transition_ms: build_transition_ms(action.transition_ms)?,
+                    })
+                }),
+        );
+    }
+    if let Some(action) = &input.rumble {
+        actions.push(
+            ensure_controllers(&action.controllers)
+                .and_then(|_| build_duration_ms(action.duration))
+                .and_then(|duration_ms| {
+                    Ok(RuleAction::Rumble {
+                        controllers: action.controllers.clone(),
+                        effect: RumbleEffectSnapshot::from(&build_rumble_effect(&action.effect)?),
+                        duration_ms,
+                    })
+                }),
+        );
+    }
+    if let Some(action) = &input.scene {
+        actions.push(build_transition_ms(action.transition_ms).map(|transition_ms| {
+            RuleAction::Scene {
+                name: action.name.clone(),
+                transition_ms,
+            }
+        }));
+    }
+    if let Some(action) = &input.toggle_scene {
+        actions.push(build_transition_ms(action.transition_ms).map(|transition_ms| {
+            RuleAction::ToggleScene {
+                name: action.name.clone(),
+                otherwise: action.otherwise.clone(),
+                transition_ms,
+            }
+        }));
+    }
+    if let Some(action) = &input.webhook {
+        actions.push(match reqwest::Url::parse(&action.url) {
+            Ok(url) if ["http", "https"].contains(&url.scheme()) => Ok(RuleAction::Webhook {
+                url: action.url.clone(),
+            }),
+            _ => Err(FieldError::new("Webhook must be an HTTP or HTTPS URL!", Value::Null)),
+        });
+    }
+
+    match actions.pop() {
+        Some(action) if actions.is_empty() => action,
+        _ => Err(FieldError::new(
+            "Exactly one action must be specified!",
+            Value::Null,
+        )),
+    }
+}
+
+fn ensure_controllers(controllers: &Option<Vec<String>>) -> FieldResult<()> {
+    if controllers.as_ref().is_some_and(|controllers| controllers.is_empty()) {
+        return Err(FieldError::new("You must specify controllers!", Value::Null));
+    }
+
+    Ok(())
+}
+
+fn build_duration_ms(duration: Option<i32>) -> FieldResult<Option<u64>> {
+    match duration {
+        Some(millis) if millis < 0 => Err(FieldError::new("Duration must be positive!", Value::Null)),
+        millis => Ok(millis.map(|millis| millis as u64)),
+    }
+}
+
+fn build_transition_ms(transition_ms: Option<i32>) -> FieldResult<Option<u64>> {
+    match transition_ms {
+        Some(millis) if millis < 0 => Err(FieldError::new("Transition must be positive!", Value::Null)),
+        millis => Ok(millis.map(|millis| millis as u64)),
+    }
+}
+
+fn get_palette<'a>(palettes: &'a Palettes, name: &str) -> FieldResult<&'a Palette> {
+    palettes
+        .get(name)
+        .ok_or_else(|| FieldError::new("No palette with this name!", Value::Null))
+}
+
+/// Activates the theme (or deactivates any with `None`), re-coloring the running effects
+/// with its palette
+async fn apply_theme(
+    ctx: &Context,
+    name: Option<String>,
+    transition: Option<Transition>,
+) -> FieldResult<MutationResponse> {
+    let (colors, brightness) = {
+        let mut palettes = ctx.palettes.lock().await;
+
+        let (colors, brightness) = match &name {
+            Some(name) => {
+                let theme = palettes
+                    .get_theme(name)
+                    .ok_or_else(|| FieldError::new("No theme with this name!", Value::Null))?;
+
+                (
+                    Some(get_palette(&palettes, &theme.palette)?.to_hsv()),
+                    theme.brightness,
+                )
+            }
+            None => (None, 1.0),
+        };
+
+        palettes.active_theme = name;
+        palettes.save();
+
+        (colors, brightness)
+    };
+
+    let output = {
+        let mut settings = ctx.settings.lock().await;
+
+        settings.output.theme_brightness = brightness;
+        settings.save();
+        settings.output.clone()
+    };
+
+    apply_output_settings(ctx, &output).await;
+
+    match colors {
+        Some(colors) => {
+            let recolor = EffectChangeType::Recolor {
+                colors: Arc::new(colors),
+                transition,
+            };
+
+            process_effect_mutation(ctx, recolor, None, None, None).await
+        }
+        None => Ok(MutationResponse::Success),
+    }
+}
+
+async fn process_led_effect_mutation(
+    ctx: &Context,
+    effect: LedEffect,
+    transition: Option<Transition>,
+    target: Option<Vec<String>>,
+    start_at: Option<StartAtInput>,
+    priority: Option<EffectPriority>,
+) -> FieldResult<MutationResponse> {
+    process_effect_mutation(
+        ctx,
+        EffectChangeType::Led { effect, transition },
+        target,
+        start_at,
+        priority,
+    )
+    .await
+}
+
+async fn process_led_group_mutation(
+    ctx: &Context,
+    effects: Vec<LedEffectKind>,
+    duration: Option<i32>,
+    transition: Option<Transition>,
+    controllers: Vec<String>,
+    start_at: Option<StartAtInput>,
+    priority: Option<EffectPriority>,
+) -> FieldResult<MutationResponse> {
+    let effects = controllers
+        .iter()
+        .cloned()
+        .zip(effects.into_iter().map(|kind| LedEffect::from(kind, duration)))
+        .collect::<HashMap<String, LedEffect>>();
+
+    process_effect_mutation(
+        ctx,
+        EffectChangeType::LedGroup {
+            effects: Arc::new(effects),
+            transition,
+        },
+        Some(controllers),
+        start_at,
+        priority,
+    )
+    .await
+}
+
+async fn process_rumble_effect_mutation(
+    ctx: &Context,
     effect: RumbleEffect,
     target: Option<Vec<String>>,
+    start_at: Option<StartAtInput>,
+    priority: Option<EffectPriority>,
 ) -> FieldResult<MutationResponse> {
-    process_effect_mutation(ctx, EffectChangeType::Rumble { effect }, target)
+    process_effect_mutation(
+        ctx,
+        EffectChangeType::Rumble { effect },
+        target,
+        start_at,
+        priority,
+    )
+    .await
 }
 
-fn process_effect_mutation(
+/// Applies the effect right away, or schedules it if `start_at` is specified (in the future)
+async fn process_effect_mutation(
     ctx: &Context,
-    effect: EffectChangeType,
+    mut effect: EffectChangeType,
     controllers: Option<Vec<String>>,
+    start_at: Option<StartAtInput>,
+    priority: Option<EffectPriority>,
 ) -> FieldResult<MutationResponse> {
-    let target = match controller_to_effect_target(controllers) {
-        Ok(value) => value,
-        Err(value) => return value,
+    let target = controller_to_effect_target(controllers)?;
+    let start = start_at.as_ref().map(build_start).transpose()?;
+
+    // a start in the past begins the effects right away, but where they would be by now
+    if let Some(start) = start.filter(|start| *start <= Instant::now()) {
+        effect.start_at(start);
+    }
+
+    let change = EffectChange {
+        effect,
+        target,
+        priority: priority.unwrap_or_default(),
+        source: ctx.client.clone(),
+        automatic: false,
     };
 
-    match ctx.effect_tx.send(EffectChange { effect, target }) {
+    if let Some(start) = start.filter(|start| *start > Instant::now()) {
+        let id = ctx.scheduled_effects.lock().await.add(start, change);
+
+        tracing::info!("Scheduled effect {id}");
+        return Ok(MutationResponse::Success);
+    }
+
+    match ctx.effect_tx.send(change) {
         Ok(_) => Ok(MutationResponse::Success),
         Err(_) => Ok(MutationResponse::ServerError),
     }
 }
 
-fn controller_to_effect_target(
-    controllers: Option<Vec<String>>,
-) -> Result<EffectTarget, FieldResult<MutationResponse>> {
+fn build_led_effect_patch(input: &LedEffectPatchInput) -> FieldResult<LedEffectPatch> {
+    if input.hue.is_none()
+        && input.saturation.is_none()
+        && input.peak.is_none()
+        && input.interval.is_none()
+        && input.speed.is_none()
+    {
+        return Err(FieldError::new("You must specify a parameter!", Value::Null));
+    }
+
+    if input.hue.is_some_and(|hue| !(0..=360).contains(&hue)) {
+        return Err(FieldError::new("Hue must be between 0 and 360!", Value::Null));
+    }
+
+    if input.saturation.is_some_and(|saturation| !(0.0..=1.0).contains(&saturation)) {
+        return Err(FieldError::new(
+            "Saturation must be between 0.0 and 1.0!",
+            Value::Null,
+        ));
+    }
+
+    if input.peak.is_some_and(|peak| !(0.0..=1.0).contains(&peak)) {
+        return Err(FieldError::new("Peak must be between 0.0 and 1.0!", Value::Null));
+    }
+
+    if input.interval.is_some_and(|interval| interval < 1) {
+        return Err(FieldError::new("Interval must be at least 1!", Value::Null));
+    }
+
+    if input.speed.is_some_and(|speed| speed <= 0.0 || speed > 20.0) {
+        return Err(FieldError::new(
+            "Speed must be above 0.0 and equal or below 20.0!",
+            Value::Null,
+        ));
+    }
+
+    Ok(LedEffectPatch {
+        hue: input.hue.map(|hue| hue as f32),
+        saturation: input.saturation.map(|saturation| saturation as f32),
+        peak: input.peak.map(|peak| peak as f32),
+        interval: input
+            .interval
+            .map(|interval| Duration::from_millis(interval as u64)),
+        speed: input.speed.map(|speed| speed as f32),
+    })
+}
+
+fn build_start(input: &StartAtInput) -> FieldResult<Instant> {
+    match (input.time, input.delay) {
+        (Some(time), None) => {
+            if !time.is_finite() {
+                return Err(FieldError::new("Time must be a number!", Value::Null));
+            }
+
+            let time = UNIX_EPOCH + Duration::from_millis(time.max(0.0) as u64);
+            let now = Instant::now();
+
+            match time.duration_since(SystemTime::now()) {
+                Ok(delay) => now
+                    .checked_add(delay)
+                    .ok_or_else(|| FieldError::new("Start is too far in the future!", Value::Null)),
+                // clients sharing a time can be a bit late, they still start in sync
+                Err(err) if err.duration() <= MAX_PAST_START => {
+                    Ok(now.checked_sub(err.duration()).unwrap_or(now))
+                }
+                Err(_) => Err(FieldError::new(
+                    format!("Time can't be more than {}ms in the past!", MAX_PAST_START.as_millis()),
+                    Value::Null,
+                )),
+            }
+        }
+        (None, Some(delay)) => {
+            if delay < 0 {
+                return Err(FieldError::new("Delay must be positive!", Value::Null));
+            }
+
+            Instant::now()
+                .checked_add(Duration::from_millis(delay as u64))
+                .ok_or_else(|| FieldError::new("Start is too far in the future!", Value::Null))
+        }
+        _ => Err(FieldError::new(
+            "Exactly one of time or delay must be specified!",
+            Value::Null,
+        )),
+    }
+}
+
+fn controller_to_effect_target(controllers: Option<Vec<String>>) -> FieldResult<EffectTarget> {
     Ok(match controllers {
         None => EffectTarget::All,
         Some(bt_addresses) => {
             if bt_addresses.is_empty() {
-                return Err(Err(FieldError::new(
+                return Err(FieldError::new(
                     "You must specify controllers!",
                     Value::Null,
-                )));
+                ));
             } else {
                 EffectTarget::Only { bt_addresses }
             }
@@ -1,10 +1,30 @@
-use juniper::FieldResult;
+use std::time::{Duration, UNIX_EPOCH};
 
+use chrono::Local;
+use juniper::{FieldError, FieldResult, Value};
+use palette::{FromColor, Srgb};
+use tokio::time::Instant;
+
+use crate::EffectTarget;
+use crate::palettes::Palettes;
+use crate::schedules::ScheduleTime;
+use crate::settings::LifecycleEffect;
+use crate::schedules::sun::{self, SunTimes};
+use crate::graphql::effect_catalog::get_effect_catalog;
 use crate::graphql::schema::Context;
-use crate::graphql::schema_response::{Controller, HealthStatus};
+use crate::graphql::schema_input::PreviewEffectInput;
+use crate::graphql::schema_mutation::{build_led_effect, build_rumble_effect};
+use crate::ps_move::render;
+use crate::graphql::schema_response::{ChannelCalibration, Circadian, ColorOutputSettings, Controller, ControllerCalibration, HealthStatus, HistoryEntry, ScheduledEffect, SequenceStatus};
+
+/// Longest time `previewEffect` renders, so a query can't keep the server busy
+const MAX_PREVIEW_DURATION: Duration = Duration::from_secs(60);
+const MIN_PREVIEW_INTERVAL: Duration = Duration::from_millis(10);
+const DEFAULT_PREVIEW_INTERVAL: Duration = Duration::from_millis(50);
 
 mod api {
     pub use crate::ps_move::effects::*;
+    pub use crate::ps_move::output::ChannelCalibration;
 }
 
 mod graphql {
@@ -30,23 +50,410 @@ impl QueryRoot {
                 address: ctl.bt_address.clone(),
                 battery_level: ctl.battery,
                 connection_type: ctl.connection_type,
-                current_led_effect: match ctl.led_effect.kind {
-                    api::LedEffectKind::Off => { graphql::LedEffectType::Off }
-                    api::LedEffectKind::Static { .. } => { graphql::LedEffectType::Static }
-                    api::LedEffectKind::Breathing { .. } => { graphql::LedEffectType::Breathing }
-                    api::LedEffectKind::Rainbow { .. } => { graphql::LedEffectType::Rainbow }
-                    api::LedEffectKind::Blink { .. } => { graphql::LedEffectType::Blink }
-                    api::LedEffectKind::Candle { .. } => { graphql::LedEffectType::Candle }
-                    api::LedEffectKind::Bounce { .. } => { graphql::LedEffectType::Bounce }
-                },
-                current_rumble_effect: match ctl.rumble_effect.kind {
-                    api::RumbleEffectKind::Off => { graphql::RumbleEffectType::Off }
-                    api::RumbleEffectKind::Static { .. } => { graphql::RumbleEffectType::Static }
-                    api::RumbleEffectKind::Breathing { .. } => { graphql::RumbleEffectType::Breathing }
-                    api::RumbleEffectKind::Blink { .. } => { graphql::RumbleEffectType::Blink }
-                },
+                current_led_effect: to_led_effect_type(&ctl.led_effect.kind),
+                current_rumble_effect: to_rumble_effect_type(&ctl.rumble_effect.kind),
+                paused: ctl.paused,
+                effect_speed: ctl.speed as f64,
+                scene: ctl.scene.clone(),
+                led_priority: ctl.leds.priority,
+                rumble_priority: ctl.rumbles.priority,
+                current_sequence: ctl.sequence.as_ref().map(|player| SequenceStatus {
+                    name: player.sequence.name.clone(),
+                    item_index: player.index as i32,
+                    item_count: player.sequence.items.len() as i32,
+                    completed_loops: player.completed_loops as i32,
+                    item_remaining: player.item_remaining().as_millis() as i32,
+                }),
+                led_pwm_frequency: ctl.led_pwm_frequency.map(|frequency| frequency as i32),
             }
         })
             .collect())
     }
+
+    #[graphql(description = "Color output settings, with the calibration of each controller")]
+    async fn color_output(ctx: &Context) -> FieldResult<ColorOutputSettings> {
+        let settings = ctx.settings.lock().await;
+        let output = &settings.output;
+
+        let to_response = |channel: &api::ChannelCalibration| ChannelCalibration {
+            gain: channel.gain as f64,
+            offset: channel.offset as f64,
+        };
+
+        Ok(ColorOutputSettings {
+            gamma: output.gamma as f64,
+            brightness_cap: output.brightness_cap as f64,
+            theme_brightness: output.theme_brightness as f64,
+            dithering: output.dithering,
+            calibrations: output
+                .calibrations
+                .iter()
+                .map(|(address, calibration)| ControllerCalibration {
+                    address: address.clone(),
+                    red: to_response(&calibration.red),
+                    green: to_response(&calibration.green),
+                    blue: to_response(&calibration.blue),
+                })
+                .collect(),
+        })
+    }
+
+    #[graphql(description = "Lists the pending scheduled effects, by start (an effect given a future `startAt` is listed here)")]
+    async fn scheduled_effects(ctx: &Context) -> FieldResult<Vec<ScheduledEffect>> {
+        let scheduled_effects = ctx.scheduled_effects.lock().await;
+        let now = Instant::now();
+
+        Ok(scheduled_effects
+            .pending()
+            .iter()
+            .map(|scheduled| ScheduledEffect {
+                id: scheduled.id as i32,
+                effect: scheduled.change.effect.to_string(),
+                source: scheduled.change.source.clone(),
+                controllers: match &scheduled.change.target {
+                    EffectTarget::All => None,
+                    EffectTarget::Only { bt_addresses } => Some(bt_addresses.clone()),
+                },
+                time: scheduled
+                    .start_time
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0.0, |time| time.as_millis() as f64),
+                remaining: scheduled
+                    .start
+                    .saturating_duration_since(now)
+                    .as_millis()
+                    .min(i32::MAX as u128) as i32,
+            })
+            .collect())
+    }
+
+    #[graphql(description = "Lists the effects history of a controller, oldest first")]
+    async fn effect_history(ctx: &Context, controller: String) -> FieldResult<Vec<HistoryEntry>> {
+        let controllers = ctx.controllers.lock().await;
+        let controller = controllers
+            .iter()
+            .find(|ctl| ctl.bt_address == controller)
+            .ok_or_else(|| FieldError::new("No controller with this address!", Value::Null))?;
+        let current_id = controller.history.current_id();
+
+        Ok(controller
+            .history
+            .entries()
+            .iter()
+            .map(|entry| HistoryEntry {
+                id: entry.id as i32,
+                time: entry
+                    .time
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0.0, |time| time.as_millis() as f64),
+                source: entry.source.clone(),
+                priority: entry.priority,
+                effect: entry.effect.to_string(),
+                led: entry.affects_led(),
+                rumble: entry.affects_rumble(),
+                current: current_id == Some(entry.id),
+            })
+            .collect())
+    }
+
+    #[graphql(description = "Lists the recurring schedules")]
+    async fn schedules(ctx: &Context) -> FieldResult<Vec<graphql::Schedule>> {
+        let schedules = ctx.schedules.lock().await;
+
+        Ok(schedules
+            .schedules
+            .iter()
+            .map(|schedule| {
+                let (hour, minute, sun_event, offset_minutes) = match schedule.time {
+                    ScheduleTime::Clock { hour, minute } => {
+                        (Some(hour as i32), Some(minute as i32), None, None)
+                    }
+                    ScheduleTime::Sun {
+                        event,
+                        offset_minutes,
+                    } => (None, None, Some(event), Some(offset_minutes)),
+                };
+
+                graphql::Schedule {
+                    name: schedule.name.clone(),
+                    enabled: schedule.enabled,
+                    hour,
+                    minute,
+                    sun_event,
+                    offset_minutes,
+                    days: schedule.days.clone(),
+                    controllers: schedule.controllers.clone(),
+                    hue: schedule.hue as f64,
+                    saturation: schedule.saturation as f64,
+                    value: schedule.value as f64,
+                    transition_ms: schedule.transition_ms.map(|millis| millis as i32),
+                    duration: schedule.duration_ms.map(|millis| millis as i32),
+                }
+            })
+            .collect())
+    }
+
+    #[graphql(description = "Circadian profile, with the location and today's sunrise/sunset")]
+    async fn circadian(ctx: &Context) -> FieldResult<Circadian> {
+        let schedules = ctx.schedules.lock().await;
+        let circadian = &schedules.circadian;
+
+        let (sunrise, sunset) = match schedules
+            .location
+            .map(|location| sun::get_sun_times(Local::now().date_naive(), location))
+        {
+            Some(SunTimes::Regular { sunrise, sunset }) => (
+                Some(sunrise.timestamp_millis() as f64),
+                Some(sunset.timestamp_millis() as f64),
+            ),
+            _ => (None, None),
+        };
+
+        Ok(Circadian {
+            enabled: circadian.enabled,
+            controllers: circadian.controllers.clone(),
+            day_kelvin: circadian.day_kelvin as f64,
+            night_kelvin: circadian.night_kelvin as f64,
+            day_brightness: circadian.day_brightness as f64,
+            night_brightness: circadian.night_brightness as f64,
+            twilight_minutes: circadian.twilight_minutes as i32,
+            latitude: schedules.location.map(|location| location.latitude),
+            longitude: schedules.location.map(|location| location.longitude),
+            sunrise,
+            sunset,
+        })
+    }
+
+    #[graphql(description = "Lists the built-in and user-defined palettes")]
+    async fn palettes(ctx: &Context) -> FieldResult<Vec<graphql::Palette>> {
+        let palettes = ctx.palettes.lock().await;
+
+        Ok(palettes
+            .all()
+            .map(|palette| graphql::Palette {
+                name: palette.name.clone(),
+                built_in: Palettes::is_built_in(&palette.name),
+                colors: palette
+                    .colors
+                    .iter()
+                    .map(|color| {
+                        let rgb = Srgb::from_color(color.to_hsv()).into_format::<u8>();
+
+                        graphql::PaletteColor {
+                            hue: color.hue as f64,
+                            saturation: color.saturation as f64,
+                            value: color.value as f64,
+                            hex: format!("#{rgb:x}"),
+                        }
+                    })
+                    .collect(),
+            })
+            .collect())
+    }
+
+    #[graphql(description = "Lists the themes")]
+    async fn themes(ctx: &Context) -> FieldResult<Vec<graphql::Theme>> {
+        let palettes = ctx.palettes.lock().await;
+
+        Ok(palettes
+            .themes
+            .iter()
+            .map(|theme| graphql::Theme {
+                name: theme.name.clone(),
+                palette: theme.palette.clone(),
+                brightness: theme.brightness as f64,
+                active: palettes.active_theme.as_ref() == Some(&theme.name),
+            })
+            .collect())
+    }
+
+    #[graphql(description = "Lists the saved scenes")]
+    async fn scenes(ctx: &Context) -> FieldResult<Vec<graphql::Scene>> {
+        let scenes = ctx.scenes.lock().await;
+
+        scenes
+            .scenes
+            .iter()
+            .map(|scene| {
+                Ok(graphql::Scene {
+                    name: scene.name.clone(),
+                    controllers: scene
+                        .controllers
+                        .iter()
+                        .map(|controller| {
+                            Ok(graphql::SceneController {
+                                address: controller.address.clone(),
+                                led_effect: to_led_effect_type(&to_kind(controller.led.to_kind())?),
+                                rumble_effect: to_rumble_effect_type(&to_kind(controller.rumble.to_kind())?),
+                            })
+                        })
+                        .collect::<FieldResult<_>>()?,
+                })
+            })
+            .collect()
+    }
+
+    #[graphql(description = "Lists the rules")]
+    async fn rules(ctx: &Context) -> FieldResult<Vec<graphql::Rule>> {
+        let rules = ctx.rules.lock().await;
+
+        Ok(rules
+            .rules
+            .iter()
+            .map(|rule| {
+                let from = rule.time_window.map(|window| window.from_minute as i32);
+                let to = rule.time_window.map(|window| window.to_minute as i32);
+
+                graphql::Rule {
+                    name: rule.name.clone(),
+                    enabled: rule.enabled,
+                    event: rule.event.to_string(),
+                    controllers: rule.controllers.clone(),
+                    from_hour: from.map(|minute| minute / 60),
+                    from_minute: from.map(|minute| minute % 60),
+                    to_hour: to.map(|minute| minute / 60),
+                    to_minute: to.map(|minute| minute % 60),
+                    days: rule.days.clone(),
+                    scene: rule.scene.clone(),
+                    priority: rule.priority,
+                    actions: rule.actions.iter().map(|action| action.to_string()).collect(),
+                }
+            })
+            .collect())
+    }
+
+    #[graphql(description = "Lists the analog bindings")]
+    async fn bindings(ctx: &Context) -> FieldResult<Vec<graphql::Binding>> {
+        let bindings = ctx.bindings.lock().await;
+
+        Ok(bindings
+            .bindings
+            .iter()
+            .map(|binding| graphql::Binding {
+                name: binding.name.clone(),
+                enabled: binding.enabled,
+                source: binding.source,
+                controller: binding.controller.clone(),
+                targets: binding.targets.clone(),
+                parameter: binding.parameter,
+                curve: binding.curve,
+                input_min: binding.input_min as f64,
+                input_max: binding.input_max as f64,
+                output_min: binding.output_min as f64,
+                output_max: binding.output_max as f64,
+            })
+            .collect())
+    }
+
+    #[graphql(description = "Led effects shown on startup and when controllers connect")]
+    async fn lifecycle_effects(ctx: &Context) -> FieldResult<graphql::LifecycleEffects> {
+        let settings = ctx.settings.lock().await;
+
+        Ok(graphql::LifecycleEffects {
+            startup: settings.startup_effect.as_ref().map(to_lifecycle_effect).transpose()?,
+            connect: settings.connect_effect.as_ref().map(to_lifecycle_effect).transpose()?,
+            controllers: settings
+                .controller_connect_effects
+                .iter()
+                .map(|(address, effect)| {
+                    Ok(graphql::ControllerConnectEffect {
+                        address: address.clone(),
+                        effect: to_lifecycle_effect(effect)?,
+                    })
+                })
+                .collect::<FieldResult<_>>()?,
+        })
+    }
+
+    #[graphql(description = "Lists the led and rumble effects with their parameters, to apply with `applyEffect`")]
+    fn effect_catalog(_ctx: &Context) -> FieldResult<Vec<graphql::EffectDescription>> {
+        Ok(get_effect_catalog().to_vec())
+    }
+
+    #[graphql(description = "Renders effects without any controller, as frames sampled over time")]
+    async fn preview_effect(ctx: &Context, input: PreviewEffectInput) -> FieldResult<Vec<graphql::EffectFrame>> {
+        let led = match &input.led {
+            Some(led) => build_led_effect(led, &*ctx.palettes.lock().await)?,
+            None => api::LedEffectKind::Off,
+        };
+        let rumble = match &input.rumble {
+            Some(rumble) => build_rumble_effect(rumble)?,
+            None => api::RumbleEffectKind::Off,
+        };
+        let duration = Duration::from_millis(input.duration.max(0) as u64);
+        let interval = input
+            .interval
+            .map_or(DEFAULT_PREVIEW_INTERVAL, |millis| Duration::from_millis(millis.max(0) as u64));
+
+        if input.duration < 0 || duration > MAX_PREVIEW_DURATION {
+            return Err(FieldError::new("Duration must be between 0 and 60000!", Value::Null));
+        }
+        if interval < MIN_PREVIEW_INTERVAL {
+            return Err(FieldError::new("Interval must be at least 10!", Value::Null));
+        }
+        if input.seed.filter(|seed| *seed < 0).is_some() {
+            return Err(FieldError::new("Seed must be positive!", Value::Null));
+        }
+
+        let seed = input.seed.unwrap_or(0) as u64;
+
+        Ok(render::render(&led, &rumble, duration, interval, seed)
+            .into_iter()
+            .map(|frame| {
+                let rgb = Srgb::from_color(frame.hsv).into_format::<u8>();
+
+                graphql::EffectFrame {
+                    time: frame.time.as_millis() as i32,
+                    hue: frame.hsv.hue.into_positive_degrees() as f64,
+                    saturation: frame.hsv.saturation as f64,
+                    value: frame.hsv.value as f64,
+                    hex: format!("#{rgb:x}"),
+                    rumble: frame.rumble as f64,
+                }
+            })
+            .collect())
+    }
+}
+
+fn to_lifecycle_effect(effect: &LifecycleEffect) -> FieldResult<graphql::LifecycleEffect> {
+    Ok(graphql::LifecycleEffect {
+        led_effect: to_led_effect_type(&to_kind(effect.led.to_kind())?),
+        duration: effect.duration_ms.map(|millis| millis as i32),
+    })
+}
+
+fn to_kind<T>(kind: Result<T, String>) -> FieldResult<T> {
+    kind.map_err(|err| FieldError::new(format!("Invalid saved effect! ({err})"), Value::Null))
+}
+
+fn to_led_effect_type(kind: &api::LedEffectKind) -> graphql::LedEffectType {
+    match kind {
+        api::LedEffectKind::Off => { graphql::LedEffectType::Off }
+        api::LedEffectKind::Static { .. } => { graphql::LedEffectType::Static }
+        api::LedEffectKind::Breathing { .. } => { graphql::LedEffectType::Breathing }
+        api::LedEffectKind::Rainbow { .. } => { graphql::LedEffectType::Rainbow }
+        api::LedEffectKind::Blink { .. } => { graphql::LedEffectType::Blink }
+        api::LedEffectKind::Candle { .. } => { graphql::LedEffectType::Candle }
+        api::LedEffectKind::Bounce { .. } => { graphql::LedEffectType::Bounce }
+        api::LedEffectKind::Wave { .. } => { graphql::LedEffectType::Wave }
+        api::LedEffectKind::Chase { .. } => { graphql::LedEffectType::Chase }
+        api::LedEffectKind::Fire { .. } => { graphql::LedEffectType::Fire }
+        api::LedEffectKind::Lightning { .. } => { graphql::LedEffectType::Lightning }
+        api::LedEffectKind::Heartbeat { .. } => { graphql::LedEffectType::Heartbeat }
+        api::LedEffectKind::Strobe { .. } => { graphql::LedEffectType::Strobe }
+        api::LedEffectKind::Police { .. } => { graphql::LedEffectType::Police }
+        api::LedEffectKind::Sparkle { .. } => { graphql::LedEffectType::Sparkle }
+        api::LedEffectKind::Noise { .. } => { graphql::LedEffectType::Noise }
+        api::LedEffectKind::Circadian { .. } => { graphql::LedEffectType::Circadian }
+    }
+}
+
+fn to_rumble_effect_type(kind: &api::RumbleEffectKind) -> graphql::RumbleEffectType {
+    match kind {
+        api::RumbleEffectKind::Off => { graphql::RumbleEffectType::Off }
+        api::RumbleEffectKind::Static { .. } => { graphql::RumbleEffectType::Static }
+        api::RumbleEffectKind::Breathing { .. } => { graphql::RumbleEffectType::Breathing }
+        api::RumbleEffectKind::Blink { .. } => { graphql::RumbleEffectType::Blink }
+        api::RumbleEffectKind::FollowLed { .. } => { graphql::RumbleEffectType::FollowLed }
+    }
 }
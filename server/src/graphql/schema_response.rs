@@ -1,6 +1,9 @@
 use juniper::{GraphQLEnum, GraphQLObject};
 
+use crate::bindings::{AnalogSource, BindingParameter, ResponseCurve};
 use crate::ps_move::models::{BatteryLevel, ConnectionType};
+use crate::schedules::{SunEvent, Weekday};
+use crate::tasks::models::EffectPriority;
 
 #[derive(GraphQLEnum)]
 pub enum HealthStatus {
@@ -21,6 +24,59 @@ pub struct Controller {
     pub connection_type: ConnectionType,
     pub(super) current_led_effect: LedEffectType,
     pub(super) current_rumble_effect: RumbleEffectType,
+    pub(super) paused: bool,
+    #[graphql(description = "How fast the effects run, 1.0 being real time.")]
+    pub(super) effect_speed: f64,
+    #[graphql(description = "Scene the controller shows, until another effect replaces it.")]
+    pub(super) scene: Option<String>,
+    pub(super) led_priority: EffectPriority,
+    pub(super) rumble_priority: EffectPriority,
+    pub(super) current_sequence: Option<SequenceStatus>,
+    #[graphql(description = "Led PWM frequency in Hz, if it was set since the controller connected.")]
+    pub(super) led_pwm_frequency: Option<i32>,
+}
+
+#[derive(GraphQLObject)]
+pub(super) struct SequenceStatus {
+    pub name: Option<String>,
+    #[graphql(description = "Index of the item currently playing. (starts at 0)")]
+    pub item_index: i32,
+    pub item_count: i32,
+    pub completed_loops: i32,
+    #[graphql(description = "Time left for the current item, in milliseconds.")]
+    pub item_remaining: i32,
+}
+
+#[derive(GraphQLObject)]
+pub(super) struct ScheduledEffect {
+    #[graphql(description = "To cancel it with `cancelScheduledEffect`.")]
+    pub id: i32,
+    pub effect: String,
+    #[graphql(description = "Who scheduled the effect, a client name or address.")]
+    pub source: String,
+    #[graphql(description = "Targeted controller addresses, all if not specified.")]
+    pub controllers: Option<Vec<String>>,
+    #[graphql(description = "Wall-clock start, in milliseconds since the Unix epoch.")]
+    pub time: f64,
+    #[graphql(description = "Time left until the start, in milliseconds.")]
+    pub remaining: i32,
+}
+
+#[derive(GraphQLObject)]
+pub(super) struct HistoryEntry {
+    pub id: i32,
+    #[graphql(description = "When the effect was set, in milliseconds since the Unix epoch.")]
+    pub time: f64,
+    #[graphql(description = "Who set the effect, a client name or address, a schedule...")]
+    pub source: String,
+    pub priority: EffectPriority,
+    pub effect: String,
+    #[graphql(description = "Whether the effect changed the led.")]
+    pub led: bool,
+    #[graphql(description = "Whether the effect changed the rumble.")]
+    pub rumble: bool,
+    #[graphql(description = "Whether the controller is at this entry (nothing after it was applied, or it was undone).")]
+    pub current: bool,
 }
 
 #[derive(GraphQLEnum)]
@@ -32,6 +88,16 @@ pub(super) enum LedEffectType {
     Blink,
     Candle,
     Bounce,
+    Wave,
+    Chase,
+    Fire,
+    Lightning,
+    Heartbeat,
+    Strobe,
+    Police,
+    Sparkle,
+    Noise,
+    Circadian,
 }
 
 #[derive(GraphQLEnum)]
@@ -39,5 +105,232 @@ pub(super) enum RumbleEffectType {
     Off,
     Static,
     Breathing,
-    Blink
+    Blink,
+    FollowLed,
+}
+
+#[derive(GraphQLObject)]
+pub(super) struct ColorOutputSettings {
+    pub gamma: f64,
+    pub brightness_cap: f64,
+    #[graphql(description = "From the active theme.")]
+    pub theme_brightness: f64,
+    pub dithering: bool,
+    pub calibrations: Vec<ControllerCalibration>,
+}
+
+#[derive(GraphQLObject)]
+pub(super) struct ControllerCalibration {
+    pub address: String,
+    pub red: ChannelCalibration,
+    pub green: ChannelCalibration,
+    pub blue: ChannelCalibration,
+}
+
+#[derive(GraphQLObject)]
+pub(super) struct ChannelCalibration {
+    pub gain: f64,
+    pub offset: f64,
+}
+
+#[derive(GraphQLObject)]
+pub(super) struct Schedule {
+    pub name: String,
+    pub enabled: bool,
+    pub hour: Option<i32>,
+    pub minute: Option<i32>,
+    pub sun_event: Option<SunEvent>,
+    pub offset_minutes: Option<i32>,
+    #[graphql(description = "Every day if empty.")]
+    pub days: Vec<Weekday>,
+    #[graphql(description = "Targeted controller addresses, all if not specified.")]
+    pub controllers: Option<Vec<String>>,
+    pub hue: f64,
+    pub saturation: f64,
+    pub value: f64,
+    pub transition_ms: Option<i32>,
+    pub duration: Option<i32>,
+}
+
+#[derive(GraphQLObject)]
+pub(super) struct Circadian {
+    pub enabled: bool,
+    #[graphql(description = "Targeted controller addresses, all if not specified.")]
+    pub controllers: Option<Vec<String>>,
+    pub day_kelvin: f64,
+    pub night_kelvin: f64,
+    pub day_brightness: f64,
+    pub night_brightness: f64,
+    pub twilight_minutes: i32,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    #[graphql(description = "Today's sunrise, in milliseconds since the Unix epoch, if the sun rises.")]
+    pub sunrise: Option<f64>,
+    #[graphql(description = "Today's sunset, in milliseconds since the Unix epoch, if the sun sets.")]
+    pub sunset: Option<f64>,
+}
+
+#[derive(GraphQLObject)]
+pub(super) struct Palette {
+    pub name: String,
+    #[graphql(description = "Built-in palettes can't be changed nor deleted.")]
+    pub built_in: bool,
+    pub colors: Vec<PaletteColor>,
+}
+
+#[derive(GraphQLObject)]
+pub(super) struct Scene {
+    pub name: String,
+    pub controllers: Vec<SceneController>,
+}
+
+#[derive(GraphQLObject)]
+pub(super) struct SceneController {
+    pub address: String,
+    pub led_effect: LedEffectType,
+    pub rumble_effect: RumbleEffectType,
+}
+
+#[derive(GraphQLObject)]
+pub(super) struct Rule {
+    pub name: String,
+    pub enabled: bool,
+    #[graphql(description = "Event the rule reacts to, like \"Cross Press\".")]
+    pub event: String,
+    #[graphql(description = "Controller addresses it reacts to, all if not specified.")]
+    pub controllers: Option<Vec<String>>,
+    pub from_hour: Option<i32>,
+    pub from_minute: Option<i32>,
+    pub to_hour: Option<i32>,
+    pub to_minute: Option<i32>,
+    #[graphql(description = "Every day if empty.")]
+    pub days: Vec<Weekday>,
+    pub scene: Option<String>,
+    pub priority: EffectPriority,
+    #[graphql(description = "Actions run in order, like \"Scene 'lamp'\".")]
+    pub actions: Vec<String>,
+}
+
+#[derive(GraphQLObject)]
+pub(super) struct Binding {
+    pub name: String,
+    pub enabled: bool,
+    pub source: AnalogSource,
+    #[graphql(description = "Controller the source is read from, each one drives its own effects if not specified.")]
+    pub controller: Option<String>,
+    #[graphql(description = "Controllers whose effects are driven, the one of the source if not specified.")]
+    pub targets: Option<Vec<String>>,
+    pub parameter: BindingParameter,
+    pub curve: ResponseCurve,
+    pub input_min: f64,
+    pub input_max: f64,
+    pub output_min: f64,
+    pub output_max: f64,
+}
+
+#[derive(GraphQLObject)]
+pub(super) struct LifecycleEffects {
+    #[graphql(description = "Shown when the server starts, none if not specified.")]
+    pub startup: Option<LifecycleEffect>,
+    #[graphql(description = "Shown when a controller connects while there's no other effect, none if not specified.")]
+    pub connect: Option<LifecycleEffect>,
+    #[graphql(description = "Shown when these controllers connect, instead of any other effect.")]
+    pub controllers: Vec<ControllerConnectEffect>,
+}
+
+#[derive(GraphQLObject)]
+pub(super) struct LifecycleEffect {
+    pub led_effect: LedEffectType,
+    #[graphql(description = "In milliseconds, never expires if not specified.")]
+    pub duration: Option<i32>,
+}
+
+#[derive(GraphQLObject)]
+pub(super) struct ControllerConnectEffect {
+    pub address: String,
+    pub effect: LifecycleEffect,
+}
+
+#[derive(GraphQLObject)]
+pub(super) struct PaletteColor {
+    pub hue: f64,
+    pub saturation: f64,
+    pub value: f64,
+    #[graphql(description = "Hex RGB, like \"#ff8800\".")]
+    pub hex: String,
+}
+
+#[derive(GraphQLObject)]
+pub(super) struct Theme {
+    pub name: String,
+    pub palette: String,
+    pub brightness: f64,
+    pub active: bool,
+}
+
+#[derive(GraphQLObject)]
+pub(super) struct EffectFrame {
+    #[graphql(description = "Time since the effects started, in milliseconds.")]
+    pub time: i32,
+    pub hue: f64,
+    pub saturation: f64,
+    pub value: f64,
+    #[graphql(description = "Hex RGB, like \"#ff8800\".")]
+    pub hex: String,
+    #[graphql(description = "Strength of the rumble, from 0.0 to 1.0.")]
+    pub rumble: f64,
+}
+
+#[derive(GraphQLEnum, PartialEq, Copy, Clone, Debug)]
+pub(super) enum EffectCategory {
+    Led,
+    Rumble,
+}
+
+#[derive(GraphQLEnum, PartialEq, Copy, Clone, Debug)]
+pub(super) enum EffectParameterType {
+    #[graphql(description = "Set with `number`.")]
+    Float,
+    #[graphql(description = "Set with `number`, without decimals.")]
+    Int,
+    #[graphql(description = "Set with `numbers`, without decimals.")]
+    Ints,
+    #[graphql(description = "Set with `color`.")]
+    Color,
+    #[graphql(description = "Set with `colors`.")]
+    Colors,
+    #[graphql(description = "Set with `text`, like the name of a palette.")]
+    Text,
+    #[graphql(description = "Set with `text`, one of the `options`.")]
+    Choice,
+}
+
+#[derive(GraphQLObject, Clone, Debug)]
+pub(super) struct EffectParameter {
+    pub name: String,
+    #[graphql(name = "type")]
+    pub type_: EffectParameterType,
+    pub required: bool,
+    #[graphql(description = "Parameters specified instead of this one, it's then not required.")]
+    pub alternatives: Option<Vec<String>>,
+    #[graphql(description = "Lowest value of a number. (included)")]
+    pub min: Option<f64>,
+    #[graphql(description = "Highest value of a number. (included)")]
+    pub max: Option<f64>,
+    #[graphql(description = "Value of a number when not specified.")]
+    pub default: Option<f64>,
+    #[graphql(description = "Values of a choice.")]
+    pub options: Option<Vec<String>>,
+    pub description: String,
+}
+
+#[derive(GraphQLObject, Clone, Debug)]
+pub(super) struct EffectDescription {
+    #[graphql(description = "Kind of the effect, to apply it with `applyEffect`.")]
+    pub kind: String,
+    pub category: EffectCategory,
+    pub description: String,
+    #[graphql(description = "Whether the effect spans the controllers, like a wave travelling across them.")]
+    pub group: bool,
+    pub parameters: Vec<EffectParameter>,
 }
@@ -6,13 +6,28 @@ use tokio::sync::Mutex;
 use graphql::graphql_api;
 use ps_move::effects::LedEffectKind;
 
+use crate::bindings::Bindings;
+use crate::palettes::Palettes;
+use crate::rules::Rules;
+use crate::scenes::Scenes;
 use crate::ps_move::controller::PsMoveController;
 use crate::ps_move::models::ButtonState;
+use crate::schedules::Schedules;
+use crate::settings::Settings;
+use crate::tasks::scheduler::ScheduledEffects;
 use crate::tasks::models::*;
 use crate::tracing_setup::setup_loki;
 
+mod bindings;
+mod cli;
 mod graphql;
+mod palettes;
+mod persistence;
 mod ps_move;
+mod rules;
+mod scenes;
+mod schedules;
+mod settings;
 mod spawn_tasks;
 mod tasks;
 mod tracing_setup;
@@ -20,6 +35,14 @@ mod monitoring;
 
 #[tokio::main]
 async fn main() {
+    if let Some(result) = cli::run(&std::env::args().skip(1).collect::<Vec<_>>()) {
+        if let Err(err) = result {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     setup_loki().await;
 
     let (effect_tx, effect_rx) = broadcast::channel(32);
@@ -28,9 +51,41 @@ async fn main() {
         &ButtonState::Released,
     ));
     let controllers = Arc::new(Mutex::new(Vec::<PsMoveController>::new()));
+    let settings = Arc::new(Mutex::new(Settings::load()));
+    let scheduled_effects = Arc::new(Mutex::new(ScheduledEffects::default()));
+    let schedules = Arc::new(Mutex::new(Schedules::load()));
+    let palettes = Arc::new(Mutex::new(Palettes::load()));
+    let scenes = Arc::new(Mutex::new(Scenes::load()));
+    let rules = Arc::new(Mutex::new(Rules::load()));
+    let bindings = Arc::new(Mutex::new(Bindings::load()));
+    let effect_tx = Arc::new(effect_tx);
 
-    let mut shutdown_command = spawn_tasks::run_move(effect_rx, ctrl_tx, &controllers).await;
-    graphql_api::start(Arc::new(effect_tx), Mutex::new(ctrl_rx), controllers).await;
+    let mut shutdown_command = spawn_tasks::run_move(
+        effect_rx,
+        ctrl_tx,
+        &controllers,
+        &settings,
+        &effect_tx,
+        &scheduled_effects,
+        &schedules,
+        &scenes,
+        &rules,
+        &bindings,
+    )
+    .await;
+    graphql_api::start(
+        effect_tx,
+        Mutex::new(ctrl_rx),
+        controllers,
+        settings,
+        scheduled_effects,
+        schedules,
+        palettes,
+        scenes,
+        rules,
+        bindings,
+    )
+    .await;
 
     tracing::info!("Shutting down...");
     shutdown_command.shutdown().await
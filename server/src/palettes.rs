@@ -0,0 +1,123 @@
+use lazy_static::lazy_static;
+use palette::Hsv;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::persistence;
+
+const PALETTES_FILE: &str = "palettes.json";
+
+lazy_static! {
+    static ref BUILT_IN_PALETTES: Vec<Palette> = vec![
+        Palette::new(
+            "ocean",
+            &[(190.0, 1.0, 1.0), (210.0, 0.9, 1.0), (175.0, 0.8, 0.9), (230.0, 0.7, 0.8)],
+        ),
+        Palette::new(
+            "fire",
+            &[(0.0, 1.0, 1.0), (15.0, 1.0, 1.0), (30.0, 1.0, 1.0), (45.0, 1.0, 1.0)],
+        ),
+        Palette::new(
+            "pastel",
+            &[
+                (330.0, 0.35, 1.0),
+                (200.0, 0.35, 1.0),
+                (140.0, 0.35, 1.0),
+                (50.0, 0.35, 1.0),
+                (270.0, 0.35, 1.0),
+            ],
+        ),
+    ];
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Copy, Clone, Debug)]
+pub struct PaletteColor {
+    pub hue: f32,
+    pub saturation: f32,
+    pub value: f32,
+}
+
+impl PaletteColor {
+    pub fn from(hsv: Hsv) -> PaletteColor {
+        PaletteColor {
+            hue: hsv.hue.into_positive_degrees(),
+            saturation: hsv.saturation,
+            value: hsv.value,
+        }
+    }
+
+    pub fn to_hsv(self) -> Hsv {
+        Hsv::new(self.hue, self.saturation, self.value)
+    }
+}
+
+/// A named list of colors
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Palette {
+    pub name: String,
+    pub colors: Vec<PaletteColor>,
+}
+
+impl Palette {
+    fn new(name: &str, colors: &[(f32, f32, f32)]) -> Palette {
+        Palette {
+            name: String::from(name),
+            colors: colors
+                .iter()
+                .map(|(hue, saturation, value)| PaletteColor {
+                    hue: *hue,
+                    saturation: *saturation,
+                    value: *value,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn to_hsv(&self) -> Vec<Hsv> {
+        self.colors.iter().map(|color| color.to_hsv()).collect()
+    }
+}
+
+/// A palette plus brightness, activating it re-colors every running effect
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Theme {
+    pub name: String,
+    pub palette: String,
+    /// Multiplies the brightness of every controller
+    pub brightness: f32,
+}
+
+/// User-defined palettes and themes (the built-in palettes aren't persisted)
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct Palettes {
+    pub palettes: Vec<Palette>,
+    pub themes: Vec<Theme>,
+    pub active_theme: Option<String>,
+}
+
+impl Palettes {
+    pub fn load() -> Palettes {
+        persistence::load(PALETTES_FILE)
+    }
+
+    pub fn save(&self) {
+        persistence::save(PALETTES_FILE, self)
+    }
+
+    pub fn is_built_in(name: &str) -> bool {
+        BUILT_IN_PALETTES.iter().any(|palette| palette.name == name)
+    }
+
+    /// Built-in palettes first, then the user-defined ones
+    pub fn all(&self) -> impl Iterator<Item = &Palette> {
+        BUILT_IN_PALETTES.iter().chain(self.palettes.iter())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Palette> {
+        self.all().find(|palette| palette.name == name)
+    }
+
+    pub fn get_theme(&self, name: &str) -> Option<&Theme> {
+        self.themes.iter().find(|theme| theme.name == name)
+    }
+}
@@ -0,0 +1,47 @@
+use std::fs;
+use std::path::PathBuf;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Relative to the working directory (ignored by git)
+const CONFIG_DIR: &str = "config";
+
+/// Loads `file_name` from the config folder, falling back to the default if it doesn't exist
+/// or is invalid
+pub fn load<T: DeserializeOwned + Default>(file_name: &str) -> T {
+    let path = get_path(file_name);
+
+    match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|err| {
+            tracing::error!("Couldn't parse '{}', using defaults. Caused by {err}", path.display());
+            T::default()
+        }),
+        Err(_) => {
+            tracing::debug!("No '{}' found, using defaults.", path.display());
+            T::default()
+        }
+    }
+}
+
+pub fn save<T: Serialize>(file_name: &str, value: &T) {
+    let path = get_path(file_name);
+
+    if let Err(err) = fs::create_dir_all(CONFIG_DIR) {
+        tracing::error!("Couldn't create config folder. Caused by {err}");
+        return;
+    }
+
+    match serde_json::to_string_pretty(value) {
+        Ok(content) => {
+            if let Err(err) = fs::write(&path, content) {
+                tracing::error!("Couldn't save '{}'. Caused by {err}", path.display());
+            }
+        }
+        Err(err) => tracing::error!("Couldn't serialize '{}'. Caused by {err}", path.display()),
+    }
+}
+
+fn get_path(file_name: &str) -> PathBuf {
+    PathBuf::from(CONFIG_DIR).join(file_name)
+}
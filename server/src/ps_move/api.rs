@@ -40,10 +40,8 @@ impl PsMoveApi {
     }
 
     pub fn refresh(&mut self) {
-        let result = self.hid.refresh_devices();
-
-        if result.is_err() {
-            tracing::error!("Failed to refresh devices {}", result.unwrap_err());
+        if let Err(err) = self.hid.refresh_devices() {
+            tracing::error!("Failed to refresh devices {err}");
         }
     }
 
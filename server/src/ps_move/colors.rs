@@ -0,0 +1,102 @@
+use juniper::GraphQLEnum;
+use palette::{FromColor, Hsv, LinSrgb, Mix, Oklab, Oklch, Srgb};
+use serde_derive::{Deserialize, Serialize};
+use strum_macros::Display;
+
+/// Below this, a color is considered to have no hue
+const ACHROMATIC_THRESHOLD: f32 = 0.0001;
+
+pub const MIN_KELVIN: f32 = 1000.0;
+pub const MAX_KELVIN: f32 = 40000.0;
+
+/// Color space used to interpolate between two colors
+#[derive(GraphQLEnum, Serialize, Deserialize, Display, Default, PartialEq, Copy, Clone, Debug)]
+pub enum ColorSpace {
+    #[graphql(description = "Goes around the hue wheel through the shortest side.")]
+    Hsv,
+    #[graphql(description = "Physically linear light, no hue shifts.")]
+    LinearRgb,
+    #[graphql(description = "Perceptually uniform, no hue shifts. (the default)")]
+    #[default]
+    Oklab,
+    #[graphql(description = "Perceptually uniform, going around the hue wheel through the shortest side.")]
+    Oklch,
+}
+
+impl ColorSpace {
+    /// Interpolates `from` into `to`, `factor` being the progress (0.0 is `from`, 1.0 is `to`)
+    pub fn mix(self, from: Hsv, to: Hsv, factor: f32) -> Hsv {
+        let factor = factor.clamp(0.0, 1.0);
+
+        match self {
+            ColorSpace::Hsv => {
+                let (from, to) = (with_hue_of(from, to), with_hue_of(to, from));
+
+                from.mix(to, factor)
+            }
+            ColorSpace::LinearRgb => {
+                let from: LinSrgb = Srgb::from_color(from).into_linear();
+                let to: LinSrgb = Srgb::from_color(to).into_linear();
+
+                Hsv::from_color(Srgb::from_linear(from.mix(to, factor)))
+            }
+            ColorSpace::Oklab => {
+                Hsv::from_color(Oklab::from_color(from).mix(Oklab::from_color(to), factor))
+            }
+            ColorSpace::Oklch => {
+                let mut from = Oklch::from_color(from);
+                let mut to = Oklch::from_color(to);
+
+                // same as with HSV, a grey/black has a meaningless hue
+                if from.chroma < ACHROMATIC_THRESHOLD {
+                    from.hue = to.hue;
+                } else if to.chroma < ACHROMATIC_THRESHOLD {
+                    to.hue = from.hue;
+                }
+
+                Hsv::from_color(from.mix(to, factor))
+            }
+        }
+    }
+}
+
+/// Takes the hue of `other` when `hsv` has none (e.g. fading from off into red shouldn't go
+/// through every hue between 0 and red)
+fn with_hue_of(hsv: Hsv, other: Hsv) -> Hsv {
+    if hsv.saturation < ACHROMATIC_THRESHOLD || hsv.value < ACHROMATIC_THRESHOLD {
+        Hsv::new(other.hue, hsv.saturation, hsv.value)
+    } else {
+        hsv
+    }
+}
+
+/// Approximates the color of a black body at `kelvin` (between 1000K and 40000K),
+/// with `value` as brightness
+pub fn kelvin_to_hsv(kelvin: f32, value: f32) -> Hsv {
+    let temperature = kelvin.clamp(MIN_KELVIN, MAX_KELVIN) / 100.0;
+
+    let red = if temperature <= 66.0 {
+        255.0
+    } else {
+        329.69873 * (temperature - 60.0).powf(-0.13320476)
+    };
+
+    let green = if temperature <= 66.0 {
+        99.4708 * temperature.ln() - 161.11957
+    } else {
+        288.12216 * (temperature - 60.0).powf(-0.07551485)
+    };
+
+    let blue = if temperature >= 66.0 {
+        255.0
+    } else if temperature <= 19.0 {
+        0.0
+    } else {
+        138.51773 * (temperature - 10.0).ln() - 305.0448
+    };
+
+    let normalize = |channel: f32| (channel / 255.0).clamp(0.0, 1.0);
+    let hsv = Hsv::from_color(Srgb::new(normalize(red), normalize(green), normalize(blue)));
+
+    Hsv::new(hsv.hue, hsv.saturation, value)
+}
@@ -1,16 +1,47 @@
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 
 use hidapi::{HidDevice, HidError};
-use palette::{FromColor, Hsv, Srgb};
+use palette::{Hsv, RgbHue};
+use rand::thread_rng;
+use tokio::time::Instant;
 use tracing::info;
 
-use crate::ps_move::effects::{LedEffect, RumbleEffect, RumbleEffectKind};
+use crate::ps_move::effects::{LedEffect, LedEffectKind, LedEffectPatch, LedTransition, RumbleEffect, RumbleEffectKind, TimeShift, Transition, UpdateProgress};
+use crate::ps_move::history::EffectHistory;
 use crate::ps_move::models::{BatteryLevel, ButtonState, ConnectionType, ControllerInfo, DataInput, fill_state_from_byte_slice, MoveRequestType, MoveSetting};
 use crate::ps_move::models::BatteryLevel::Unknown;
-use crate::tasks::models::Button;
+use crate::ps_move::output::{ColorOutput, TemporalDither};
+use crate::ps_move::priorities::PriorityStack;
+use crate::ps_move::sequence::{EffectSequence, SequencePlayer};
+use crate::tasks::models::{Button, EffectPriority};
 
 pub const MIN_LED_PWM_FREQUENCY: u64 = 0x02dd;
 pub const MAX_LED_PWM_FREQUENCY: u64 = 0x24e6;
+pub const MIN_EFFECT_SPEED: f32 = 0.1;
+pub const MAX_EFFECT_SPEED: f32 = 10.0;
+
+/// Overrides of the led and timeline, driven by analog inputs
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Modulation {
+    pub hue: Option<f32>,
+    pub saturation: Option<f32>,
+    /// Multiplies the value of the led
+    pub brightness: Option<f32>,
+    /// Replaces the speed of the timeline
+    pub speed: Option<f32>,
+}
+
+impl Modulation {
+    pub fn apply(&self, hsv: Hsv) -> Hsv {
+        Hsv::new(
+            self.hue.map(RgbHue::from_degrees).unwrap_or(hsv.hue),
+            self.saturation.unwrap_or(hsv.saturation),
+            hsv.value * self.brightness.unwrap_or(1.0),
+        )
+    }
+}
 
 pub struct PsMoveController {
     device: HidDevice,
@@ -19,12 +50,34 @@ pub struct PsMoveController {
     pub last_led_effect: LedEffect,
     pub led_effect: LedEffect,
     pub rumble_effect: RumbleEffect,
+    pub leds: PriorityStack<LedEffect>,
+    pub rumbles: PriorityStack<RumbleEffect>,
+    pub sequence: Option<SequencePlayer>,
+    pub history: EffectHistory,
+    pub led_transition: Option<LedTransition>,
+    /// Holds the timeline of every effect (and sequence), freezing the led and rumble
+    pub paused: bool,
+    /// How fast the timeline runs, 1.0 being real time
+    pub speed: f32,
+    update_progress: UpdateProgress,
+    pub color_output: ColorOutput,
+    /// Only known if set by us, the controller doesn't report it
+    pub led_pwm_frequency: Option<u64>,
+    dither: TemporalDither,
     pub setting: MoveSetting,
     pub last_battery: BatteryLevel,
     pub battery: BatteryLevel,
     last_button_state: HashMap<Button, ButtonState>,
     button_state: HashMap<Button, ButtonState>,
     pub trigger: f32,
+    /// In g, with y pointing towards the sphere
+    pub accelerometer: [f32; 3],
+    /// In degrees per second
+    pub gyroscope: [f32; 3],
+    /// Set by analog bindings on every tick, on top of the effects
+    pub modulation: Modulation,
+    /// Scene the controller shows, until another effect replaces it
+    pub scene: Option<String>,
     pub connection_type: ConnectionType,
 }
 
@@ -46,6 +99,17 @@ impl PsMoveController {
             last_led_effect: LedEffect::off(),
             led_effect: LedEffect::off(),
             rumble_effect: RumbleEffect::off(),
+            leds: PriorityStack::default(),
+            rumbles: PriorityStack::default(),
+            sequence: None,
+            history: EffectHistory::default(),
+            led_transition: None,
+            paused: false,
+            speed: 1.0,
+            update_progress: UpdateProgress::default(),
+            color_output: ColorOutput::default(),
+            led_pwm_frequency: None,
+            dither: TemporalDither::default(),
             setting: MoveSetting {
                 led: Hsv::from_components((0.0, 0.0, 0.0)),
                 last_led: Hsv::from_components((0.0, 0.0, 0.0)),
@@ -57,6 +121,10 @@ impl PsMoveController {
             last_button_state: HashMap::new(),
             button_state: HashMap::new(),
             trigger: 0.0,
+            accelerometer: [0.0; 3],
+            gyroscope: [0.0; 3],
+            modulation: Modulation::default(),
+            scene: None,
         }
     }
 
@@ -86,11 +154,17 @@ impl PsMoveController {
         self.connection_type = ConnectionType::UsbAndBluetooth;
     }
 
-    #[allow(dead_code)]
-    pub fn set_led_pwm_frequency(&self, frequency: u64) -> bool {
-        let request = build_set_led_pwm_request(frequency);
+    pub fn set_led_pwm_frequency(&mut self, frequency: u64) -> Result<(), String> {
+        let request = build_set_led_pwm_request(frequency)?;
 
-        self.device.write(&request).is_ok()
+        match self.device.write(&request) {
+            Ok(_) => {
+                info!("Set led PWM frequency of '{}' to {frequency}Hz", self.bt_address);
+                self.led_pwm_frequency = Some(frequency);
+                Ok(())
+            }
+            Err(err) => Err(format!("Failed to set led PWM frequency {err}")),
+        }
     }
 
     pub fn revert_led_effect(&mut self) {
@@ -126,11 +200,302 @@ impl PsMoveController {
         self.led_effect = effect;
     }
 
+    /// Sets the effect, unless one with a higher priority is active, in which case it's kept
+    /// to resume once the higher ones are gone
+    ///
+    /// Returns `false` if the effect was only kept.
+    pub fn set_prioritized_led_effect(
+        &mut self,
+        effect: LedEffect,
+        priority: EffectPriority,
+        transition: Option<Transition>,
+    ) -> bool {
+        let shown_priority = self.leds.priority;
+
+        match self.leds.push(effect, priority, &self.led_effect) {
+            Ok(effect) => {
+                self.start_led_transition(transition);
+                self.set_led_effect(effect);
+                true
+            }
+            Err(kept) => {
+                info!(
+                    "Kept '{kept}' of '{}' until {shown_priority} led effects are gone",
+                    self.bt_address
+                );
+                false
+            }
+        }
+    }
+
+    /// Same as [`PsMoveController::set_prioritized_led_effect`], for the rumble
+    pub fn set_prioritized_rumble_effect(
+        &mut self,
+        effect: RumbleEffect,
+        priority: EffectPriority,
+    ) -> bool {
+        let shown_priority = self.rumbles.priority;
+
+        match self.rumbles.push(effect, priority, &self.rumble_effect) {
+            Ok(effect) => {
+                self.set_rumble_effect(effect);
+                true
+            }
+            Err(kept) => {
+                info!(
+                    "Kept '{kept}' of '{}' until {shown_priority} rumble effects are gone",
+                    self.bt_address
+                );
+                false
+            }
+        }
+    }
+
+    /// Drops the effects of `priority`, resuming lower ones if they were showing
+    pub fn release_priority(&mut self, priority: EffectPriority) {
+        if let Some(effect) = self.leds.release(priority) {
+            self.show_resumed_led_effect(effect);
+        }
+
+        if let Some(effect) = self.rumbles.release(priority) {
+            self.show_resumed_rumble_effect(effect);
+        }
+    }
+
+    fn show_resumed_led_effect(&mut self, effect: LedEffect) {
+        info!("Resumed '{effect}' of '{}'", self.bt_address);
+        self.set_led_effect(effect);
+    }
+
+    fn show_resumed_rumble_effect(&mut self, effect: RumbleEffect) {
+        info!("Resumed '{effect}' of '{}'", self.bt_address);
+        self.set_rumble_effect(effect);
+    }
+
+    /// Changes parameters of the current led effect in place, without restarting it
+    /// (nor replacing the last one)
+    ///
+    /// Returns `false` if none of them apply to the current effect.
+    pub fn patch_led_effect(&mut self, patch: &LedEffectPatch) -> bool {
+        if !self.led_effect.kind.patch(patch) {
+            return false;
+        }
+
+        // these step from the displayed led, so they'd only show the change on their next step
+        match &self.led_effect.kind {
+            LedEffectKind::Rainbow {
+                saturation, value, ..
+            } => {
+                self.setting.led.saturation = *saturation;
+                self.setting.led.value = *value;
+            }
+            LedEffectKind::Blink { hsv, .. } if self.setting.led.value > 0.0 => {
+                self.setting.led = *hsv
+            }
+            LedEffectKind::Candle {
+                hue, saturation, ..
+            } => {
+                self.setting.led = Hsv::new(*hue, *saturation, self.setting.led.value);
+            }
+            _ => {}
+        }
+
+        info!("Patched '{}' of '{}'", self.led_effect, self.bt_address);
+        true
+    }
+
+    /// Blends from the currently displayed led into whatever effect is set next
+    pub fn start_led_transition(&mut self, transition: Option<Transition>) {
+        if let Some(transition) = transition.filter(|transition| !transition.duration.is_zero()) {
+            self.led_transition = Some(LedTransition::new(self.get_displayed_led(), transition));
+        }
+    }
+
     pub fn set_led_effect_with_hsv(&mut self, effect: LedEffect, hsv: Hsv) {
         self.setting.led = hsv;
         self.led_effect = effect;
     }
 
+    /// Replaces the running sequence, unless it has a higher priority
+    /// Returns `false` if the sequence was ignored, a running one having a higher priority
+    pub fn set_sequence(
+        &mut self,
+        sequence: Arc<EffectSequence>,
+        priority: EffectPriority,
+        start: Instant,
+    ) -> bool {
+        if let Some(player) = self.sequence.as_ref().filter(|player| player.priority > priority) {
+            tracing::warn!(
+                "Ignored '{sequence}' of '{}', '{}' has a higher priority",
+                self.bt_address,
+                player.sequence
+            );
+            return false;
+        }
+
+        self.sequence = Some(SequencePlayer::new(sequence, priority, start));
+        self.apply_sequence_item(false);
+        true
+    }
+
+    pub fn stop_sequence(&mut self) {
+        if let Some(player) = self.sequence.take() {
+            info!("Stopped '{}' of '{}'", player.sequence, self.bt_address);
+        }
+    }
+
+    /// Stops the running sequence if it has `priority`, since an effect with the same
+    /// priority replaces it (a higher one only preempts it, a lower one waits for it)
+    pub fn stop_sequence_of(&mut self, priority: EffectPriority) {
+        if self
+            .sequence
+            .as_ref()
+            .is_some_and(|player| player.priority == priority)
+        {
+            self.stop_sequence();
+        }
+    }
+
+    pub fn skip_sequence_item(&mut self) {
+        match self.sequence.as_mut() {
+            Some(player) => {
+                if player.advance() {
+                    self.apply_sequence_item(true);
+                } else {
+                    self.finish_sequence();
+                }
+            }
+            None => tracing::warn!("No sequence to skip on '{}'", self.bt_address),
+        }
+    }
+
+    pub fn pause(&mut self) {
+        info!("Paused effects of '{}'", self.bt_address);
+        self.paused = true;
+    }
+
+    /// Continues from the phase the effects were paused at
+    pub fn resume(&mut self) {
+        info!("Resumed effects of '{}'", self.bt_address);
+        self.paused = false;
+    }
+
+    pub fn set_speed(&mut self, speed: f32) {
+        info!("Set effect speed of '{}' to {speed}", self.bt_address);
+        self.speed = speed;
+    }
+
+    /// Recolors every effect of the led, the kept ones and the sequence included,
+    /// see [`LedEffectKind::recolor`]
+    pub fn recolor(&mut self, colors: &[Hsv], offset: usize) {
+        self.led_effect.kind.recolor(colors, offset);
+        self.last_led_effect.kind.recolor(colors, offset);
+        self.leds
+            .suspended_mut()
+            .for_each(|effect| effect.kind.recolor(colors, offset));
+
+        if let Some(player) = self.sequence.as_mut() {
+            player.recolor(colors, offset);
+        }
+    }
+
+    /// Moves the timeline (effects, kept effects, transition and sequence) according to the
+    /// pause and speed, for a tick of `elapsed`
+    ///
+    /// Returns how many times the effects must be updated on this tick, so the ones stepping
+    /// each update also follow the speed.
+    pub fn advance_timeline(&mut self, elapsed: Duration) -> u32 {
+        let speed = if self.paused {
+            0.0
+        } else {
+            self.modulation.speed.unwrap_or(self.speed)
+        };
+
+        if speed != 1.0 {
+            let shift = TimeShift::from_speed(elapsed, speed);
+
+            self.led_effect.shift(shift);
+            self.last_led_effect.shift(shift);
+            self.rumble_effect.shift(shift);
+            self.leds.suspended_mut().for_each(|effect| effect.shift(shift));
+            self.rumbles.suspended_mut().for_each(|effect| effect.shift(shift));
+
+            if let Some(transition) = self.led_transition.as_mut() {
+                transition.shift(shift);
+            }
+            if let Some(player) = self.sequence.as_mut() {
+                player.shift(shift);
+            }
+        }
+
+        self.update_progress.advance(speed)
+    }
+
+    /// Moves the sequence to its next item when the current one expires
+    pub fn transform_sequence(&mut self) {
+        let Some(player) = self.sequence.as_mut() else {
+            return;
+        };
+
+        if !player.has_item_expired() {
+            return;
+        }
+
+        if player.advance() {
+            self.apply_sequence_item(true);
+        } else {
+            self.finish_sequence();
+        }
+    }
+
+    /// `follows_item` if it replaces the previous item, which it crossfades from
+    fn apply_sequence_item(&mut self, follows_item: bool) {
+        let Some(player) = self.sequence.as_ref() else {
+            return;
+        };
+        let item = player.current_item().clone();
+        let transition = player.sequence.crossfade.filter(|_| follows_item);
+        let priority = player.priority;
+        let item_start = player.item_start;
+        // reverting goes back to the effect shown before the sequence, not to the previous item
+        let last_led = follows_item.then(|| (self.last_led_effect.clone(), self.setting.last_led));
+
+        let mut led_kind = item.led.unwrap_or(LedEffectKind::Off);
+        let mut rumble_kind = item.rumble.unwrap_or(RumbleEffectKind::Off);
+
+        // items are timed from when they should have started, so a late sequence keeps its rhythm
+        led_kind.restart_at(item_start);
+        rumble_kind.restart_at(item_start);
+
+        self.set_prioritized_led_effect(
+            LedEffect {
+                start: item_start,
+                ..LedEffect::new_expiring(led_kind, item.duration)
+            },
+            priority,
+            transition,
+        );
+        self.set_prioritized_rumble_effect(
+            RumbleEffect {
+                start: item_start,
+                ..RumbleEffect::new_expiring(rumble_kind, item.duration)
+            },
+            priority,
+        );
+
+        if let Some((effect, led)) = last_led {
+            self.last_led_effect = effect;
+            self.setting.last_led = led;
+        }
+    }
+
+    fn finish_sequence(&mut self) {
+        if let Some(player) = self.sequence.take() {
+            info!("Finished '{}' of '{}'", player.sequence, self.bt_address);
+            self.release_priority(player.priority);
+        }
+    }
+
     pub fn set_rumble_effect(&mut self, effect: RumbleEffect) {
         match effect.kind {
             RumbleEffectKind::Off => {}
@@ -162,6 +527,14 @@ impl PsMoveController {
                     tracing::error!("Strength must be between 0.0 and 1.0")
                 }
             }
+            RumbleEffectKind::FollowLed {
+                min_strength,
+                max_strength,
+            } => {
+                if !(0.0..=1.0).contains(&min_strength) || !(0.0..=1.0).contains(&max_strength) {
+                    tracing::error!("Strengths must be between 0.0 and 1.0")
+                }
+            }
         };
 
         self.rumble_effect = effect;
@@ -180,6 +553,8 @@ impl PsMoveController {
             self.update_battery(data.battery);
             self.update_button_state(data.get_button_slice());
             self.trigger = data.get_trigger();
+            self.accelerometer = data.get_accelerometer();
+            self.gyroscope = data.get_gyroscope();
         }
 
         Ok(())
@@ -197,41 +572,93 @@ impl PsMoveController {
             .collect::<Vec<(Button, ButtonState)>>())
     }
 
+    /// In g, 1.0 at rest
+    pub fn get_acceleration(&self) -> f32 {
+        let [x, y, z] = self.accelerometer;
+
+        (x * x + y * y + z * z).sqrt()
+    }
+
+    /// In degrees, 90.0 with the sphere up, -90.0 with it down
+    pub fn get_pitch(&self) -> f32 {
+        let [x, y, z] = self.accelerometer;
+
+        y.atan2((x * x + z * z).sqrt()).to_degrees()
+    }
+
+    /// In degrees, around the length of the controller (unreliable when it points up or down)
+    pub fn get_roll(&self) -> f32 {
+        let [x, _, z] = self.accelerometer;
+
+        x.atan2(z).to_degrees()
+    }
+
+    /// In degrees per second, around any axis
+    pub fn get_rotation_speed(&self) -> f32 {
+        let [x, y, z] = self.gyroscope;
+
+        (x * x + y * y + z * z).sqrt()
+    }
+
     pub fn transform_led(&mut self) {
+        if self.led_transition.is_some_and(|transition| transition.has_finished()) {
+            self.led_transition = None;
+        }
+
         let led_effect = &mut self.led_effect;
         let current_hsv = self.setting.led;
 
-        if led_effect.duration.is_some() {
-            let duration = led_effect.duration.unwrap();
-
+        if let Some(duration) = led_effect.duration {
             if led_effect.start.elapsed() >= duration {
                 info!("Led effect '{}' of '{}' expired", led_effect, self.bt_address);
-                self.set_led_effect(LedEffect::off());
+
+                let effect = self.leds.resume();
+                self.show_resumed_led_effect(effect);
                 return;
             }
         };
 
-        self.setting.led = led_effect.kind.get_updated_hsv(current_hsv);
+        self.setting.led = led_effect
+            .kind
+            .get_updated_hsv(current_hsv, Instant::now(), &mut thread_rng());
     }
 
     pub fn transform_rumble(&mut self) {
         let rumble_effect = &mut self.rumble_effect;
         let current_rumble = self.setting.rumble;
 
-        if rumble_effect.duration.is_some() {
-            let duration = rumble_effect.duration.unwrap();
-
+        if let Some(duration) = rumble_effect.duration {
             if rumble_effect.start.elapsed() >= duration {
-                self.set_rumble_effect(RumbleEffect::off());
+                let effect = self.rumbles.resume();
+                self.show_resumed_rumble_effect(effect);
                 return;
             }
         };
 
-        self.setting.rumble = rumble_effect.kind.get_updated_rumble(current_rumble);
+        // the led was already transformed on this tick, so both stay in step
+        self.setting.rumble = rumble_effect
+            .kind
+            .get_updated_rumble(current_rumble, self.setting.led.value, Instant::now());
+    }
+
+    /// The led that is actually displayed, which differs from the effect's
+    /// while transitioning
+    pub fn get_output_led(&self) -> Hsv {
+        self.modulation.apply(self.get_displayed_led())
     }
 
-    fn update_hsv_and_rumble(&self) -> Result<(), ()> {
-        let request = build_set_led_and_rumble_request(self.setting.led, self.setting.rumble);
+    /// Before the modulation, which applies on top of transitions as well
+    fn get_displayed_led(&self) -> Hsv {
+        match self.led_transition {
+            Some(transition) => transition.apply(self.setting.led),
+            None => self.setting.led,
+        }
+    }
+
+    fn update_hsv_and_rumble(&mut self) -> Result<(), ()> {
+        let rgb = self.color_output.convert(self.get_output_led());
+        let rgb = self.dither.quantize(rgb, self.color_output.dithering);
+        let request = build_set_led_and_rumble_request(rgb, self.setting.rumble);
 
         let res = self.device.write(&request);
 
@@ -281,13 +708,14 @@ impl PsMoveController {
     }
 }
 
-#[allow(dead_code)]
-fn build_set_led_pwm_request(frequency: u64) -> [u8; 7] {
+fn build_set_led_pwm_request(frequency: u64) -> Result<[u8; 7], String> {
     if !(MIN_LED_PWM_FREQUENCY..=MAX_LED_PWM_FREQUENCY).contains(&frequency) {
-        panic!("Frequency must be between {MIN_LED_PWM_FREQUENCY} and {MAX_LED_PWM_FREQUENCY}!")
+        return Err(format!(
+            "Frequency must be between {MIN_LED_PWM_FREQUENCY} and {MAX_LED_PWM_FREQUENCY}!"
+        ));
     }
 
-    [
+    Ok([
         MoveRequestType::SetLEDPWMFrequency as u8,
         0x41,
         0,
@@ -295,12 +723,11 @@ fn build_set_led_pwm_request(frequency: u64) -> [u8; 7] {
         ((frequency >> 8) & 0xFF) as u8,
         ((frequency >> 16) & 0xFF) as u8,
         ((frequency >> 24) & 0xFF) as u8,
-    ]
+    ])
 }
 
-fn build_set_led_and_rumble_request(hsv: Hsv, rumble: f32) -> [u8; 8] {
+fn build_set_led_and_rumble_request(rgb: [u8; 3], rumble: f32) -> [u8; 8] {
     let f32_to_u8 = |f: f32| (f * 255.0) as u8;
-    let rgb = hsv_to_rgb(hsv, f32_to_u8);
 
     [
         MoveRequestType::SetLED as u8,
@@ -313,9 +740,3 @@ fn build_set_led_and_rumble_request(hsv: Hsv, rumble: f32) -> [u8; 8] {
         0,
     ]
 }
-
-fn hsv_to_rgb(hsv: Hsv, f32_to_u8: fn(f: f32) -> u8) -> [u8; 3] {
-    let rgb = Srgb::from_color(hsv);
-
-    [rgb.red, rgb.green, rgb.blue].map(f32_to_u8)
-}
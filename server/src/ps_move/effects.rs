@@ -2,13 +2,17 @@ use std::fmt;
 use std::fmt::Formatter;
 use std::sync::Arc;
 
+use chrono::{DateTime, Local, TimeDelta};
 use lazy_static::lazy_static;
-use palette::{Hsv, Mix, ShiftHue};
+use palette::{Hsv, ShiftHue};
 use rand::distributions::{Distribution, Uniform};
-use rand::thread_rng;
+use rand::seq::SliceRandom;
+use rand::{thread_rng, Rng};
 use strum_macros::Display;
 use tokio::time::{Duration, Instant};
 
+use crate::ps_move::colors::ColorSpace;
+use crate::schedules::{CircadianProfile, Location};
 use crate::tasks::effects_update;
 
 lazy_static! {
@@ -17,6 +21,22 @@ lazy_static! {
 
 const MAX_HUE_VALUE: f32 = 360.0;
 
+/// Frequency (Hz) and weight of each flicker layer of the fire, from slow to fast
+const FIRE_LAYERS: [(f32, f32); 3] = [(0.7, 0.5), (3.0, 0.3), (11.0, 0.2)];
+/// How much the hue of the fire shifts (towards yellow) when it flares
+const FIRE_HUE_SHIFT: f32 = 25.0;
+const LIGHTNING_FLASH_DURATION: Duration = Duration::from_millis(60);
+const LIGHTNING_FLASH_GAP: Duration = Duration::from_millis(80);
+const LIGHTNING_MAX_FLASHES: u32 = 3;
+/// Brightness (relative to the flash) the afterglow starts at
+const LIGHTNING_AFTERGLOW_LEVEL: f32 = 0.3;
+const MAX_NOISE_OCTAVES: u32 = 8;
+/// Where the second pulse ("dub") of a heartbeat peaks, as a fraction of the beat
+const HEARTBEAT_SECOND_PULSE: f32 = 0.3;
+const HEARTBEAT_SECOND_PULSE_LEVEL: f32 = 0.6;
+/// Width of each heartbeat pulse, as a fraction of the beat
+const HEARTBEAT_PULSE_WIDTH: f32 = 0.05;
+
 #[derive(Clone)]
 pub struct LedEffect {
     pub kind: LedEffectKind,
@@ -65,6 +85,22 @@ impl LedEffect {
         self.kind == LedEffectKind::Off
     }
 
+    /// Moves the whole timeline (expiry, runtime state and shared clock) to begin at `start`
+    pub fn start_at(&mut self, start: Instant) {
+        self.start = start;
+        self.kind.restart_at(start);
+
+        if let LedEffectKind::Wave { epoch, .. } | LedEffectKind::Chase { epoch, .. } = &mut self.kind {
+            *epoch = start;
+        }
+    }
+
+    /// Moves the whole timeline (expiry, runtime state and clock) by `shift`
+    pub fn shift(&mut self, shift: TimeShift) {
+        shift.apply(&mut self.start);
+        self.kind.shift(shift);
+    }
+
     pub fn has_expired(&self) -> bool {
         if let Some(duration) = self.duration {
             self.start.elapsed() > duration
@@ -109,14 +145,112 @@ pub enum LedEffectKind {
         value_sample: Uniform<f32>,
         min_value: f32,
         max_value: f32,
+        /// How far samples go beyond the value range (clamped back to it), as a fraction of it
+        variability: f32,
         interval: i32,
         last_change: Instant,
+        /// Each change picks the hue and saturation of one of these at random, if any
+        palette: Option<Arc<Vec<Hsv>>>,
     },
     Bounce {
         colors: Arc<Vec<Hsv>>,
         step: f32,
+        color_space: ColorSpace,
+        /// From 0.0 (at `colors[color_index]`) to 1.0 (at the next color)
         progress: f32,
-        next_color_index: usize,
+        color_index: usize,
+    },
+    /// Sine between `min_value` and the value of `hsv`, on a clock shared by a group of
+    /// controllers, each one being `phase` (fraction of `period`) behind the clock
+    Wave {
+        hsv: Hsv,
+        min_value: f32,
+        period: Duration,
+        phase: f32,
+        epoch: Instant,
+    },
+    /// Lit while it's the turn of `position` (out of `count` controllers), on a clock shared by
+    /// a group of controllers
+    Chase {
+        hsv: Hsv,
+        background: Hsv,
+        interval: Duration,
+        position: usize,
+        count: usize,
+        epoch: Instant,
+    },
+    /// Flicker between `min_value` and `max_value`, from layers of smooth noise
+    Fire {
+        hue: f32,
+        saturation: f32,
+        min_value: f32,
+        max_value: f32,
+        seed: u32,
+        start: Instant,
+    },
+    /// Bursts of flashes at random intervals, each one fading out during `afterglow`
+    Lightning {
+        hsv: Hsv,
+        min_interval: Duration,
+        max_interval: Duration,
+        afterglow: Duration,
+        /// Start of the last burst, `None` before the first one
+        strike: Option<Instant>,
+        flashes: u32,
+        next_strike: Instant,
+    },
+    /// Double pulse ("lub-dub") every beat, from `min_value` to the value of `hsv`
+    Heartbeat {
+        hsv: Hsv,
+        min_value: f32,
+        beat: Duration,
+        start: Instant,
+    },
+    /// Lit during `duty_cycle` (fraction) of every `period`
+    Strobe {
+        hsv: Hsv,
+        period: Duration,
+        duty_cycle: f32,
+        start: Instant,
+    },
+    /// Alternates between `first` and `second` every `interval`, flashing `flashes` times each
+    Police {
+        first: Hsv,
+        second: Hsv,
+        interval: Duration,
+        flashes: u32,
+        start: Instant,
+    },
+    /// Hue and value drifting within their ranges, driven by fractal 1-D Perlin noise over time
+    /// (`seed` makes it reproducible)
+    Noise {
+        min_hue: f32,
+        /// How far the hue can drift from `min_hue`, 0.0 keeps it fixed
+        hue_range: f32,
+        saturation: f32,
+        min_value: f32,
+        max_value: f32,
+        /// Noise units travelled per second
+        speed: f32,
+        octaves: u32,
+        seed: u32,
+        start: Instant,
+    },
+    /// Brief flashes of `sparkle` over `base`, `rate` times per second on average
+    Sparkle {
+        base: Hsv,
+        sparkle: Hsv,
+        rate: f32,
+        sparkle_duration: Duration,
+        /// Start of the last sparkle, `None` before the first one
+        sparkle_start: Option<Instant>,
+        next_sparkle: Instant,
+    },
+    /// Color temperature and brightness of the time of day (on the wall clock, which pausing
+    /// or speeding up the effects doesn't affect), following the sun at `location`
+    Circadian {
+        profile: Arc<CircadianProfile>,
+        location: Location,
     },
 }
 
@@ -157,6 +291,19 @@ impl LedEffectKind {
         }
     }
 
+    /// Creates an instance with `LedEffect::Bounce` having `step`
+    /// according to `time_to_complete` (going through every color) and tick rate
+    pub fn new_timed_bounce(
+        colors: Vec<Hsv>,
+        time_to_complete: Duration,
+        color_space: ColorSpace,
+    ) -> LedEffectKind {
+        let time_per_color = time_to_complete.as_millis() as f32 / colors.len().max(1) as f32;
+        let step = effects_update::INTERVAL_DURATION.as_millis() as f32 / time_per_color;
+
+        LedEffectKind::new_bounce(colors, step.min(1.0), color_space)
+    }
+
     pub fn new_candle(
         hue: f32,
         saturation: f32,
@@ -177,28 +324,487 @@ impl LedEffectKind {
             min_value,
             max_value,
             value_sample,
+            variability,
             interval: interval.unwrap_or(1),
             last_change: Instant::now(),
+            palette: None,
         }
     }
 
-    pub fn new_bounce(
-        hues: Vec<f32>,
-        saturation: f32,
-        value: f32,
-        step: f32,
+    /// Creates an instance with `LedEffect::Candle` flickering through the colors of a palette
+    /// (starting with the first one)
+    pub fn new_palette_candle(
+        colors: Vec<Hsv>,
+        min_value: f32,
+        max_value: f32,
+        variability: f32,
+        interval: Option<i32>,
     ) -> LedEffectKind {
+        let first = colors.first().copied().unwrap_or_default();
+        let mut candle = LedEffectKind::new_candle(
+            first.hue.into_positive_degrees(),
+            first.saturation,
+            min_value,
+            max_value,
+            variability,
+            interval,
+        );
+
+        if let LedEffectKind::Candle { palette, .. } = &mut candle {
+            *palette = Some(Arc::new(colors));
+        }
+        candle
+    }
+
+    pub fn new_bounce(colors: Vec<Hsv>, step: f32, color_space: ColorSpace) -> LedEffectKind {
         LedEffectKind::Bounce {
-            colors: Arc::new(hues.iter()
-                .map(|hue| Hsv::from_components((*hue, saturation, value)))
-                .collect()),
+            colors: Arc::new(colors),
             step,
+            color_space,
             progress: 0.0,
-            next_color_index: 1,
+            color_index: 0,
+        }
+    }
+
+    pub fn new_fire(hue: f32, saturation: f32, min_value: f32, max_value: f32) -> LedEffectKind {
+        LedEffectKind::Fire {
+            hue,
+            saturation,
+            min_value,
+            max_value,
+            seed: thread_rng().gen(),
+            start: Instant::now(),
+        }
+    }
+
+    pub fn new_lightning(
+        hsv: Hsv,
+        min_interval: Duration,
+        max_interval: Duration,
+        afterglow: Duration,
+    ) -> LedEffectKind {
+        LedEffectKind::Lightning {
+            hsv,
+            min_interval,
+            max_interval,
+            afterglow,
+            strike: None,
+            flashes: 1,
+            next_strike: Instant::now() + thread_rng().gen_range(min_interval..=max_interval),
+        }
+    }
+
+    /// Creates an instance with `LedEffect::Heartbeat` beating `bpm` times per minute
+    pub fn new_heartbeat(hsv: Hsv, min_value: f32, bpm: f32) -> LedEffectKind {
+        LedEffectKind::Heartbeat {
+            hsv,
+            min_value,
+            beat: Duration::from_secs_f32(60.0 / bpm),
+            start: Instant::now(),
+        }
+    }
+
+    /// Creates an instance with `LedEffect::Strobe` flashing `frequency` times per second
+    pub fn new_strobe(hsv: Hsv, frequency: f32, duty_cycle: f32) -> LedEffectKind {
+        LedEffectKind::Strobe {
+            hsv,
+            period: Duration::from_secs_f32(1.0 / frequency),
+            duty_cycle,
+            start: Instant::now(),
+        }
+    }
+
+    pub fn new_police(first: Hsv, second: Hsv, interval: Duration, flashes: u32) -> LedEffectKind {
+        LedEffectKind::Police {
+            first,
+            second,
+            interval,
+            flashes,
+            start: Instant::now(),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_noise(
+        min_hue: f32,
+        hue_range: f32,
+        saturation: f32,
+        min_value: f32,
+        max_value: f32,
+        speed: f32,
+        octaves: u32,
+        seed: Option<u32>,
+    ) -> LedEffectKind {
+        LedEffectKind::Noise {
+            min_hue,
+            hue_range,
+            saturation,
+            min_value,
+            max_value,
+            speed,
+            octaves: octaves.clamp(1, MAX_NOISE_OCTAVES),
+            seed: seed.unwrap_or_else(|| thread_rng().gen()),
+            start: Instant::now(),
+        }
+    }
+
+    pub fn new_sparkle(
+        base: Hsv,
+        sparkle: Hsv,
+        rate: f32,
+        sparkle_duration: Duration,
+    ) -> LedEffectKind {
+        LedEffectKind::Sparkle {
+            base,
+            sparkle,
+            rate,
+            sparkle_duration,
+            sparkle_start: None,
+            next_sparkle: Instant::now() + Self::get_sparkle_interval(rate, &mut thread_rng()),
+        }
+    }
+
+    /// Resets the runtime state, as if the effect was created at `start`
+    pub fn restart_at(&mut self, start: Instant) {
+        self.restart_with(start, &mut thread_rng())
+    }
+
+    /// Resets the runtime state, as if the effect was created at `start`, drawing from `rng`
+    pub fn restart_with<R: Rng>(&mut self, start: Instant, rng: &mut R) {
+        match self {
+            // the shared clock isn't restarted, otherwise the group would fall out of sync
+            LedEffectKind::Off
+            | LedEffectKind::Static { .. }
+            | LedEffectKind::Rainbow { .. }
+            | LedEffectKind::Wave { .. }
+            | LedEffectKind::Chase { .. }
+            | LedEffectKind::Circadian { .. } => {}
+            LedEffectKind::Breathing {
+                initial_hsv,
+                peak,
+                inhaling,
+                last_update,
+                ..
+            } => {
+                *inhaling = initial_hsv.value < *peak;
+                *last_update = start;
+            }
+            LedEffectKind::Blink { last_blink, .. } => *last_blink = start,
+            LedEffectKind::Candle { last_change, .. } => *last_change = start,
+            LedEffectKind::Bounce {
+                progress,
+                color_index,
+                ..
+            } => {
+                *progress = 0.0;
+                *color_index = 0;
+            }
+            LedEffectKind::Fire {
+                start: effect_start,
+                ..
+            }
+            | LedEffectKind::Heartbeat {
+                start: effect_start,
+                ..
+            }
+            | LedEffectKind::Strobe {
+                start: effect_start,
+                ..
+            }
+            | LedEffectKind::Police {
+                start: effect_start,
+                ..
+            }
+            | LedEffectKind::Noise {
+                start: effect_start,
+                ..
+            } => *effect_start = start,
+            LedEffectKind::Lightning {
+                min_interval,
+                max_interval,
+                strike,
+                next_strike,
+                ..
+            } => {
+                *strike = None;
+                *next_strike = start + rng.gen_range(*min_interval..=*max_interval);
+            }
+            LedEffectKind::Sparkle {
+                rate,
+                sparkle_start,
+                next_sparkle,
+                ..
+            } => {
+                *sparkle_start = None;
+                *next_sparkle = start + Self::get_sparkle_interval(*rate, rng);
+            }
+        }
+    }
+
+    /// Replaces the seed a fire or noise was created with, which their noise derives from
+    pub fn reseed(&mut self, new_seed: u32) {
+        if let LedEffectKind::Fire { seed, .. } | LedEffectKind::Noise { seed, .. } = self {
+            *seed = new_seed;
+        }
+    }
+
+    /// Moves every instant of the runtime state by `shift`, keeping the phase of the effect
+    pub fn shift(&mut self, shift: TimeShift) {
+        match self {
+            LedEffectKind::Off
+            | LedEffectKind::Static { .. }
+            | LedEffectKind::Rainbow { .. }
+            | LedEffectKind::Bounce { .. }
+            | LedEffectKind::Circadian { .. } => {}
+            LedEffectKind::Breathing { last_update, .. } => shift.apply(last_update),
+            LedEffectKind::Blink { last_blink, .. } => shift.apply(last_blink),
+            LedEffectKind::Candle { last_change, .. } => shift.apply(last_change),
+            LedEffectKind::Wave { epoch, .. } | LedEffectKind::Chase { epoch, .. } => {
+                shift.apply(epoch)
+            }
+            LedEffectKind::Fire { start, .. }
+            | LedEffectKind::Heartbeat { start, .. }
+            | LedEffectKind::Strobe { start, .. }
+            | LedEffectKind::Police { start, .. }
+            | LedEffectKind::Noise { start, .. } => shift.apply(start),
+            LedEffectKind::Lightning {
+                strike,
+                next_strike,
+                ..
+            } => {
+                if let Some(strike) = strike {
+                    shift.apply(strike);
+                }
+                shift.apply(next_strike);
+            }
+            LedEffectKind::Sparkle {
+                sparkle_start,
+                next_sparkle,
+                ..
+            } => {
+                if let Some(sparkle_start) = sparkle_start {
+                    shift.apply(sparkle_start);
+                }
+                shift.apply(next_sparkle);
+            }
+        }
+    }
+
+    /// Takes hue and saturation from `colors` (the one at `offset` for single color effects),
+    /// keeping the brightness of the effect
+    pub fn recolor(&mut self, colors: &[Hsv], offset: usize) {
+        let Some(color) = colors.get(offset % colors.len().max(1)) else {
+            return;
+        };
+
+        let with_color = |hsv: &mut Hsv| {
+            hsv.hue = color.hue;
+            hsv.saturation = color.saturation;
+        };
+
+        match self {
+            // the hue of the circadian comes from its color temperature
+            LedEffectKind::Off | LedEffectKind::Rainbow { .. } | LedEffectKind::Circadian { .. } => {}
+            LedEffectKind::Static { hsv }
+            | LedEffectKind::Blink { hsv, .. }
+            | LedEffectKind::Wave { hsv, .. }
+            | LedEffectKind::Lightning { hsv, .. }
+            | LedEffectKind::Heartbeat { hsv, .. }
+            | LedEffectKind::Strobe { hsv, .. }
+            | LedEffectKind::Sparkle { base: hsv, .. } => with_color(hsv),
+            LedEffectKind::Breathing { initial_hsv, .. } => with_color(initial_hsv),
+            LedEffectKind::Chase {
+                hsv, background, ..
+            } => {
+                with_color(hsv);
+                with_color(background);
+            }
+            LedEffectKind::Candle {
+                hue,
+                saturation,
+                palette,
+                ..
+            } => {
+                *hue = color.hue.into_positive_degrees();
+                *saturation = color.saturation;
+
+                if palette.is_some() {
+                    *palette = Some(Arc::new(colors.to_vec()));
+                }
+            }
+            LedEffectKind::Fire {
+                hue, saturation, ..
+            } => {
+                *hue = color.hue.into_positive_degrees();
+                *saturation = color.saturation;
+            }
+            LedEffectKind::Noise {
+                min_hue, saturation, ..
+            } => {
+                *min_hue = color.hue.into_positive_degrees();
+                *saturation = color.saturation;
+            }
+            LedEffectKind::Police { first, second, .. } => {
+                let second_color = colors[(offset + 1) % colors.len()];
+
+                with_color(first);
+                second.hue = second_color.hue;
+                second.saturation = second_color.saturation;
+            }
+            LedEffectKind::Bounce {
+                colors: bounce_colors,
+                color_index,
+                ..
+            } => {
+                let value = bounce_colors.first().map_or(1.0, |color| color.value);
+
+                *bounce_colors = Arc::new(
+                    colors
+                        .iter()
+                        .map(|color| Hsv::new(color.hue, color.saturation, value))
+                        .collect(),
+                );
+                *color_index %= colors.len();
+            }
+        }
+    }
+
+    /// Changes the parameters of `patch` the effect has, keeping its phase (a new cycle length
+    /// continues from the same point of the cycle)
+    ///
+    /// Returns `false` if none of them apply to this effect.
+    pub fn patch(&mut self, patch: &LedEffectPatch) -> bool {
+        let patched = match self {
+            LedEffectKind::Off
+            | LedEffectKind::Police { .. }
+            | LedEffectKind::Bounce { .. }
+            | LedEffectKind::Circadian { .. } => false,
+            LedEffectKind::Static { hsv }
+            | LedEffectKind::Blink { hsv, .. }
+            | LedEffectKind::Wave { hsv, .. }
+            | LedEffectKind::Chase { hsv, .. }
+            | LedEffectKind::Lightning { hsv, .. }
+            | LedEffectKind::Heartbeat { hsv, .. }
+            | LedEffectKind::Strobe { hsv, .. }
+            | LedEffectKind::Sparkle { base: hsv, .. } => {
+                patch_hsv(hsv, patch) | patch_value(&mut hsv.value, patch.peak)
+            }
+            LedEffectKind::Breathing {
+                initial_hsv, peak, ..
+            } => patch_hsv(initial_hsv, patch) | patch_value(peak, patch.peak),
+            LedEffectKind::Rainbow {
+                saturation, value, ..
+            } => patch_value(saturation, patch.saturation) | patch_value(value, patch.peak),
+            LedEffectKind::Candle {
+                hue, saturation, ..
+            } => patch_value(hue, patch.hue) | patch_value(saturation, patch.saturation),
+            LedEffectKind::Fire {
+                hue: min_hue,
+                saturation,
+                min_value,
+                max_value,
+                ..
+            }
+            | LedEffectKind::Noise {
+                min_hue,
+                saturation,
+                min_value,
+                max_value,
+                ..
+            } => {
+                let peak = patch.peak.map(|peak| peak.max(*min_value));
+
+                patch_value(min_hue, patch.hue)
+                    | patch_value(saturation, patch.saturation)
+                    | patch_value(max_value, peak)
+            }
+        };
+
+        let interval_patched = patch
+            .interval
+            .is_some_and(|interval| self.patch_interval(interval));
+        let speed_patched = patch.speed.is_some_and(|speed| self.patch_speed(speed));
+
+        patched | interval_patched | speed_patched
+    }
+
+    fn patch_interval(&mut self, new_interval: Duration) -> bool {
+        match self {
+            LedEffectKind::Breathing {
+                time_to_peak,
+                last_update,
+                ..
+            } => {
+                let new_time_to_peak = new_interval.as_millis() as i32;
+
+                rescale_elapsed(last_update, new_time_to_peak as f32 / (*time_to_peak).max(1) as f32);
+                *time_to_peak = new_time_to_peak;
+            }
+            LedEffectKind::Rainbow { step, .. } => {
+                *step = effects_update::INTERVAL_DURATION.as_millis() as f32 * MAX_HUE_VALUE
+                    / new_interval.as_millis() as f32;
+            }
+            LedEffectKind::Bounce { colors, step, .. } => {
+                let time_per_color = new_interval.as_millis() as f32 / colors.len().max(1) as f32;
+
+                *step = (effects_update::INTERVAL_DURATION.as_millis() as f32 / time_per_color).min(1.0);
+            }
+            LedEffectKind::Blink { interval, .. } => *interval = new_interval,
+            LedEffectKind::Candle { interval, .. } => *interval = new_interval.as_millis() as i32,
+            LedEffectKind::Wave {
+                period: interval,
+                epoch: start,
+                ..
+            }
+            | LedEffectKind::Chase {
+                interval,
+                epoch: start,
+                ..
+            }
+            | LedEffectKind::Heartbeat {
+                beat: interval,
+                start,
+                ..
+            }
+            | LedEffectKind::Strobe {
+                period: interval,
+                start,
+                ..
+            }
+            | LedEffectKind::Police {
+                interval, start, ..
+            } => {
+                rescale_elapsed(start, new_interval.as_secs_f32() / interval.as_secs_f32());
+                *interval = new_interval;
+            }
+            LedEffectKind::Off
+            | LedEffectKind::Static { .. }
+            | LedEffectKind::Fire { .. }
+            | LedEffectKind::Lightning { .. }
+            | LedEffectKind::Noise { .. }
+            | LedEffectKind::Sparkle { .. }
+            | LedEffectKind::Circadian { .. } => return false,
         }
+
+        true
+    }
+
+    fn patch_speed(&mut self, new_speed: f32) -> bool {
+        let LedEffectKind::Noise { speed, start, .. } = self else {
+            return false;
+        };
+
+        rescale_elapsed(start, *speed / new_speed);
+        *speed = new_speed;
+        true
     }
 
     pub fn get_initial_hsv(&self) -> Hsv {
+        self.get_initial_hsv_at(Instant::now())
+    }
+
+    /// Color of the effect when it starts, at `now`
+    pub fn get_initial_hsv_at(&self, now: Instant) -> Hsv {
         match self {
             LedEffectKind::Off => Hsv::from_components((0.0, 0.0, 0.0)),
             LedEffectKind::Static { hsv }
@@ -238,10 +844,34 @@ impl LedEffectKind {
             } => {
                 colors[0]
             }
+            LedEffectKind::Wave {
+                hsv,
+                min_value,
+                period,
+                phase,
+                epoch,
+            } => Self::get_wave_hsv(*hsv, *min_value, *period, *phase, *epoch, now),
+            LedEffectKind::Chase {
+                hsv,
+                background,
+                interval,
+                position,
+                count,
+                epoch,
+            } => Self::get_chase_hsv(*hsv, *background, *interval, *position, *count, *epoch, now),
+            LedEffectKind::Fire { .. }
+            | LedEffectKind::Heartbeat { .. }
+            | LedEffectKind::Strobe { .. }
+            | LedEffectKind::Police { .. }
+            | LedEffectKind::Noise { .. }
+            | LedEffectKind::Circadian { .. } => self.get_timed_hsv(now),
+            LedEffectKind::Lightning { .. } => *LED_OFF,
+            LedEffectKind::Sparkle { base, .. } => *base,
         }
     }
 
-    pub fn get_updated_hsv(&mut self, current_hsv: Hsv) -> Hsv {
+    /// Color following `current_hsv` on the tick at `now`, drawing any randomness from `rng`
+    pub fn get_updated_hsv<R: Rng>(&mut self, current_hsv: Hsv, now: Instant, rng: &mut R) -> Hsv {
         match self {
             LedEffectKind::Off => *LED_OFF,
             LedEffectKind::Static { hsv } => *hsv,
@@ -257,6 +887,7 @@ impl LedEffectKind {
                 *time_to_peak as f32,
                 *peak,
                 inhaling,
+                now,
             ),
             LedEffectKind::Rainbow {
                 step,
@@ -272,8 +903,8 @@ impl LedEffectKind {
                 interval,
                 last_blink: ref mut start,
             } => {
-                if start.elapsed() > *interval / 2 {
-                    *start = Instant::now();
+                if now.saturating_duration_since(*start) > *interval / 2 {
+                    *start = now;
 
                     if current_hsv.value == 0.0 {
                         *hsv
@@ -291,14 +922,19 @@ impl LedEffectKind {
                 min_value,
                 max_value,
                 interval,
-                ref mut last_change
+                ref mut last_change,
+                palette,
+                ..
             } => {
-                if last_change.elapsed().as_millis() as i32 > *interval {
-                    *last_change = Instant::now();
+                if now.saturating_duration_since(*last_change).as_millis() as i32 > *interval {
+                    *last_change = now;
 
-                    let new_value = value_sample
-                        .sample(&mut thread_rng())
-                        .clamp(*min_value, *max_value);
+                    let new_value = value_sample.sample(rng).clamp(*min_value, *max_value);
+
+                    if let Some(color) = palette.as_ref().and_then(|palette| palette.choose(rng)) {
+                        *hue = color.hue.into_positive_degrees();
+                        *saturation = color.saturation;
+                    }
 
                     Hsv::from_components((*hue, *saturation, new_value))
                 } else {
@@ -308,27 +944,255 @@ impl LedEffectKind {
             LedEffectKind::Bounce {
                 colors,
                 step,
+                color_space,
                 progress,
-                next_color_index,
+                color_index,
             } => {
-                let target_color = colors[*next_color_index];
-                let new_color = current_hsv.mix(target_color, *progress);
-
                 *progress += *step;
 
-                // the new color never reaches exactly the target hue (the float "99.99994" problem)
-                if (new_color.hue.into_degrees() - target_color.hue.into_degrees()).abs() < 2.0 {
-                    *next_color_index += 1;
+                // the target is reached by progress, not by comparing colors
+                // (which never match exactly, due to float precision)
+                if *progress >= 1.0 {
+                    *progress -= progress.floor();
+                    *color_index = (*color_index + 1) % colors.len();
+                }
 
-                    if *next_color_index + 1 > colors.len() {
-                        *next_color_index = 0
-                    }
+                let from = colors[*color_index];
+                let to = colors[(*color_index + 1) % colors.len()];
+
+                color_space.mix(from, to, *progress)
+            }
+            LedEffectKind::Wave {
+                hsv,
+                min_value,
+                period,
+                phase,
+                epoch,
+            } => Self::get_wave_hsv(*hsv, *min_value, *period, *phase, *epoch, now),
+            LedEffectKind::Chase {
+                hsv,
+                background,
+                interval,
+                position,
+                count,
+                epoch,
+            } => Self::get_chase_hsv(*hsv, *background, *interval, *position, *count, *epoch, now),
+            LedEffectKind::Fire { .. }
+            | LedEffectKind::Heartbeat { .. }
+            | LedEffectKind::Strobe { .. }
+            | LedEffectKind::Police { .. }
+            | LedEffectKind::Noise { .. }
+            | LedEffectKind::Circadian { .. } => self.get_timed_hsv(now),
+            LedEffectKind::Lightning {
+                hsv,
+                min_interval,
+                max_interval,
+                afterglow,
+                strike,
+                flashes,
+                next_strike,
+            } => {
+                // catches up on strikes missed between ticks (or before a delayed start)
+                while *next_strike <= now {
+                    *strike = Some(*next_strike);
+                    *flashes = rng.gen_range(1..=LIGHTNING_MAX_FLASHES);
+                    *next_strike += rng.gen_range(*min_interval..=*max_interval);
+                }
+
+                let factor = strike.map_or(0.0, |strike| {
+                    Self::get_lightning_factor(now.saturating_duration_since(strike), *flashes, *afterglow)
+                });
+
+                Hsv::from_components((hsv.hue, hsv.saturation, hsv.value * factor))
+            }
+            LedEffectKind::Sparkle {
+                base,
+                sparkle,
+                rate,
+                sparkle_duration,
+                sparkle_start,
+                next_sparkle,
+            } => {
+                while *next_sparkle <= now {
+                    *sparkle_start = Some(*next_sparkle);
+                    *next_sparkle += Self::get_sparkle_interval(*rate, rng);
+                }
+
+                match sparkle_start.map(|start| now.saturating_duration_since(start)) {
+                    Some(elapsed) if elapsed < *sparkle_duration => ColorSpace::Oklab.mix(
+                        *sparkle,
+                        *base,
+                        elapsed.as_secs_f32() / sparkle_duration.as_secs_f32(),
+                    ),
+                    _ => *base,
+                }
+            }
+        }
+    }
+
+    /// Color of the effects that only depend on the time elapsed since their start
+    fn get_timed_hsv(&self, now: Instant) -> Hsv {
+        match *self {
+            LedEffectKind::Fire {
+                hue,
+                saturation,
+                min_value,
+                max_value,
+                seed,
+                start,
+            } => {
+                let time = now.saturating_duration_since(start).as_secs_f32();
+                let flare = FIRE_LAYERS
+                    .iter()
+                    .enumerate()
+                    .map(|(layer, (frequency, weight))| {
+                        weight * get_noise(seed.wrapping_add(layer as u32), time * frequency)
+                    })
+                    .sum::<f32>();
+
+                Hsv::from_components((
+                    (hue + FIRE_HUE_SHIFT * flare).rem_euclid(MAX_HUE_VALUE),
+                    saturation,
+                    min_value + (max_value - min_value) * flare,
+                ))
+            }
+            LedEffectKind::Heartbeat {
+                hsv,
+                min_value,
+                beat,
+                start,
+            } => {
+                let position = (now.saturating_duration_since(start).as_secs_f32() / beat.as_secs_f32()).fract();
+                let pulse = |center: f32| {
+                    (-((position - center) / HEARTBEAT_PULSE_WIDTH).powi(2)).exp()
+                };
+                // the first pulse also peaks at the end of the beat, so it rises smoothly
+                let factor = pulse(0.0)
+                    .max(pulse(1.0))
+                    .max(HEARTBEAT_SECOND_PULSE_LEVEL * pulse(HEARTBEAT_SECOND_PULSE));
 
-                    *progress = 0.0;
+                Hsv::from_components((
+                    hsv.hue,
+                    hsv.saturation,
+                    min_value + (hsv.value - min_value) * factor,
+                ))
+            }
+            LedEffectKind::Strobe {
+                hsv,
+                period,
+                duty_cycle,
+                start,
+            } => {
+                let position = (now.saturating_duration_since(start).as_secs_f32() / period.as_secs_f32()).fract();
+
+                if position < duty_cycle {
+                    hsv
+                } else {
+                    *LED_OFF
                 }
+            }
+            LedEffectKind::Police {
+                first,
+                second,
+                interval,
+                flashes,
+                start,
+            } => {
+                let turns = now.saturating_duration_since(start).as_secs_f32() / interval.as_secs_f32();
+                let color = if (turns as u64).is_multiple_of(2) { first } else { second };
 
-                new_color
+                // each flash is followed by an equally long gap
+                if ((turns.fract() * flashes as f32 * 2.0) as u32).is_multiple_of(2) {
+                    color
+                } else {
+                    *LED_OFF
+                }
             }
+            LedEffectKind::Noise {
+                min_hue,
+                hue_range,
+                saturation,
+                min_value,
+                max_value,
+                speed,
+                octaves,
+                seed,
+                start,
+            } => {
+                let position = now.saturating_duration_since(start).as_secs_f32() * speed;
+                // hue and value follow their own noise, so they don't drift together
+                let hue_noise = get_fractal_noise(seed, position, octaves);
+                let value_noise = get_fractal_noise(seed.wrapping_add(MAX_NOISE_OCTAVES), position, octaves);
+
+                Hsv::from_components((
+                    (min_hue + hue_range * hue_noise).rem_euclid(MAX_HUE_VALUE),
+                    saturation,
+                    min_value + (max_value - min_value) * value_noise,
+                ))
+            }
+            LedEffectKind::Circadian {
+                ref profile,
+                location,
+            } => profile.get_hsv(to_local_time(now), location),
+            _ => self.get_initial_hsv_at(now),
+        }
+    }
+
+    /// Brightness (relative to the flash) `since_strike` into a burst of `flashes`
+    fn get_lightning_factor(since_strike: Duration, flashes: u32, afterglow: Duration) -> f32 {
+        let flash_cycle = LIGHTNING_FLASH_DURATION + LIGHTNING_FLASH_GAP;
+        let burst = flash_cycle * flashes - LIGHTNING_FLASH_GAP;
+
+        if since_strike < burst {
+            let in_cycle = since_strike.as_millis() % flash_cycle.as_millis();
+
+            return if in_cycle < LIGHTNING_FLASH_DURATION.as_millis() {
+                1.0
+            } else {
+                LIGHTNING_AFTERGLOW_LEVEL
+            };
+        }
+
+        let fade = (since_strike - burst).as_secs_f32() / afterglow.as_secs_f32().max(f32::EPSILON);
+
+        LIGHTNING_AFTERGLOW_LEVEL * (1.0 - fade.min(1.0)).powi(2)
+    }
+
+    /// Random time until the next sparkle, exponentially distributed to average `rate` per second
+    fn get_sparkle_interval<R: Rng>(rate: f32, rng: &mut R) -> Duration {
+        let sample = 1.0 - rng.gen::<f32>();
+
+        Duration::from_secs_f32(-sample.ln() / rate)
+    }
+
+    /// Only depends on the shared clock, so every controller of the group stays in sync
+    fn get_wave_hsv(hsv: Hsv, min_value: f32, period: Duration, phase: f32, epoch: Instant, now: Instant) -> Hsv {
+        let cycle = now.saturating_duration_since(epoch).as_secs_f32() / period.as_secs_f32() - phase;
+        let factor = (1.0 - (cycle * std::f32::consts::TAU).cos()) / 2.0;
+
+        Hsv::from_components((
+            hsv.hue,
+            hsv.saturation,
+            min_value + (hsv.value - min_value) * factor,
+        ))
+    }
+
+    /// Only depends on the shared clock, so every controller of the group stays in sync
+    fn get_chase_hsv(
+        hsv: Hsv,
+        background: Hsv,
+        interval: Duration,
+        position: usize,
+        count: usize,
+        epoch: Instant,
+        now: Instant,
+    ) -> Hsv {
+        let turn = now.saturating_duration_since(epoch).as_millis() / interval.as_millis().max(1);
+
+        if turn as usize % count == position {
+            hsv
+        } else {
+            background
         }
     }
 
@@ -338,10 +1202,11 @@ impl LedEffectKind {
         time_to_peak: f32,
         peak: f32,
         inhaling: &mut bool,
+        now: Instant,
     ) -> Hsv {
         let initial_value = initial_hsv.value;
 
-        let time_elapsed = (*last_update).elapsed().as_millis() as f32;
+        let time_elapsed = now.saturating_duration_since(*last_update).as_millis() as f32;
 
         let factor = (time_elapsed / time_to_peak).powf(2.0);
 
@@ -352,11 +1217,11 @@ impl LedEffectKind {
         };
 
         if *inhaling && new_value >= peak {
-            *last_update = Instant::now();
+            *last_update = now;
             new_value = peak;
             *inhaling = false;
         } else if !*inhaling && new_value <= initial_value {
-            *last_update = Instant::now();
+            *last_update = now;
             new_value = initial_value;
             *inhaling = true;
         }
@@ -365,6 +1230,202 @@ impl LedEffectKind {
     }
 }
 
+/// Returns `false` if there's no `value`
+fn patch_value(target: &mut f32, value: Option<f32>) -> bool {
+    if let Some(value) = value {
+        *target = value;
+    }
+
+    value.is_some()
+}
+
+/// Patches hue and saturation (the value depends on the effect)
+fn patch_hsv(hsv: &mut Hsv, patch: &LedEffectPatch) -> bool {
+    if let Some(hue) = patch.hue {
+        hsv.hue = hue.into();
+    }
+
+    patch.hue.is_some() | patch_value(&mut hsv.saturation, patch.saturation)
+}
+
+/// Moves `start` so the time elapsed since is `ratio` times what it was
+fn rescale_elapsed(start: &mut Instant, ratio: f32) {
+    let now = Instant::now();
+    let elapsed = now.saturating_duration_since(*start).mul_f32(ratio);
+
+    if let Some(rescaled) = now.checked_sub(elapsed) {
+        *start = rescaled
+    }
+}
+
+/// Smooth noise between 0.0 and 1.0, interpolating random values at every integer `x`
+fn get_noise(seed: u32, x: f32) -> f32 {
+    let index = x.floor();
+    let fraction = x - index;
+    let smooth = fraction * fraction * (3.0 - 2.0 * fraction);
+
+    let from = get_hash(seed, index as i64);
+    let to = get_hash(seed, index as i64 + 1);
+
+    from + (to - from) * smooth
+}
+
+/// 1-D Perlin noise between 0.0 and 1.0, from random gradients at every integer `x`
+fn get_perlin_noise(seed: u32, x: f32) -> f32 {
+    let index = x.floor();
+    let fraction = x - index;
+    let fade = fraction * fraction * fraction * (fraction * (fraction * 6.0 - 15.0) + 10.0);
+
+    let from = (get_hash(seed, index as i64) * 2.0 - 1.0) * fraction;
+    let to = (get_hash(seed, index as i64 + 1) * 2.0 - 1.0) * (fraction - 1.0);
+
+    // gradients between -1.0 and 1.0 keep the noise between -0.5 and 0.5
+    (from + (to - from) * fade + 0.5).clamp(0.0, 1.0)
+}
+
+/// Wall-clock time at `now`
+fn to_local_time(now: Instant) -> DateTime<Local> {
+    let present = Instant::now();
+
+    if now >= present {
+        Local::now() + TimeDelta::from_std(now - present).unwrap_or_default()
+    } else {
+        Local::now() - TimeDelta::from_std(present - now).unwrap_or_default()
+    }
+}
+
+/// Sum of `octaves` layers of Perlin noise, each one twice as fast and half as strong
+/// as the previous one, between 0.0 and 1.0
+fn get_fractal_noise(seed: u32, x: f32, octaves: u32) -> f32 {
+    let (sum, total_weight) = (0..octaves).fold((0.0, 0.0), |(sum, total_weight), octave| {
+        let frequency = (1 << octave) as f32;
+        let weight = 1.0 / frequency;
+
+        (
+            sum + weight * get_perlin_noise(seed.wrapping_add(octave), x * frequency),
+            total_weight + weight,
+        )
+    });
+
+    sum / total_weight
+}
+
+/// Deterministic pseudo-random value between 0.0 and 1.0
+fn get_hash(seed: u32, index: i64) -> f32 {
+    let mut hash = (index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ seed as u64;
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xFF51_AFD7_ED55_8CCD);
+    hash ^= hash >> 33;
+
+    (hash >> 40) as f32 / (1u64 << 24) as f32
+}
+
+/// Parameters to change on a running effect, the ones it doesn't have are ignored
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LedEffectPatch {
+    pub hue: Option<f32>,
+    pub saturation: Option<f32>,
+    /// Highest value/brightness
+    pub peak: Option<f32>,
+    /// Length of a cycle (interval, period, beat, time to peak or to complete)
+    pub interval: Option<Duration>,
+    /// Noise units travelled per second
+    pub speed: Option<f32>,
+}
+
+/// Moves the instants of a timeline, to pause it or change its speed
+#[derive(Clone, Copy, Debug)]
+pub enum TimeShift {
+    /// Later, so less time has elapsed since
+    Delay(Duration),
+    /// Earlier, so more time has elapsed since
+    Advance(Duration),
+}
+
+impl TimeShift {
+    /// Shift for a tick of `elapsed` at `speed` (0.0 holds the timeline)
+    pub fn from_speed(elapsed: Duration, speed: f32) -> TimeShift {
+        if speed < 1.0 {
+            TimeShift::Delay(elapsed.mul_f32(1.0 - speed))
+        } else {
+            TimeShift::Advance(elapsed.mul_f32(speed - 1.0))
+        }
+    }
+
+    pub fn apply(self, instant: &mut Instant) {
+        match self {
+            TimeShift::Delay(offset) => *instant += offset,
+            // can't go before the clock's origin, that's as far back as it gets
+            TimeShift::Advance(offset) => {
+                if let Some(shifted) = instant.checked_sub(offset) {
+                    *instant = shifted
+                }
+            }
+        }
+    }
+}
+
+/// Updates owed to a timeline running at some speed, carrying the fraction of an update over
+/// to the next tick when not running at real time
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UpdateProgress(f32);
+
+impl UpdateProgress {
+    /// Returns how many updates a tick at `speed` is worth
+    pub fn advance(&mut self, speed: f32) -> u32 {
+        self.0 += speed;
+        let updates = self.0.floor();
+        self.0 -= updates;
+
+        updates as u32
+    }
+}
+
+/// How the led blends into a new effect
+#[derive(Clone, Copy, Debug)]
+pub struct Transition {
+    pub duration: Duration,
+    pub color_space: ColorSpace,
+}
+
+/// Blends the displayed led from a color into the current effect's output
+#[derive(Clone, Copy)]
+pub struct LedTransition {
+    pub from: Hsv,
+    pub start: Instant,
+    pub duration: Duration,
+    pub color_space: ColorSpace,
+}
+
+impl LedTransition {
+    pub fn new(from: Hsv, transition: Transition) -> LedTransition {
+        LedTransition {
+            from,
+            start: Instant::now(),
+            duration: transition.duration,
+            color_space: transition.color_space,
+        }
+    }
+
+    pub fn shift(&mut self, shift: TimeShift) {
+        shift.apply(&mut self.start);
+    }
+
+    pub fn has_finished(&self) -> bool {
+        self.start.elapsed() >= self.duration
+    }
+
+    pub fn apply(&self, to: Hsv) -> Hsv {
+        if self.has_finished() {
+            return to;
+        }
+
+        let progress = self.start.elapsed().as_secs_f32() / self.duration.as_secs_f32();
+
+        self.color_space.mix(self.from, to, progress)
+    }
+}
+
 #[derive(Clone, Copy)]
 pub struct RumbleEffect {
     pub kind: RumbleEffectKind,
@@ -408,6 +1469,26 @@ impl RumbleEffect {
             RumbleEffect::new_expiring(kind, Duration::from_millis(millis as u64))
         })
     }
+
+    /// Moves the whole timeline (expiry and runtime state) to begin at `start`
+    pub fn start_at(&mut self, start: Instant) {
+        self.start = start;
+        self.kind.restart_at(start);
+    }
+
+    /// Moves the whole timeline (expiry and runtime state) by `shift`
+    pub fn shift(&mut self, shift: TimeShift) {
+        shift.apply(&mut self.start);
+
+        if let RumbleEffectKind::Blink { last_blink, .. } = &mut self.kind {
+            shift.apply(last_blink);
+        }
+    }
+
+    pub fn has_expired(&self) -> bool {
+        self.duration
+            .is_some_and(|duration| self.start.elapsed() > duration)
+    }
 }
 
 impl fmt::Display for RumbleEffect {
@@ -433,10 +1514,28 @@ pub enum RumbleEffectKind {
         interval: Duration,
         last_blink: Instant,
     },
+    /// Strength follows the led's brightness, from `min_strength` (led off)
+    /// to `max_strength` (full brightness)
+    FollowLed {
+        min_strength: f32,
+        max_strength: f32,
+    },
 }
 
 impl RumbleEffectKind {
-    pub fn get_updated_rumble(&mut self, mut current_rumble: f32) -> f32 {
+    /// Resets the runtime state, as if the effect was created at `start`
+    pub fn restart_at(&mut self, start: Instant) {
+        match self {
+            RumbleEffectKind::Off
+            | RumbleEffectKind::Static { .. }
+            | RumbleEffectKind::FollowLed { .. } => {}
+            RumbleEffectKind::Breathing { inhaling, .. } => *inhaling = true,
+            RumbleEffectKind::Blink { last_blink, .. } => *last_blink = start,
+        }
+    }
+
+    /// `led_value` is the brightness of the led on the same tick, at `now`
+    pub fn get_updated_rumble(&mut self, mut current_rumble: f32, led_value: f32, now: Instant) -> f32 {
         match *self {
             RumbleEffectKind::Off => 0.0,
             RumbleEffectKind::Static { strength: value } => value,
@@ -467,8 +1566,8 @@ impl RumbleEffectKind {
                 interval,
                 last_blink: ref mut start,
             } => {
-                if start.elapsed() > interval / 2 {
-                    *start = Instant::now();
+                if now.saturating_duration_since(*start) > interval / 2 {
+                    *start = now;
 
                     if current_rumble == 0.0 {
                         strength
@@ -479,6 +1578,58 @@ impl RumbleEffectKind {
                     current_rumble
                 }
             }
+            RumbleEffectKind::FollowLed {
+                min_strength,
+                max_strength,
+            } => min_strength + (max_strength - min_strength) * led_value.clamp(0.0, 1.0),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TICK: Duration = Duration::from_millis(250);
+
+    fn get_updates(speed: f32, ticks: usize) -> Vec<u32> {
+        let mut progress = UpdateProgress::default();
+        (0..ticks).map(|_| progress.advance(speed)).collect()
+    }
+
+    #[test]
+    fn updates_at_the_speed_of_the_timeline() {
+        assert_eq!(get_updates(1.0, 4), [1, 1, 1, 1]);
+        assert_eq!(get_updates(0.5, 4), [0, 1, 0, 1]);
+        assert_eq!(get_updates(2.5, 4), [2, 3, 2, 3]);
+        assert_eq!(get_updates(0.0, 4), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn shifts_by_the_time_gained_or_lost() {
+        let get_shift = |speed| match TimeShift::from_speed(TICK, speed) {
+            TimeShift::Delay(offset) => ("delay", offset),
+            TimeShift::Advance(offset) => ("advance", offset),
+        };
+
+        assert_eq!(get_shift(0.0), ("delay", TICK));
+        assert_eq!(get_shift(0.5), ("delay", TICK / 2));
+        assert_eq!(get_shift(1.0), ("advance", Duration::ZERO));
+        assert_eq!(get_shift(3.0), ("advance", TICK * 2));
+    }
+
+    #[test]
+    fn shift_moves_the_expiry() {
+        let mut effect = LedEffect::new_expiring(LedEffectKind::Off, TICK * 2);
+        effect.start = Instant::now() - TICK * 3;
+        assert!(effect.has_expired());
+
+        // paused for two ticks, so only one tick has elapsed on its timeline
+        effect.shift(TimeShift::from_speed(TICK * 2, 0.0));
+        assert!(!effect.has_expired());
+
+        // twice as fast for two ticks, so it catches up by two ticks
+        effect.shift(TimeShift::from_speed(TICK * 2, 2.0));
+        assert!(effect.has_expired());
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::VecDeque;
+use std::time::SystemTime;
+
+use crate::tasks::models::{EffectChangeType, EffectPriority};
+
+/// How many entries each controller keeps, the oldest ones are dropped first
+const MAX_ENTRIES: usize = 50;
+
+#[derive(Clone)]
+pub struct HistoryEntry {
+    pub id: u32,
+    pub time: SystemTime,
+    /// Who set the effect (a client, a schedule...)
+    pub source: String,
+    pub priority: EffectPriority,
+    /// Only led, rumble, combined or sequence changes
+    pub effect: EffectChangeType,
+}
+
+impl HistoryEntry {
+    pub fn affects_led(&self) -> bool {
+        !matches!(self.effect, EffectChangeType::Rumble { .. })
+    }
+
+    pub fn affects_rumble(&self) -> bool {
+        !matches!(self.effect, EffectChangeType::Led { .. })
+    }
+}
+
+/// Latest led and rumble entries up to a position in the history
+/// (the same one if it affects both), `None` if there are none
+pub struct HistoryState {
+    pub led: Option<HistoryEntry>,
+    pub rumble: Option<HistoryEntry>,
+}
+
+/// Effects applied on a controller, undoing moves back through them without adding entries
+/// (so undoing twice goes two steps back, instead of toggling)
+pub struct EffectHistory {
+    entries: VecDeque<HistoryEntry>,
+    next_id: u32,
+    /// How many entries make the current state, all of them unless some were undone
+    position: usize,
+}
+
+impl Default for EffectHistory {
+    fn default() -> EffectHistory {
+        EffectHistory {
+            entries: VecDeque::new(),
+            next_id: 1,
+            position: 0,
+        }
+    }
+}
+
+impl EffectHistory {
+    /// Drops the undone entries (they can't be jumped back to anymore), then adds the change
+    ///
+    /// A change from the same automatic source as the current entry, on the same channels,
+    /// replaces it (so a rule firing often doesn't push everything else out)
+    pub fn record(&mut self, source: &str, automatic: bool, priority: EffectPriority, effect: EffectChangeType) {
+        let entry = HistoryEntry {
+            id: self.next_id,
+            time: SystemTime::now(),
+            source: String::from(source),
+            priority,
+            effect,
+        };
+        self.entries.truncate(self.position);
+
+        let replaces_current = automatic
+            && self.entries.back().is_some_and(|current| {
+                current.source == entry.source
+                    && current.affects_led() == entry.affects_led()
+                    && current.affects_rumble() == entry.affects_rumble()
+            });
+
+        if replaces_current {
+            self.entries.pop_back();
+        }
+
+        self.entries.push_back(entry);
+        self.next_id += 1;
+        self.position = self.entries.len();
+
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.pop_front();
+            self.position -= 1;
+        }
+    }
+
+    /// Oldest first
+    pub fn entries(&self) -> &VecDeque<HistoryEntry> {
+        &self.entries
+    }
+
+    /// Id of the entry the controller is at, `None` if everything was undone
+    pub fn current_id(&self) -> Option<u32> {
+        self.position
+            .checked_sub(1)
+            .map(|index| self.entries[index].id)
+    }
+
+    /// Moves `steps` entries back, returning the entries that make the state there
+    pub fn undo(&mut self, steps: usize) -> HistoryState {
+        self.position = self.position.saturating_sub(steps);
+        self.get_state()
+    }
+
+    /// Moves to the entry with `id` (back or forth), returning the entries that make the
+    /// state there, `None` if there's no such entry
+    pub fn jump_to(&mut self, id: u32) -> Option<HistoryState> {
+        let index = self.entries.iter().position(|entry| entry.id == id)?;
+
+        self.position = index + 1;
+        Some(self.get_state())
+    }
+
+    fn get_state(&self) -> HistoryState {
+        let applied = || self.entries.range(..self.position).rev();
+
+        HistoryState {
+            led: applied().find(|entry| entry.affects_led()).cloned(),
+            rumble: applied().find(|entry| entry.affects_rumble()).cloned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ps_move::effects::{LedEffect, RumbleEffect};
+
+    use super::*;
+
+    fn led() -> EffectChangeType {
+        EffectChangeType::Led {
+            effect: LedEffect::off(),
+            transition: None,
+        }
+    }
+
+    fn rumble() -> EffectChangeType {
+        EffectChangeType::Rumble {
+            effect: RumbleEffect::off(),
+        }
+    }
+
+    #[test]
+    fn merges_repeated_automatic_changes() {
+        let mut history = EffectHistory::default();
+
+        history.record("client", false, EffectPriority::Normal, led());
+        (0..100).for_each(|_| history.record("rule 'a'", true, EffectPriority::Normal, led()));
+        history.record("rule 'a'", true, EffectPriority::Normal, rumble());
+
+        let sources = history.entries().iter().map(|entry| entry.source.as_str()).collect::<Vec<_>>();
+
+        assert_eq!(sources, ["client", "rule 'a'", "rule 'a'"]);
+        assert_eq!(history.current_id(), Some(102));
+    }
+
+    #[test]
+    fn drops_undone_entries_when_recording() {
+        let mut history = EffectHistory::default();
+
+        history.record("client", false, EffectPriority::Normal, led());
+        history.record("client", false, EffectPriority::Normal, rumble());
+
+        let state = history.undo(1);
+        assert!(state.rumble.is_none());
+
+        history.record("client", false, EffectPriority::Normal, led());
+
+        let state = history.undo(1);
+        assert_eq!(state.led.map(|entry| entry.id), Some(1));
+        assert!(state.rumble.is_none(), "the undone rumble came back");
+        assert_eq!(history.entries().len(), 2);
+    }
+
+    #[test]
+    fn keeps_repeated_client_changes() {
+        let mut history = EffectHistory::default();
+
+        (0..3).for_each(|_| history.record("client", false, EffectPriority::Normal, led()));
+
+        assert_eq!(history.entries().len(), 3);
+    }
+}
@@ -1,4 +1,12 @@
 pub mod api;
+pub mod colors;
 pub mod controller;
 pub mod effects;
+pub mod history;
 pub mod models;
+pub mod morse;
+pub mod output;
+pub mod priorities;
+pub mod render;
+pub mod sequence;
+pub mod snapshot;
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 use juniper::GraphQLEnum;
 use palette::Hsv;
+use serde_derive::{Deserialize, Serialize};
 use strum_macros::Display;
 
 use crate::ps_move::models::BatteryLevel::*;
@@ -72,7 +73,7 @@ pub(super) enum MoveRequestType {
     GetFirmwareInfo = 0xF9,
 }
 
-#[derive(Display, PartialEq, Copy, Clone, GraphQLEnum)]
+#[derive(Display, PartialEq, Copy, Clone, GraphQLEnum, Serialize, Deserialize, Debug)]
 pub enum BatteryLevel {
     Unknown,
     Empty,
@@ -136,6 +137,11 @@ impl BatteryLevel {
     }
 }
 
+/// Raw accelerometer reading at 1g, roughly (controllers aren't calibrated)
+const ACCELERATION_UNITS_PER_G: f32 = 4096.0;
+/// Raw gyroscope reading at 1 degree per second, roughly
+const GYROSCOPE_UNITS_PER_DEGREE: f32 = 16.4;
+
 /// Adapted from [psmoveapi's source](https://github.com/thp/psmoveapi/blob/master/src/psmove.c)
 #[allow(unused)]
 pub(super) struct DataInput {
@@ -270,4 +276,31 @@ impl DataInput {
     pub fn get_trigger(&self) -> f32 {
         (self.trigger as u16 + self.trigger2 as u16) as f32 / 2.0
     }
+
+    /// In g, averaged over both frames
+    pub fn get_accelerometer(&self) -> [f32; 3] {
+        [
+            average_axis(self.accel_x_low, self.accel_x_high, self.accel_x_low2, self.accel_x_high2),
+            average_axis(self.accel_y_low, self.accel_y_high, self.accel_y_low2, self.accel_y_high2),
+            average_axis(self.accel_z_low, self.accel_z_high, self.accel_z_low2, self.accel_z_high2),
+        ]
+        .map(|axis| axis / ACCELERATION_UNITS_PER_G)
+    }
+
+    /// In degrees per second, averaged over both frames
+    pub fn get_gyroscope(&self) -> [f32; 3] {
+        [
+            average_axis(self.gyro_x_low, self.gyro_x_high, self.gyro_x_low2, self.gyro_x_high2),
+            average_axis(self.gyro_y_low, self.gyro_y_high, self.gyro_y_low2, self.gyro_y_high2),
+            average_axis(self.gyro_z_low, self.gyro_z_high, self.gyro_z_low2, self.gyro_z_high2),
+        ]
+        .map(|axis| axis / GYROSCOPE_UNITS_PER_DEGREE)
+    }
+}
+
+/// Sensor axes are unsigned 16 bits, centered on 0x8000
+fn average_axis(low: u8, high: u8, low2: u8, high2: u8) -> f32 {
+    let axis = |low: u8, high: u8| (u16::from_le_bytes([low, high]) as i32 - 0x8000) as f32;
+
+    (axis(low, high) + axis(low2, high2)) / 2.0
 }
@@ -0,0 +1,198 @@
+use palette::Hsv;
+use tokio::time::Duration;
+
+use crate::ps_move::effects::{LedEffectKind, RumbleEffectKind};
+use crate::ps_move::sequence::SequenceItem;
+
+/// Length of each part of the code, in units
+const DOT_UNITS: u32 = 1;
+const DASH_UNITS: u32 = 3;
+const SIGNAL_GAP_UNITS: u32 = 1;
+const LETTER_GAP_UNITS: u32 = 3;
+const WORD_GAP_UNITS: u32 = 7;
+
+/// How a message is signalled, any channel left as `None` stays off
+pub struct MorseSignal {
+    pub unit: Duration,
+    pub led: Option<Hsv>,
+    /// Led while not signalling, off if `None`
+    pub background: Option<Hsv>,
+    pub rumble: Option<f32>,
+}
+
+/// Builds the items of a sequence playing `text` in Morse code, ending with a word gap
+/// if `trailing_gap` (so repetitions stay apart)
+///
+/// Returns the first character that has no Morse code, if any.
+pub fn build_items(
+    text: &str,
+    signal: &MorseSignal,
+    trailing_gap: bool,
+) -> Result<Vec<SequenceItem>, char> {
+    let words = text
+        .split_whitespace()
+        .map(|word| word.chars().map(get_code).collect::<Result<Vec<&str>, char>>())
+        .collect::<Result<Vec<Vec<&str>>, char>>()?;
+
+    let mut items = Vec::new();
+
+    for (word_index, word) in words.iter().enumerate() {
+        for (letter_index, code) in word.iter().enumerate() {
+            for (signal_index, symbol) in code.chars().enumerate() {
+                let units = if symbol == '.' { DOT_UNITS } else { DASH_UNITS };
+                items.push(build_signal_item(signal, units));
+
+                let gap = if signal_index + 1 < code.len() {
+                    SIGNAL_GAP_UNITS
+                } else if letter_index + 1 < word.len() {
+                    LETTER_GAP_UNITS
+                } else if word_index + 1 < words.len() || trailing_gap {
+                    WORD_GAP_UNITS
+                } else {
+                    0
+                };
+
+                if gap > 0 {
+                    items.push(build_gap_item(signal, gap));
+                }
+            }
+        }
+    }
+
+    Ok(items)
+}
+
+fn build_signal_item(signal: &MorseSignal, units: u32) -> SequenceItem {
+    SequenceItem {
+        led: signal
+            .led
+            .or(signal.background)
+            .map(|hsv| LedEffectKind::Static { hsv }),
+        rumble: signal
+            .rumble
+            .map(|strength| RumbleEffectKind::Static { strength }),
+        duration: signal.unit * units,
+    }
+}
+
+fn build_gap_item(signal: &MorseSignal, units: u32) -> SequenceItem {
+    SequenceItem {
+        led: signal.background.map(|hsv| LedEffectKind::Static { hsv }),
+        rumble: None,
+        duration: signal.unit * units,
+    }
+}
+
+/// International Morse code of a character (case-insensitive)
+fn get_code(character: char) -> Result<&'static str, char> {
+    let code = match character.to_ascii_uppercase() {
+        'A' => ".-",
+        'B' => "-...",
+        'C' => "-.-.",
+        'D' => "-..",
+        'E' => ".",
+        'F' => "..-.",
+        'G' => "--.",
+        'H' => "....",
+        'I' => "..",
+        'J' => ".---",
+        'K' => "-.-",
+        'L' => ".-..",
+        'M' => "--",
+        'N' => "-.",
+        'O' => "---",
+        'P' => ".--.",
+        'Q' => "--.-",
+        'R' => ".-.",
+        'S' => "...",
+        'T' => "-",
+        'U' => "..-",
+        'V' => "...-",
+        'W' => ".--",
+        'X' => "-..-",
+        'Y' => "-.--",
+        'Z' => "--..",
+        '0' => "-----",
+        '1' => ".----",
+        '2' => "..---",
+        '3' => "...--",
+        '4' => "....-",
+        '5' => ".....",
+        '6' => "-....",
+        '7' => "--...",
+        '8' => "---..",
+        '9' => "----.",
+        '.' => ".-.-.-",
+        ',' => "--..--",
+        '?' => "..--..",
+        '\'' => ".----.",
+        '!' => "-.-.--",
+        '/' => "-..-.",
+        '(' => "-.--.",
+        ')' => "-.--.-",
+        '&' => ".-...",
+        ':' => "---...",
+        ';' => "-.-.-.",
+        '=' => "-...-",
+        '+' => ".-.-.",
+        '-' => "-....-",
+        '_' => "..--.-",
+        '"' => ".-..-.",
+        '$' => "...-..-",
+        '@' => ".--.-.",
+        _ => return Err(character),
+    };
+
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIGNAL: MorseSignal = MorseSignal {
+        unit: Duration::from_millis(100),
+        led: Some(Hsv::new_const(palette::RgbHue::new(0.0), 0.0, 1.0)),
+        background: None,
+        rumble: None,
+    };
+
+    /// "SOS A": signals and gaps alternating, the gaps being within a letter,
+    /// between letters, then between words
+    const SOS_A_UNITS: [u32; 22] = [
+        1, 1, 1, 1, 1, 3, // S
+        3, 1, 3, 1, 3, 3, // O
+        1, 1, 1, 1, 1, 7, // S
+        1, 1, 3, 7, // A
+    ];
+
+    fn get_units(items: &[SequenceItem]) -> Vec<u32> {
+        items
+            .iter()
+            .map(|item| (item.duration.as_millis() / SIGNAL.unit.as_millis()) as u32)
+            .collect()
+    }
+
+    #[test]
+    fn builds_the_units_of_letters_and_words() {
+        let items = build_items("SOS A", &SIGNAL, false).unwrap();
+
+        assert_eq!(get_units(&items), SOS_A_UNITS[..SOS_A_UNITS.len() - 1]);
+        items.iter().enumerate().for_each(|(index, item)| {
+            // the signals are lit, the gaps off
+            assert_eq!(item.led.is_some(), index % 2 == 0, "item {index}");
+        });
+    }
+
+    #[test]
+    fn ends_with_a_word_gap_if_trailing() {
+        let items = build_items("sos a", &SIGNAL, true).unwrap();
+
+        assert_eq!(get_units(&items), SOS_A_UNITS);
+    }
+
+    #[test]
+    fn rejects_characters_without_code() {
+        assert_eq!(build_items("SOS #", &SIGNAL, false).err(), Some('#'));
+    }
+}
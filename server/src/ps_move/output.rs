@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use palette::{FromColor, Hsv, Srgb};
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, PartialEq, Copy, Clone, Debug)]
+pub struct ChannelCalibration {
+    pub gain: f32,
+    /// Only added to a lit channel, so "off" stays off
+    pub offset: f32,
+}
+
+impl Default for ChannelCalibration {
+    fn default() -> ChannelCalibration {
+        ChannelCalibration {
+            gain: 1.0,
+            offset: 0.0,
+        }
+    }
+}
+
+impl ChannelCalibration {
+    fn apply(&self, value: f32) -> f32 {
+        if value <= 0.0 {
+            return 0.0;
+        }
+
+        (value * self.gain + self.offset).clamp(0.0, 1.0)
+    }
+}
+
+/// Per-channel correction, since each controller's LEDs differ slightly
+#[derive(Serialize, Deserialize, Default, PartialEq, Copy, Clone, Debug)]
+pub struct ColorCalibration {
+    pub red: ChannelCalibration,
+    pub green: ChannelCalibration,
+    pub blue: ChannelCalibration,
+}
+
+/// Persisted color output configuration, global and per controller (by bt address)
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct OutputSettings {
+    pub gamma: f32,
+    pub brightness_cap: f32,
+    /// From the active theme
+    pub theme_brightness: f32,
+    pub dithering: bool,
+    pub calibrations: HashMap<String, ColorCalibration>,
+}
+
+impl Default for OutputSettings {
+    fn default() -> OutputSettings {
+        OutputSettings {
+            gamma: 1.0,
+            brightness_cap: 1.0,
+            theme_brightness: 1.0,
+            dithering: false,
+            calibrations: HashMap::new(),
+        }
+    }
+}
+
+impl OutputSettings {
+    pub fn for_controller(&self, bt_address: &str) -> ColorOutput {
+        ColorOutput {
+            gamma: self.gamma,
+            brightness_cap: self.brightness_cap,
+            theme_brightness: self.theme_brightness,
+            dithering: self.dithering,
+            calibration: self
+                .calibrations
+                .get(bt_address)
+                .copied()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Converts the led color into what's actually sent to the controller
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct ColorOutput {
+    pub gamma: f32,
+    pub brightness_cap: f32,
+    pub theme_brightness: f32,
+    pub dithering: bool,
+    pub calibration: ColorCalibration,
+}
+
+impl Default for ColorOutput {
+    fn default() -> ColorOutput {
+        OutputSettings::default().for_controller("")
+    }
+}
+
+impl ColorOutput {
+    /// Returns each channel from 0.0 to 1.0 (theme brightness -> brightness cap -> gamma -> calibration)
+    pub fn convert(&self, hsv: Hsv) -> [f32; 3] {
+        let rgb = Srgb::from_color(hsv);
+        let calibration = &self.calibration;
+
+        let brightness = self.theme_brightness * self.brightness_cap;
+        let correct = |value: f32| (value * brightness).clamp(0.0, 1.0).powf(self.gamma);
+
+        [
+            calibration.red.apply(correct(rgb.red)),
+            calibration.green.apply(correct(rgb.green)),
+            calibration.blue.apply(correct(rgb.blue)),
+        ]
+    }
+}
+
+/// Error diffusion across updates, so that a channel between two 8-bit levels
+/// alternates between them, averaging to the intended level
+#[derive(Default, Copy, Clone, Debug)]
+pub struct TemporalDither {
+    error: [f32; 3],
+}
+
+impl TemporalDither {
+    pub fn quantize(&mut self, rgb: [f32; 3], enabled: bool) -> [u8; 3] {
+        if !enabled {
+            self.error = [0.0; 3];
+            return rgb.map(|channel| (channel * 255.0).round() as u8);
+        }
+
+        let mut quantized = [0_u8; 3];
+
+        for (i, channel) in rgb.iter().enumerate() {
+            // a channel that's off must stay off, instead of leaking leftover error
+            if *channel <= 0.0 {
+                self.error[i] = 0.0;
+                continue;
+            }
+
+            let target = channel * 255.0 + self.error[i];
+            let level = target.round().clamp(0.0, 255.0);
+
+            self.error[i] = target - level;
+            quantized[i] = level as u8;
+        }
+
+        quantized
+    }
+}
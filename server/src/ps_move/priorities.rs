@@ -0,0 +1,226 @@
+use std::collections::BTreeMap;
+
+use crate::ps_move::effects::{LedEffect, RumbleEffect};
+use crate::tasks::models::EffectPriority;
+
+/// An effect of a channel (led or rumble), as the priorities keep it
+pub trait ChannelEffect: Clone {
+    /// Shown once every effect is gone
+    fn off() -> Self;
+
+    fn has_expired(&self) -> bool;
+}
+
+impl ChannelEffect for LedEffect {
+    fn off() -> LedEffect {
+        LedEffect::off()
+    }
+
+    fn has_expired(&self) -> bool {
+        LedEffect::has_expired(self)
+    }
+}
+
+impl ChannelEffect for RumbleEffect {
+    fn off() -> RumbleEffect {
+        RumbleEffect::off()
+    }
+
+    fn has_expired(&self) -> bool {
+        RumbleEffect::has_expired(self)
+    }
+}
+
+/// Priority of the effect shown on a channel, with the effects of lower priorities it replaced
+/// (at most one for each), resumed once it's gone
+pub struct PriorityStack<E: ChannelEffect> {
+    pub priority: EffectPriority,
+    suspended: BTreeMap<EffectPriority, E>,
+}
+
+impl<E: ChannelEffect> Default for PriorityStack<E> {
+    fn default() -> PriorityStack<E> {
+        PriorityStack {
+            priority: EffectPriority::Ambient,
+            suspended: BTreeMap::new(),
+        }
+    }
+}
+
+impl<E: ChannelEffect> PriorityStack<E> {
+    /// Returns `effect` back if it replaces the `shown` one (kept if its priority is lower),
+    /// otherwise it's kept until the higher priorities are gone
+    pub fn push(&mut self, effect: E, priority: EffectPriority, shown: &E) -> Result<E, &E> {
+        if priority < self.priority {
+            self.suspended.insert(priority, effect);
+            return Err(&self.suspended[&priority]);
+        }
+
+        if priority > self.priority {
+            self.suspended.insert(self.priority, shown.clone());
+        }
+
+        self.priority = priority;
+        Ok(effect)
+    }
+
+    /// Drops the effects of `priority`, returning the effect to show instead if they were shown
+    pub fn release(&mut self, priority: EffectPriority) -> Option<E> {
+        if self.priority != priority {
+            self.suspended.remove(&priority);
+            return None;
+        }
+
+        Some(self.resume())
+    }
+
+    /// Highest priority effect kept which hasn't expired (on its original timeline),
+    /// off if none is left
+    pub fn resume(&mut self) -> E {
+        while let Some((priority, effect)) = self.suspended.pop_last() {
+            if !effect.has_expired() {
+                self.priority = priority;
+                return effect;
+            }
+        }
+
+        self.priority = EffectPriority::Ambient;
+        E::off()
+    }
+
+    pub fn suspended_mut(&mut self) -> impl Iterator<Item = &mut E> {
+        self.suspended.values_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::time::{Duration, Instant};
+
+    use crate::ps_move::effects::LedEffectKind;
+
+    use super::*;
+
+    fn get_effect(value: f32) -> LedEffect {
+        LedEffect::new(LedEffectKind::Static {
+            hsv: palette::Hsv::new(0.0, 0.0, value),
+        })
+    }
+
+    /// Pushes an effect expected to show over `shown`
+    fn show(
+        stack: &mut PriorityStack<LedEffect>,
+        effect: LedEffect,
+        priority: EffectPriority,
+        shown: &LedEffect,
+    ) -> LedEffect {
+        match stack.push(effect, priority, shown) {
+            Ok(effect) => effect,
+            Err(kept) => panic!("'{kept}' was kept"),
+        }
+    }
+
+    fn get_value(effect: &LedEffect) -> f32 {
+        effect.kind.get_initial_hsv().value
+    }
+
+    #[test]
+    fn keeps_lower_priorities_until_released() {
+        let mut stack = PriorityStack::default();
+        let normal = show(
+            &mut stack,
+            get_effect(0.1),
+            EffectPriority::Normal,
+            &LedEffect::off(),
+        );
+        let alarm = show(&mut stack, get_effect(0.2), EffectPriority::Alarm, &normal);
+
+        assert!(stack
+            .push(get_effect(0.3), EffectPriority::Notification, &alarm)
+            .is_err());
+        assert_eq!(stack.priority, EffectPriority::Alarm);
+
+        let resumed = stack.release(EffectPriority::Alarm).unwrap();
+        assert_eq!(
+            (stack.priority, get_value(&resumed)),
+            (EffectPriority::Notification, 0.3)
+        );
+
+        let resumed = stack.release(EffectPriority::Notification).unwrap();
+        assert_eq!(
+            (stack.priority, get_value(&resumed)),
+            (EffectPriority::Normal, 0.1)
+        );
+    }
+
+    #[test]
+    fn replaces_the_kept_effect_of_the_same_priority() {
+        let mut stack = PriorityStack::default();
+        let alarm = show(
+            &mut stack,
+            get_effect(0.1),
+            EffectPriority::Alarm,
+            &LedEffect::off(),
+        );
+
+        assert!(stack
+            .push(get_effect(0.2), EffectPriority::Normal, &alarm)
+            .is_err());
+        assert!(stack
+            .push(get_effect(0.3), EffectPriority::Normal, &alarm)
+            .is_err());
+
+        let resumed = stack.resume();
+        assert_eq!(
+            (stack.priority, get_value(&resumed)),
+            (EffectPriority::Normal, 0.3)
+        );
+        assert!(stack.resume().is_off());
+        assert_eq!(stack.priority, EffectPriority::Ambient);
+    }
+
+    #[test]
+    fn skips_expired_kept_effects() {
+        let mut stack = PriorityStack::default();
+        let mut expired = LedEffect::new_expiring(get_effect(0.1).kind, Duration::from_millis(100));
+        expired.start = Instant::now() - Duration::from_secs(1);
+
+        show(
+            &mut stack,
+            get_effect(0.2),
+            EffectPriority::Ambient,
+            &LedEffect::off(),
+        );
+        let alarm = show(
+            &mut stack,
+            get_effect(0.3),
+            EffectPriority::Alarm,
+            &get_effect(0.2),
+        );
+        assert!(stack.push(expired, EffectPriority::Normal, &alarm).is_err());
+
+        let resumed = stack.release(EffectPriority::Alarm).unwrap();
+        assert_eq!(
+            (stack.priority, get_value(&resumed)),
+            (EffectPriority::Ambient, 0.2)
+        );
+    }
+
+    #[test]
+    fn drops_released_kept_effects() {
+        let mut stack = PriorityStack::default();
+        let alarm = show(
+            &mut stack,
+            get_effect(0.1),
+            EffectPriority::Alarm,
+            &LedEffect::off(),
+        );
+
+        assert!(stack
+            .push(get_effect(0.2), EffectPriority::Normal, &alarm)
+            .is_err());
+        assert!(stack.release(EffectPriority::Normal).is_none());
+        assert_eq!(stack.priority, EffectPriority::Alarm);
+        assert!(stack.resume().is_off());
+    }
+}
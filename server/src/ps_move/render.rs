@@ -0,0 +1,247 @@
+use std::io::Write;
+
+use palette::{FromColor, Hsv, Srgb};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tokio::time::{Duration, Instant};
+
+use crate::ps_move::effects::{LedEffectKind, RumbleEffectKind};
+use crate::tasks::effects_update::INTERVAL_DURATION;
+
+/// Size in pixels of each frame in a strip, the rumble being a gray band under the led
+const STRIP_FRAME_WIDTH: usize = 4;
+const STRIP_LED_HEIGHT: usize = 32;
+const STRIP_RUMBLE_HEIGHT: usize = 8;
+
+/// State of the led and rumble `time` after the effects started
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Frame {
+    pub time: Duration,
+    pub hsv: Hsv,
+    pub rumble: f32,
+}
+
+/// Steps the effects on a virtual clock, tick by tick as a controller would, without any hardware
+///
+/// Returns a frame every `sample_interval` (from the start up to `duration`, included),
+/// which is at least a tick since nothing changes in between.
+/// The randomness (including the seed of fires and noises) is drawn from `seed`, so the same
+/// arguments always render the same frames.
+pub fn render(
+    led: &LedEffectKind,
+    rumble: &RumbleEffectKind,
+    duration: Duration,
+    sample_interval: Duration,
+    seed: u64,
+) -> Vec<Frame> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut led = led.clone();
+    let mut rumble = *rumble;
+    let sample_interval = sample_interval.max(INTERVAL_DURATION);
+    let start = Instant::now();
+
+    led.reseed(rng.gen());
+    led.restart_with(start, &mut rng);
+    rumble.restart_at(start);
+
+    // the shared clock starts with the effect, otherwise the phase would depend on when it was created
+    if let LedEffectKind::Wave { epoch, .. } | LedEffectKind::Chase { epoch, .. } = &mut led {
+        *epoch = start;
+    }
+
+    let mut hsv = led.get_initial_hsv_at(start);
+    let mut strength = 0.0;
+    let mut elapsed = Duration::ZERO;
+    let mut frames = Vec::new();
+    let mut time = Duration::ZERO;
+
+    while time <= duration {
+        // the state shown at `time` is the one of the last tick before it
+        while elapsed + INTERVAL_DURATION <= time {
+            elapsed += INTERVAL_DURATION;
+
+            let now = start + elapsed;
+
+            hsv = led.get_updated_hsv(hsv, now, &mut rng);
+            strength = rumble.get_updated_rumble(strength, hsv.value, now);
+        }
+
+        frames.push(Frame {
+            time,
+            hsv,
+            rumble: strength,
+        });
+
+        time += sample_interval;
+    }
+
+    frames
+}
+
+/// One line per frame, with the time in milliseconds
+pub fn write_csv<W: Write>(frames: &[Frame], mut writer: W) -> std::io::Result<()> {
+    writeln!(writer, "time,hue,saturation,value,hex,rumble")?;
+
+    for frame in frames {
+        writeln!(
+            writer,
+            "{},{:.2},{:.4},{:.4},#{:x},{:.4}",
+            frame.time.as_millis(),
+            frame.hsv.hue.into_positive_degrees(),
+            frame.hsv.saturation,
+            frame.hsv.value,
+            to_rgb(frame.hsv),
+            frame.rumble,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Strip of the frames from left to right, the led on top of the rumble
+pub fn write_png<W: Write>(frames: &[Frame], writer: W) -> Result<(), png::EncodingError> {
+    let width = frames.len() * STRIP_FRAME_WIDTH;
+    let height = STRIP_LED_HEIGHT + STRIP_RUMBLE_HEIGHT;
+    let mut data = Vec::with_capacity(width * height * 3);
+
+    for row in 0..height {
+        for frame in frames {
+            let pixel = if row < STRIP_LED_HEIGHT {
+                let rgb = to_rgb(frame.hsv);
+
+                [rgb.red, rgb.green, rgb.blue]
+            } else {
+                [(frame.rumble.clamp(0.0, 1.0) * 255.0).round() as u8; 3]
+            };
+
+            for _ in 0..STRIP_FRAME_WIDTH {
+                data.extend_from_slice(&pixel);
+            }
+        }
+    }
+
+    let mut encoder = png::Encoder::new(writer, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&data)?;
+    writer.finish()
+}
+
+fn to_rgb(hsv: Hsv) -> Srgb<u8> {
+    Srgb::from_color(hsv).into_format::<u8>()
+}
+
+#[cfg(test)]
+mod tests {
+    use palette::Hsv;
+
+    use crate::ps_move::colors::ColorSpace;
+
+    use super::*;
+
+    const WHITE: Hsv = Hsv::new_const(palette::RgbHue::new(0.0), 0.0, 1.0);
+
+    /// One of each kind (but the circadian, which follows the wall clock), created anew so
+    /// anything random at creation differs between calls
+    fn get_led_kinds() -> Vec<LedEffectKind> {
+        let now = Instant::now();
+
+        vec![
+            LedEffectKind::Off,
+            LedEffectKind::Static { hsv: WHITE },
+            LedEffectKind::new_timed_breathing(Hsv::new(0.0, 1.0, 0.1), Duration::from_millis(700), 0.9),
+            LedEffectKind::new_timed_rainbow(1.0, 1.0, Duration::from_secs(2)),
+            LedEffectKind::Blink {
+                hsv: WHITE,
+                interval: Duration::from_millis(300),
+                last_blink: now,
+            },
+            LedEffectKind::new_candle(30.0, 1.0, 0.3, 0.9, 0.5, None),
+            LedEffectKind::new_palette_candle(
+                vec![Hsv::new(20.0, 1.0, 1.0), Hsv::new(40.0, 0.8, 1.0)],
+                0.3,
+                0.9,
+                0.5,
+                Some(50),
+            ),
+            LedEffectKind::new_bounce(vec![Hsv::new(0.0, 1.0, 1.0), Hsv::new(240.0, 1.0, 1.0)], 0.02, ColorSpace::Oklab),
+            LedEffectKind::Wave {
+                hsv: WHITE,
+                min_value: 0.1,
+                period: Duration::from_millis(800),
+                phase: 0.25,
+                epoch: now,
+            },
+            LedEffectKind::Chase {
+                hsv: WHITE,
+                background: Hsv::new(0.0, 0.0, 0.1),
+                interval: Duration::from_millis(200),
+                position: 1,
+                count: 3,
+                epoch: now,
+            },
+            LedEffectKind::new_fire(20.0, 1.0, 0.2, 1.0),
+            LedEffectKind::new_lightning(
+                WHITE,
+                Duration::from_millis(100),
+                Duration::from_millis(600),
+                Duration::from_millis(200),
+            ),
+            LedEffectKind::new_heartbeat(Hsv::new(0.0, 1.0, 1.0), 0.1, 72.0),
+            LedEffectKind::new_strobe(WHITE, 8.0, 0.25),
+            LedEffectKind::new_police(Hsv::new(0.0, 1.0, 1.0), Hsv::new(240.0, 1.0, 1.0), Duration::from_millis(250), 2),
+            LedEffectKind::new_noise(180.0, 90.0, 1.0, 0.2, 1.0, 2.0, 3, None),
+            LedEffectKind::new_sparkle(Hsv::new(200.0, 1.0, 0.3), WHITE, 10.0, Duration::from_millis(50)),
+        ]
+    }
+
+    #[test]
+    fn renders_the_same_frames_with_the_same_seed() {
+        let rumble = RumbleEffectKind::FollowLed {
+            min_strength: 0.0,
+            max_strength: 1.0,
+        };
+
+        for (first, second) in get_led_kinds().iter().zip(get_led_kinds().iter()) {
+            let first_frames = render(first, &rumble, Duration::from_secs(3), Duration::from_millis(10), 42);
+            let second_frames = render(second, &rumble, Duration::from_secs(3), Duration::from_millis(10), 42);
+
+            assert_eq!(first_frames, second_frames, "{first} isn't deterministic");
+        }
+    }
+
+    #[test]
+    fn renders_the_strobe_duty_cycle() {
+        // a period of 125ms, lit during its first 31.25ms
+        let strobe = LedEffectKind::new_strobe(WHITE, 8.0, 0.25);
+        let frames = render(&strobe, &RumbleEffectKind::Off, Duration::from_secs(1), Duration::from_millis(10), 0);
+
+        for frame in frames {
+            let lit = frame.time.as_millis() % 125 <= 30;
+
+            assert_eq!(frame.hsv.value, if lit { 1.0 } else { 0.0 }, "at {:?}", frame.time);
+        }
+    }
+
+    #[test]
+    fn renders_the_heartbeat_peaks() {
+        // a beat every second, peaking at its start and again (lower) 300ms later
+        let heartbeat = LedEffectKind::new_heartbeat(WHITE, 0.0, 60.0);
+        let frames = render(&heartbeat, &RumbleEffectKind::Off, Duration::from_secs(2), Duration::from_millis(100), 0);
+        let value_at = |millis: u128| {
+            frames
+                .iter()
+                .find(|frame| frame.time.as_millis() == millis)
+                .map(|frame| frame.hsv.value)
+                .unwrap()
+        };
+
+        for beat in [0, 1000] {
+            assert!((value_at(beat) - 1.0).abs() < 0.01);
+            assert!((value_at(beat + 300) - 0.6).abs() < 0.01);
+            assert!(value_at(beat + 600) < 0.01);
+        }
+    }
+}
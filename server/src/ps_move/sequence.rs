@@ -0,0 +1,193 @@
+use std::fmt;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+use palette::Hsv;
+use tokio::time::{Duration, Instant};
+
+use crate::ps_move::effects::{LedEffectKind, RumbleEffectKind, TimeShift, Transition};
+use crate::tasks::models::EffectPriority;
+
+/// A list of effects played one after another
+pub struct EffectSequence {
+    pub name: Option<String>,
+    pub items: Vec<SequenceItem>,
+    /// How many times the whole sequence plays, `None` loops forever
+    pub loops: Option<u32>,
+    /// How the led blends from one item into the next
+    pub crossfade: Option<Transition>,
+}
+
+/// A channel not present in an item is turned off while that item plays
+#[derive(Clone)]
+pub struct SequenceItem {
+    pub led: Option<LedEffectKind>,
+    pub rumble: Option<RumbleEffectKind>,
+    pub duration: Duration,
+}
+
+impl fmt::Display for EffectSequence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "Sequence '{name}' ({} items)", self.items.len()),
+            None => write!(f, "Sequence ({} items)", self.items.len()),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SequencePlayer {
+    pub sequence: Arc<EffectSequence>,
+    pub index: usize,
+    pub completed_loops: u32,
+    pub item_start: Instant,
+    /// Its items are set with this priority
+    pub priority: EffectPriority,
+}
+
+impl SequencePlayer {
+    /// Its first item starts at `start`
+    pub fn new(sequence: Arc<EffectSequence>, priority: EffectPriority, start: Instant) -> SequencePlayer {
+        SequencePlayer {
+            sequence,
+            index: 0,
+            completed_loops: 0,
+            item_start: start,
+            priority,
+        }
+    }
+
+    pub fn current_item(&self) -> &SequenceItem {
+        &self.sequence.items[self.index]
+    }
+
+    pub fn shift(&mut self, shift: TimeShift) {
+        shift.apply(&mut self.item_start);
+    }
+
+    /// Recolors the led of every item, see [`LedEffectKind::recolor`]
+    pub fn recolor(&mut self, colors: &[Hsv], offset: usize) {
+        let items = self
+            .sequence
+            .items
+            .iter()
+            .map(|item| {
+                let mut item = item.clone();
+
+                if let Some(led) = item.led.as_mut() {
+                    led.recolor(colors, offset);
+                }
+                item
+            })
+            .collect();
+
+        self.sequence = Arc::new(EffectSequence {
+            name: self.sequence.name.clone(),
+            items,
+            loops: self.sequence.loops,
+            crossfade: self.sequence.crossfade,
+        });
+    }
+
+    pub fn has_item_expired(&self) -> bool {
+        self.item_start.elapsed() >= self.current_item().duration
+    }
+
+    pub fn item_remaining(&self) -> Duration {
+        self.current_item()
+            .duration
+            .saturating_sub(self.item_start.elapsed())
+    }
+
+    /// Moves to the next item (wrapping around if there are loops left)
+    ///
+    /// The next item starts when the current one ends, rather than on the tick noticing it
+    /// (so the timing doesn't drift), or now if it's skipped early.
+    ///
+    /// Returns `false` if the sequence has finished.
+    pub fn advance(&mut self) -> bool {
+        let item_end = self.item_start + self.current_item().duration;
+
+        self.index += 1;
+
+        if self.index >= self.sequence.items.len() {
+            self.completed_loops += 1;
+
+            if self
+                .sequence
+                .loops
+                .is_some_and(|loops| self.completed_loops >= loops)
+            {
+                return false;
+            }
+
+            self.index = 0;
+        }
+
+        self.item_start = item_end.min(Instant::now());
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ITEM_DURATION: Duration = Duration::from_millis(100);
+
+    fn get_player(loops: Option<u32>, start: Instant) -> SequencePlayer {
+        let item = SequenceItem {
+            led: Some(LedEffectKind::Off),
+            rumble: None,
+            duration: ITEM_DURATION,
+        };
+        let sequence = EffectSequence {
+            name: None,
+            items: vec![item.clone(), item],
+            loops,
+            crossfade: None,
+        };
+
+        SequencePlayer::new(Arc::new(sequence), EffectPriority::Normal, start)
+    }
+
+    #[test]
+    fn ends_after_its_loops() {
+        let mut player = get_player(Some(2), Instant::now());
+
+        assert!((0..3).all(|_| player.advance()));
+        assert_eq!((player.index, player.completed_loops), (1, 1));
+        assert!(!player.advance());
+    }
+
+    #[test]
+    fn wraps_around_without_loops() {
+        let mut player = get_player(None, Instant::now());
+
+        (0..5).for_each(|_| assert!(player.advance()));
+
+        assert_eq!((player.index, player.completed_loops), (1, 2));
+    }
+
+    #[test]
+    fn keeps_the_rhythm_when_ticked_late() {
+        let start = Instant::now() - ITEM_DURATION * 3;
+        let mut player = get_player(None, start);
+
+        player.advance();
+        assert_eq!(player.item_start, start + ITEM_DURATION);
+
+        player.advance();
+        assert_eq!(player.item_start, start + ITEM_DURATION * 2);
+    }
+
+    #[test]
+    fn starts_the_next_item_now_when_skipped_early() {
+        let mut player = get_player(None, Instant::now());
+
+        player.advance();
+
+        assert!(player.item_start <= Instant::now());
+        assert!(!player.has_item_expired());
+    }
+}
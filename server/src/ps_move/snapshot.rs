@@ -0,0 +1,971 @@
+use std::sync::Arc;
+
+use serde_derive::{Deserialize, Serialize};
+use strum_macros::Display;
+use tokio::time::{Duration, Instant};
+
+use crate::palettes::PaletteColor;
+use crate::ps_move::colors::{ColorSpace, MAX_KELVIN, MIN_KELVIN};
+use crate::ps_move::effects::{LedEffectKind, RumbleEffectKind};
+use crate::schedules::{CircadianProfile, Location};
+
+/// Parameters of a led effect, without its runtime state (so it can be saved and rebuilt)
+///
+/// Times are in milliseconds.
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug, Display)]
+pub enum LedEffectSnapshot {
+    Off,
+    Static {
+        color: PaletteColor,
+    },
+    Breathing {
+        initial_color: PaletteColor,
+        time_to_peak: i32,
+        peak: f32,
+    },
+    Rainbow {
+        saturation: f32,
+        value: f32,
+        step: f32,
+    },
+    Blink {
+        color: PaletteColor,
+        interval: u64,
+    },
+    Candle {
+        hue: f32,
+        saturation: f32,
+        min_value: f32,
+        max_value: f32,
+        variability: f32,
+        interval: i32,
+        /// Flickers through these colors instead of `hue` and `saturation`, if any
+        palette: Option<Vec<PaletteColor>>,
+    },
+    Bounce {
+        colors: Vec<PaletteColor>,
+        step: f32,
+        color_space: ColorSpace,
+    },
+    Wave {
+        color: PaletteColor,
+        min_value: f32,
+        period: u64,
+        phase: f32,
+    },
+    Chase {
+        color: PaletteColor,
+        background: PaletteColor,
+        interval: u64,
+        position: usize,
+        count: usize,
+    },
+    Fire {
+        hue: f32,
+        saturation: f32,
+        min_value: f32,
+        max_value: f32,
+        seed: u32,
+    },
+    Lightning {
+        color: PaletteColor,
+        min_interval: u64,
+        max_interval: u64,
+        afterglow: u64,
+    },
+    Heartbeat {
+        color: PaletteColor,
+        min_value: f32,
+        beat: u64,
+    },
+    Strobe {
+        color: PaletteColor,
+        period: u64,
+        duty_cycle: f32,
+    },
+    Police {
+        first: PaletteColor,
+        second: PaletteColor,
+        interval: u64,
+        flashes: u32,
+    },
+    Noise {
+        min_hue: f32,
+        hue_range: f32,
+        saturation: f32,
+        min_value: f32,
+        max_value: f32,
+        speed: f32,
+        octaves: u32,
+        seed: u32,
+    },
+    Sparkle {
+        base: PaletteColor,
+        sparkle: PaletteColor,
+        rate: f32,
+        sparkle_duration: u64,
+    },
+    Circadian {
+        profile: CircadianProfile,
+        location: Location,
+    },
+}
+
+impl LedEffectSnapshot {
+    pub fn from(kind: &LedEffectKind) -> LedEffectSnapshot {
+        let millis = |duration: &Duration| duration.as_millis() as u64;
+
+        match kind {
+            LedEffectKind::Off => LedEffectSnapshot::Off,
+            LedEffectKind::Static { hsv } => LedEffectSnapshot::Static {
+                color: PaletteColor::from(*hsv),
+            },
+            LedEffectKind::Breathing {
+                initial_hsv,
+                time_to_peak,
+                peak,
+                ..
+            } => LedEffectSnapshot::Breathing {
+                initial_color: PaletteColor::from(*initial_hsv),
+                time_to_peak: *time_to_peak,
+                peak: *peak,
+            },
+            LedEffectKind::Rainbow {
+                saturation,
+                value,
+                step,
+            } => LedEffectSnapshot::Rainbow {
+                saturation: *saturation,
+                value: *value,
+                step: *step,
+            },
+            LedEffectKind::Blink { hsv, interval, .. } => LedEffectSnapshot::Blink {
+                color: PaletteColor::from(*hsv),
+                interval: millis(interval),
+            },
+            LedEffectKind::Candle {
+                hue,
+                saturation,
+                min_value,
+                max_value,
+                variability,
+                interval,
+                palette,
+                ..
+            } => LedEffectSnapshot::Candle {
+                hue: *hue,
+                saturation: *saturation,
+                min_value: *min_value,
+                max_value: *max_value,
+                variability: *variability,
+                interval: *interval,
+                palette: palette
+                    .as_ref()
+                    .map(|colors| colors.iter().map(|hsv| PaletteColor::from(*hsv)).collect()),
+            },
+            LedEffectKind::Bounce {
+                colors,
+                step,
+                color_space,
+                ..
+            } => LedEffectSnapshot::Bounce {
+                colors: colors.iter().map(|hsv| PaletteColor::from(*hsv)).collect(),
+                step: *step,
+                color_space: *color_space,
+            },
+            LedEffectKind::Wave {
+                hsv,
+                min_value,
+                period,
+                phase,
+                ..
+            } => LedEffectSnapshot::Wave {
+                color: PaletteColor::from(*hsv),
+                min_value: *min_value,
+                period: millis(period),
+                phase: *phase,
+            },
+            LedEffectKind::Chase {
+                hsv,
+                background,
+                interval,
+                position,
+                count,
+                ..
+            } => LedEffectSnapshot::Chase {
+                color: PaletteColor::from(*hsv),
+                background: PaletteColor::from(*background),
+                interval: millis(interval),
+                position: *position,
+                count: *count,
+            },
+            LedEffectKind::Fire {
+                hue,
+                saturation,
+                min_value,
+                max_value,
+                seed,
+                ..
+            } => LedEffectSnapshot::Fire {
+                hue: *hue,
+                saturation: *saturation,
+                min_value: *min_value,
+                max_value: *max_value,
+                seed: *seed,
+            },
+            LedEffectKind::Lightning {
+                hsv,
+                min_interval,
+                max_interval,
+                afterglow,
+                ..
+            } => LedEffectSnapshot::Lightning {
+                color: PaletteColor::from(*hsv),
+                min_interval: millis(min_interval),
+                max_interval: millis(max_interval),
+                afterglow: millis(afterglow),
+            },
+            LedEffectKind::Heartbeat {
+                hsv,
+                min_value,
+                beat,
+                ..
+            } => LedEffectSnapshot::Heartbeat {
+                color: PaletteColor::from(*hsv),
+                min_value: *min_value,
+                beat: millis(beat),
+            },
+            LedEffectKind::Strobe {
+                hsv,
+                period,
+                duty_cycle,
+                ..
+            } => LedEffectSnapshot::Strobe {
+                color: PaletteColor::from(*hsv),
+                period: millis(period),
+                duty_cycle: *duty_cycle,
+            },
+            LedEffectKind::Police {
+                first,
+                second,
+                interval,
+                flashes,
+                ..
+            } => LedEffectSnapshot::Police {
+                first: PaletteColor::from(*first),
+                second: PaletteColor::from(*second),
+                interval: millis(interval),
+                flashes: *flashes,
+            },
+            LedEffectKind::Noise {
+                min_hue,
+                hue_range,
+                saturation,
+                min_value,
+                max_value,
+                speed,
+                octaves,
+                seed,
+                ..
+            } => LedEffectSnapshot::Noise {
+                min_hue: *min_hue,
+                hue_range: *hue_range,
+                saturation: *saturation,
+                min_value: *min_value,
+                max_value: *max_value,
+                speed: *speed,
+                octaves: *octaves,
+                seed: *seed,
+            },
+            LedEffectKind::Sparkle {
+                base,
+                sparkle,
+                rate,
+                sparkle_duration,
+                ..
+            } => LedEffectSnapshot::Sparkle {
+                base: PaletteColor::from(*base),
+                sparkle: PaletteColor::from(*sparkle),
+                rate: *rate,
+                sparkle_duration: millis(sparkle_duration),
+            },
+            LedEffectKind::Circadian { profile, location } => LedEffectSnapshot::Circadian {
+                profile: CircadianProfile::clone(profile),
+                location: *location,
+            },
+        }
+    }
+
+    /// Builds the effect, starting now
+    ///
+    /// Fails with the same rules as building it from the API, since saved files can be edited.
+    pub fn to_kind(&self) -> Result<LedEffectKind, String> {
+        self.validate()?;
+
+        let now = Instant::now();
+
+        Ok(match self.clone() {
+            LedEffectSnapshot::Off => LedEffectKind::Off,
+            LedEffectSnapshot::Static { color } => LedEffectKind::Static {
+                hsv: color.to_hsv(),
+            },
+            LedEffectSnapshot::Breathing {
+                initial_color,
+                time_to_peak,
+                peak,
+            } => LedEffectKind::new_timed_breathing(
+                initial_color.to_hsv(),
+                Duration::from_millis(time_to_peak.max(0) as u64),
+                peak,
+            ),
+            LedEffectSnapshot::Rainbow {
+                saturation,
+                value,
+                step,
+            } => LedEffectKind::Rainbow {
+                saturation,
+                value,
+                step,
+            },
+            LedEffectSnapshot::Blink { color, interval } => LedEffectKind::Blink {
+                hsv: color.to_hsv(),
+                interval: Duration::from_millis(interval),
+                last_blink: now,
+            },
+            LedEffectSnapshot::Candle {
+                hue,
+                saturation,
+                min_value,
+                max_value,
+                variability,
+                interval,
+                palette: None,
+            } => LedEffectKind::new_candle(
+                hue,
+                saturation,
+                min_value,
+                max_value,
+                variability,
+                Some(interval),
+            ),
+            LedEffectSnapshot::Candle {
+                min_value,
+                max_value,
+                variability,
+                interval,
+                palette: Some(colors),
+                ..
+            } => LedEffectKind::new_palette_candle(
+                colors.iter().map(|color| color.to_hsv()).collect(),
+                min_value,
+                max_value,
+                variability,
+                Some(interval),
+            ),
+            LedEffectSnapshot::Bounce {
+                colors,
+                step,
+                color_space,
+            } => LedEffectKind::new_bounce(
+                colors.iter().map(|color| color.to_hsv()).collect(),
+                step,
+                color_space,
+            ),
+            LedEffectSnapshot::Wave {
+                color,
+                min_value,
+                period,
+                phase,
+            } => LedEffectKind::Wave {
+                hsv: color.to_hsv(),
+                min_value,
+                period: Duration::from_millis(period.max(1)),
+                phase,
+                epoch: now,
+            },
+            LedEffectSnapshot::Chase {
+                color,
+                background,
+                interval,
+                position,
+                count,
+            } => LedEffectKind::Chase {
+                hsv: color.to_hsv(),
+                background: background.to_hsv(),
+                interval: Duration::from_millis(interval.max(1)),
+                position,
+                count,
+                epoch: now,
+            },
+            LedEffectSnapshot::Fire {
+                hue,
+                saturation,
+                min_value,
+                max_value,
+                seed,
+            } => LedEffectKind::Fire {
+                hue,
+                saturation,
+                min_value,
+                max_value,
+                seed,
+                start: now,
+            },
+            LedEffectSnapshot::Lightning {
+                color,
+                min_interval,
+                max_interval,
+                afterglow,
+            } => LedEffectKind::new_lightning(
+                color.to_hsv(),
+                Duration::from_millis(min_interval),
+                Duration::from_millis(max_interval.max(min_interval)),
+                Duration::from_millis(afterglow),
+            ),
+            LedEffectSnapshot::Heartbeat {
+                color,
+                min_value,
+                beat,
+            } => LedEffectKind::Heartbeat {
+                hsv: color.to_hsv(),
+                min_value,
+                beat: Duration::from_millis(beat.max(1)),
+                start: now,
+            },
+            LedEffectSnapshot::Strobe {
+                color,
+                period,
+                duty_cycle,
+            } => LedEffectKind::Strobe {
+                hsv: color.to_hsv(),
+                period: Duration::from_millis(period.max(1)),
+                duty_cycle,
+                start: now,
+            },
+            LedEffectSnapshot::Police {
+                first,
+                second,
+                interval,
+                flashes,
+            } => LedEffectKind::new_police(
+                first.to_hsv(),
+                second.to_hsv(),
+                Duration::from_millis(interval.max(1)),
+                flashes,
+            ),
+            LedEffectSnapshot::Noise {
+                min_hue,
+                hue_range,
+                saturation,
+                min_value,
+                max_value,
+                speed,
+                octaves,
+                seed,
+            } => LedEffectKind::new_noise(
+                min_hue,
+                hue_range,
+                saturation,
+                min_value,
+                max_value,
+                speed,
+                octaves,
+                Some(seed),
+            ),
+            LedEffectSnapshot::Sparkle {
+                base,
+                sparkle,
+                rate,
+                sparkle_duration,
+            } => LedEffectKind::new_sparkle(
+                base.to_hsv(),
+                sparkle.to_hsv(),
+                rate,
+                Duration::from_millis(sparkle_duration),
+            ),
+            LedEffectSnapshot::Circadian { profile, location } => LedEffectKind::Circadian {
+                profile: Arc::new(profile),
+                location,
+            },
+        })
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            LedEffectSnapshot::Off => Ok(()),
+            LedEffectSnapshot::Static { color } => check_color("Color", color),
+            LedEffectSnapshot::Breathing {
+                initial_color,
+                time_to_peak,
+                peak,
+            } => {
+                check_color("Initial color", initial_color)?;
+                check_unit("Peak", *peak)?;
+
+                if *time_to_peak < 0 {
+                    return Err(String::from("Time to peak must be positive!"));
+                }
+
+                if initial_color.value > *peak {
+                    return Err(String::from("Initial value can't be higher than peak!"));
+                }
+
+                Ok(())
+            }
+            LedEffectSnapshot::Rainbow {
+                saturation,
+                value,
+                step,
+            } => {
+                check_unit("Saturation", *saturation)?;
+                check_unit("Value", *value)?;
+
+                if *step < 0.0 {
+                    return Err(String::from("Step must be positive!"));
+                }
+
+                Ok(())
+            }
+            LedEffectSnapshot::Blink { color, .. } => {
+                check_color("Color", color)?;
+
+                if color.value <= 0.0 {
+                    return Err(String::from("Value must be above 0.0 and equal or below 1.0!"));
+                }
+
+                Ok(())
+            }
+            LedEffectSnapshot::Candle {
+                hue,
+                saturation,
+                min_value,
+                max_value,
+                variability,
+                palette,
+                ..
+            } => {
+                if palette.as_ref().is_some_and(Vec::is_empty) {
+                    return Err(String::from("The palette must have at least one color!"));
+                }
+
+                palette
+                    .iter()
+                    .flatten()
+                    .try_for_each(|color| check_color("Palette", color))?;
+                check_hue("Hue", *hue)?;
+                check_unit("Saturation", *saturation)?;
+                check_unit("Min value", *min_value)?;
+                check_unit("Max value", *max_value)?;
+                check_unit("Variability", *variability)
+            }
+            LedEffectSnapshot::Bounce { colors, step, .. } => {
+                if colors.is_empty() {
+                    return Err(String::from("You must specify at least one color!"));
+                }
+
+                colors.iter().try_for_each(|color| check_color("Colors", color))?;
+                check_unit("Step", *step)
+            }
+            LedEffectSnapshot::Wave {
+                color,
+                min_value,
+                period,
+                phase,
+            } => {
+                check_color("Color", color)?;
+                check_unit("Min value", *min_value)?;
+                check_unit("Phase", *phase)?;
+                check_above_zero("Period", *period)?;
+
+                if *min_value > color.value {
+                    return Err(String::from("Min value can't be higher than value!"));
+                }
+
+                Ok(())
+            }
+            LedEffectSnapshot::Chase {
+                color,
+                background,
+                interval,
+                position,
+                count,
+            } => {
+                check_color("Color", color)?;
+                check_color("Background", background)?;
+                check_above_zero("Interval", *interval)?;
+
+                if *count == 0 {
+                    return Err(String::from("Count must be above 0!"));
+                }
+
+                if position >= count {
+                    return Err(String::from("Position must be below count!"));
+                }
+
+                Ok(())
+            }
+            LedEffectSnapshot::Fire {
+                hue,
+                saturation,
+                min_value,
+                max_value,
+                ..
+            } => {
+                check_hue("Hue", *hue)?;
+                check_unit("Saturation", *saturation)?;
+                check_value_range(*min_value, *max_value)
+            }
+            LedEffectSnapshot::Lightning {
+                color,
+                min_interval,
+                max_interval,
+                ..
+            } => {
+                check_color("Color", color)?;
+                check_above_zero("Min interval", *min_interval)?;
+
+                if min_interval > max_interval {
+                    return Err(String::from("Min interval can't be higher than max interval!"));
+                }
+
+                Ok(())
+            }
+            LedEffectSnapshot::Heartbeat {
+                color,
+                min_value,
+                beat,
+            } => {
+                check_color("Color", color)?;
+                check_unit("Min value", *min_value)?;
+                check_above_zero("Beat", *beat)?;
+
+                if *min_value > color.value {
+                    return Err(String::from("Min value can't be higher than value!"));
+                }
+
+                Ok(())
+            }
+            LedEffectSnapshot::Strobe {
+                color,
+                period,
+                duty_cycle,
+            } => {
+                check_color("Color", color)?;
+                check_above_zero("Period", *period)?;
+
+                if *duty_cycle <= 0.0 || *duty_cycle > 1.0 {
+                    return Err(String::from("Duty cycle must be above 0.0 and equal or below 1.0!"));
+                }
+
+                Ok(())
+            }
+            LedEffectSnapshot::Police {
+                first,
+                second,
+                interval,
+                flashes,
+            } => {
+                check_color("First", first)?;
+                check_color("Second", second)?;
+                check_above_zero("Interval", *interval)?;
+
+                if !(1..=10).contains(flashes) {
+                    return Err(String::from("Flashes must be between 1 and 10!"));
+                }
+
+                Ok(())
+            }
+            LedEffectSnapshot::Noise {
+                min_hue,
+                hue_range,
+                saturation,
+                min_value,
+                max_value,
+                speed,
+                octaves,
+                ..
+            } => {
+                check_hue("Min hue", *min_hue)?;
+                check_hue("Hue range", *hue_range)?;
+                check_unit("Saturation", *saturation)?;
+                check_value_range(*min_value, *max_value)?;
+
+                if *speed <= 0.0 || *speed > 20.0 {
+                    return Err(String::from("Speed must be above 0.0 and equal or below 20.0!"));
+                }
+
+                if !(1..=8).contains(octaves) {
+                    return Err(String::from("Octaves must be between 1 and 8!"));
+                }
+
+                Ok(())
+            }
+            LedEffectSnapshot::Sparkle {
+                base,
+                sparkle,
+                rate,
+                sparkle_duration,
+            } => {
+                check_color("Base", base)?;
+                check_color("Sparkle", sparkle)?;
+                check_above_zero("Sparkle duration", *sparkle_duration)?;
+
+                if *rate <= 0.0 || *rate > 100.0 {
+                    return Err(String::from("Rate must be above 0.0 and equal or below 100.0!"));
+                }
+
+                Ok(())
+            }
+            LedEffectSnapshot::Circadian { profile, .. } => {
+                if ![profile.day_kelvin, profile.night_kelvin]
+                    .iter()
+                    .all(|kelvin| (MIN_KELVIN..=MAX_KELVIN).contains(kelvin))
+                {
+                    return Err(format!("Color temperature must be between {MIN_KELVIN} and {MAX_KELVIN}!"));
+                }
+
+                check_unit("Day brightness", profile.day_brightness)?;
+                check_unit("Night brightness", profile.night_brightness)
+            }
+        }
+    }
+}
+
+/// Parameters of a rumble effect, without its runtime state
+///
+/// Times are in milliseconds.
+#[derive(Serialize, Deserialize, PartialEq, Copy, Clone, Debug, Display)]
+pub enum RumbleEffectSnapshot {
+    Off,
+    Static {
+        strength: f32,
+    },
+    Breathing {
+        initial_strength: f32,
+        step: f32,
+        peak: f32,
+    },
+    Blink {
+        strength: f32,
+        interval: u64,
+    },
+    FollowLed {
+        min_strength: f32,
+        max_strength: f32,
+    },
+}
+
+impl RumbleEffectSnapshot {
+    pub fn from(kind: &RumbleEffectKind) -> RumbleEffectSnapshot {
+        match *kind {
+            RumbleEffectKind::Off => RumbleEffectSnapshot::Off,
+            RumbleEffectKind::Static { strength } => RumbleEffectSnapshot::Static { strength },
+            RumbleEffectKind::Breathing {
+                initial_strength,
+                step,
+                peak,
+                ..
+            } => RumbleEffectSnapshot::Breathing {
+                initial_strength,
+                step,
+                peak,
+            },
+            RumbleEffectKind::Blink {
+                strength, interval, ..
+            } => RumbleEffectSnapshot::Blink {
+                strength,
+                interval: interval.as_millis() as u64,
+            },
+            RumbleEffectKind::FollowLed {
+                min_strength,
+                max_strength,
+            } => RumbleEffectSnapshot::FollowLed {
+                min_strength,
+                max_strength,
+            },
+        }
+    }
+
+    /// Builds the effect, starting now
+    ///
+    /// Fails with the same rules as building it from the API, since saved files can be edited.
+    pub fn to_kind(self) -> Result<RumbleEffectKind, String> {
+        self.validate()?;
+
+        Ok(match self {
+            RumbleEffectSnapshot::Off => RumbleEffectKind::Off,
+            RumbleEffectSnapshot::Static { strength } => RumbleEffectKind::Static { strength },
+            RumbleEffectSnapshot::Breathing {
+                initial_strength,
+                step,
+                peak,
+            } => RumbleEffectKind::Breathing {
+                initial_strength,
+                step,
+                peak,
+                inhaling: true,
+            },
+            RumbleEffectSnapshot::Blink { strength, interval } => RumbleEffectKind::Blink {
+                strength,
+                interval: Duration::from_millis(interval),
+                last_blink: Instant::now(),
+            },
+            RumbleEffectSnapshot::FollowLed {
+                min_strength,
+                max_strength,
+            } => RumbleEffectKind::FollowLed {
+                min_strength,
+                max_strength,
+            },
+        })
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match *self {
+            RumbleEffectSnapshot::Off => Ok(()),
+            RumbleEffectSnapshot::Static { strength } | RumbleEffectSnapshot::Blink { strength, .. } => {
+                check_unit("Strength", strength)
+            }
+            RumbleEffectSnapshot::Breathing {
+                initial_strength,
+                step,
+                peak,
+            } => {
+                check_unit("Initial strength", initial_strength)?;
+                check_unit("Step", step)?;
+                check_unit("Peak", peak)?;
+
+                if initial_strength > peak {
+                    return Err(String::from("Initial strength can't be higher than peak!"));
+                }
+
+                Ok(())
+            }
+            RumbleEffectSnapshot::FollowLed {
+                min_strength,
+                max_strength,
+            } => {
+                check_unit("Min strength", min_strength)?;
+                check_unit("Max strength", max_strength)
+            }
+        }
+    }
+}
+
+fn check_unit(name: &str, value: f32) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&value) {
+        return Err(format!("{name} must be between 0.0 and 1.0!"));
+    }
+
+    Ok(())
+}
+
+fn check_hue(name: &str, hue: f32) -> Result<(), String> {
+    if !(0.0..=360.0).contains(&hue) {
+        return Err(format!("{name} must be between 0 and 360!"));
+    }
+
+    Ok(())
+}
+
+fn check_color(name: &str, color: &PaletteColor) -> Result<(), String> {
+    check_hue(&format!("{name} hue"), color.hue)?;
+    check_unit(&format!("{name} saturation"), color.saturation)?;
+    check_unit(&format!("{name} value"), color.value)
+}
+
+fn check_value_range(min_value: f32, max_value: f32) -> Result<(), String> {
+    check_unit("Min value", min_value)?;
+    check_unit("Max value", max_value)?;
+
+    if min_value > max_value {
+        return Err(String::from("Min value can't be higher than max value!"));
+    }
+
+    Ok(())
+}
+
+fn check_above_zero(name: &str, millis: u64) -> Result<(), String> {
+    if millis == 0 {
+        return Err(format!("{name} must be above 0!"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use palette::Hsv;
+
+    use super::*;
+
+    const WHITE: PaletteColor = PaletteColor {
+        hue: 0.0,
+        saturation: 0.0,
+        value: 1.0,
+    };
+
+    #[test]
+    fn rejects_bounce_without_colors() {
+        let snapshot = LedEffectSnapshot::Bounce {
+            colors: Vec::new(),
+            step: 0.01,
+            color_space: ColorSpace::Hsv,
+        };
+
+        assert!(snapshot.to_kind().is_err());
+    }
+
+    #[test]
+    fn rejects_chase_without_controllers() {
+        let snapshot = LedEffectSnapshot::Chase {
+            color: WHITE,
+            background: WHITE,
+            interval: 100,
+            position: 0,
+            count: 0,
+        };
+
+        assert!(snapshot.to_kind().is_err());
+    }
+
+    #[test]
+    fn rejects_lightning_without_interval() {
+        let snapshot = LedEffectSnapshot::Lightning {
+            color: WHITE,
+            min_interval: 0,
+            max_interval: 0,
+            afterglow: 300,
+        };
+
+        assert!(snapshot.to_kind().is_err());
+    }
+
+    #[test]
+    fn rebuilds_saved_effects() {
+        let kinds = [
+            LedEffectKind::new_bounce(vec![Hsv::new(0.0, 1.0, 1.0)], 0.01, ColorSpace::Oklch),
+            LedEffectKind::new_lightning(
+                Hsv::new(0.0, 0.0, 1.0),
+                Duration::from_millis(500),
+                Duration::from_secs(2),
+                Duration::from_millis(300),
+            ),
+            LedEffectKind::new_strobe(Hsv::new(0.0, 0.0, 1.0), 10.0, 0.1),
+            LedEffectKind::new_noise(200.0, 60.0, 1.0, 0.2, 0.8, 1.0, 3, Some(7)),
+            LedEffectKind::new_palette_candle(
+                vec![Hsv::new(20.0, 1.0, 1.0), Hsv::new(40.0, 0.8, 1.0)],
+                0.3,
+                0.9,
+                0.5,
+                Some(50),
+            ),
+        ];
+
+        for kind in kinds {
+            let snapshot = LedEffectSnapshot::from(&kind);
+
+            assert_eq!(LedEffectSnapshot::from(&snapshot.to_kind().unwrap()), snapshot);
+        }
+    }
+}
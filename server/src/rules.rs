@@ -0,0 +1,250 @@
+use core::fmt;
+
+use chrono::{DateTime, Datelike, Local, Timelike};
+use juniper::GraphQLEnum;
+use serde_derive::{Deserialize, Serialize};
+use strum_macros::Display;
+
+use crate::persistence;
+use crate::ps_move::models::BatteryLevel;
+use crate::ps_move::snapshot::{LedEffectSnapshot, RumbleEffectSnapshot};
+use crate::schedules::Weekday;
+use crate::tasks::models::{Button, EffectPriority};
+
+const RULES_FILE: &str = "rules.json";
+
+#[derive(GraphQLEnum, Serialize, Deserialize, PartialEq, Copy, Clone, Debug, Display)]
+pub enum ButtonGesture {
+    Press,
+    Release,
+    #[graphql(description = "Kept down for a while, fires before the button is released.")]
+    Hold,
+    #[graphql(description = "Pressed twice in a short time.")]
+    DoublePress,
+}
+
+/// Something that happened on a controller
+#[derive(Copy, Clone, Debug)]
+pub enum ControllerEvent {
+    Button { button: Button, gesture: ButtonGesture },
+    /// From 0.0 (released) to 1.0 (fully pulled)
+    Trigger { from: f32, to: f32 },
+    /// How far the acceleration is from gravity, in g
+    Motion { from: f32, to: f32 },
+    Connect,
+    Disconnect,
+    Battery { level: BatteryLevel },
+}
+
+impl fmt::Display for ControllerEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ControllerEvent::Button { button, gesture } => write!(f, "{button} {gesture}"),
+            ControllerEvent::Trigger { to, .. } => write!(f, "Trigger at {to:.2}"),
+            ControllerEvent::Motion { to, .. } => write!(f, "Motion of {to:.2}g"),
+            ControllerEvent::Connect => write!(f, "Connect"),
+            ControllerEvent::Disconnect => write!(f, "Disconnect"),
+            ControllerEvent::Battery { level } => write!(f, "Battery at {level}"),
+        }
+    }
+}
+
+/// Event a rule reacts to
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub enum RuleEvent {
+    Button { button: Button, gesture: ButtonGesture },
+    /// The trigger is pulled past the threshold (from 0.0 to 1.0)
+    Trigger { threshold: f32 },
+    /// The controller is moved harder than the threshold (in g)
+    Motion { threshold: f32 },
+    Connect,
+    Disconnect,
+    /// Any change of the battery level if `None`
+    Battery { level: Option<BatteryLevel> },
+}
+
+impl RuleEvent {
+    pub fn matches(&self, event: &ControllerEvent) -> bool {
+        match (self, event) {
+            (
+                RuleEvent::Button { button, gesture },
+                ControllerEvent::Button {
+                    button: event_button,
+                    gesture: event_gesture,
+                },
+            ) => button == event_button && gesture == event_gesture,
+            // only when crossing the threshold, not for as long as it stays above
+            (RuleEvent::Trigger { threshold }, ControllerEvent::Trigger { from, to })
+            | (RuleEvent::Motion { threshold }, ControllerEvent::Motion { from, to }) => {
+                from < threshold && to >= threshold
+            }
+            (RuleEvent::Connect, ControllerEvent::Connect)
+            | (RuleEvent::Disconnect, ControllerEvent::Disconnect) => true,
+            (RuleEvent::Battery { level }, ControllerEvent::Battery { level: event_level }) => {
+                level.is_none_or(|level| level == *event_level)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for RuleEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleEvent::Button { button, gesture } => write!(f, "{button} {gesture}"),
+            RuleEvent::Trigger { threshold } => write!(f, "Trigger past {threshold}"),
+            RuleEvent::Motion { threshold } => write!(f, "Motion past {threshold}g"),
+            RuleEvent::Connect => write!(f, "Connect"),
+            RuleEvent::Disconnect => write!(f, "Disconnect"),
+            RuleEvent::Battery { level: Some(level) } => write!(f, "Battery at {level}"),
+            RuleEvent::Battery { level: None } => write!(f, "Battery change"),
+        }
+    }
+}
+
+/// Local time of day, from `from_minute` (included) to `to_minute` (excluded),
+/// going past midnight if `to_minute` comes first
+#[derive(Serialize, Deserialize, PartialEq, Copy, Clone, Debug)]
+pub struct TimeWindow {
+    pub from_minute: u32,
+    pub to_minute: u32,
+}
+
+impl TimeWindow {
+    pub fn contains(&self, minute: u32) -> bool {
+        if self.from_minute <= self.to_minute {
+            self.from_minute <= minute && minute < self.to_minute
+        } else {
+            minute >= self.from_minute || minute < self.to_minute
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum RuleAction {
+    /// On the controller of the event if `controllers` is `None`
+    Led {
+        controllers: Option<Vec<String>>,
+        effect: LedEffectSnapshot,
+        duration_ms: Option<u64>,
+        transition_ms: Option<u64>,
+    },
+    /// On the controller of the event if `controllers` is `None`
+    Rumble {
+        controllers: Option<Vec<String>>,
+        effect: RumbleEffectSnapshot,
+        duration_ms: Option<u64>,
+    },
+    Scene {
+        name: String,
+        transition_ms: Option<u64>,
+    },
+    /// Applies the scene, unless one of its controllers shows it already:
+    /// then `otherwise` is applied, or the scene's controllers are turned off
+    ToggleScene {
+        name: String,
+        otherwise: Option<String>,
+        transition_ms: Option<u64>,
+    },
+    /// Posts the rule, controller and event as JSON
+    Webhook { url: String },
+}
+
+impl fmt::Display for RuleAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleAction::Led { effect, .. } => write!(f, "Led::{effect}"),
+            RuleAction::Rumble { effect, .. } => write!(f, "Rumble::{effect}"),
+            RuleAction::Scene { name, .. } => write!(f, "Scene '{name}'"),
+            RuleAction::ToggleScene {
+                name,
+                otherwise: Some(otherwise),
+                ..
+            } => write!(f, "Toggle scene '{name}' and '{otherwise}'"),
+            RuleAction::ToggleScene { name, .. } => write!(f, "Toggle scene '{name}'"),
+            RuleAction::Webhook { url } => write!(f, "Webhook to {url}"),
+        }
+    }
+}
+
+/// Runs its actions when an event happens on a controller, if the conditions are met
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Rule {
+    pub name: String,
+    pub enabled: bool,
+    pub event: RuleEvent,
+    /// All controllers if `None`
+    pub controllers: Option<Vec<String>>,
+    /// All day if `None`
+    pub time_window: Option<TimeWindow>,
+    /// Every day if empty
+    pub days: Vec<Weekday>,
+    /// Only while the controller of the event shows this scene
+    pub scene: Option<String>,
+    /// Of the effects the actions apply
+    pub priority: EffectPriority,
+    pub actions: Vec<RuleAction>,
+}
+
+impl Rule {
+    /// Whether the rule runs for `event` on the controller at `address`, showing `scene`
+    pub fn applies(
+        &self,
+        event: &ControllerEvent,
+        address: &str,
+        scene: Option<&str>,
+        now: DateTime<Local>,
+    ) -> bool {
+        self.enabled
+            && self.event.matches(event)
+            && self
+                .controllers
+                .as_ref()
+                .is_none_or(|controllers| controllers.iter().any(|controller| controller == address))
+            && self
+                .time_window
+                .is_none_or(|window| window.contains(now.hour() * 60 + now.minute()))
+            && (self.days.is_empty() || self.days.contains(&Weekday::from(now.weekday())))
+            && self.scene.as_ref().is_none_or(|name| Some(name.as_str()) == scene)
+    }
+
+    pub fn get_source(&self) -> String {
+        format!("rule '{}'", self.name)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        self.actions.iter().try_for_each(|action| match action {
+            RuleAction::Led { effect, .. } => effect.to_kind().map(|_| ()),
+            RuleAction::Rumble { effect, .. } => effect.to_kind().map(|_| ()),
+            RuleAction::Scene { .. } | RuleAction::ToggleScene { .. } | RuleAction::Webhook { .. } => Ok(()),
+        })
+    }
+}
+
+/// Effects and other actions run by the server itself, when something happens on a controller
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct Rules {
+    pub rules: Vec<Rule>,
+}
+
+impl Rules {
+    /// Rules with invalid effects (e.g. edited by hand) are dropped
+    pub fn load() -> Rules {
+        let mut rules: Rules = persistence::load(RULES_FILE);
+
+        rules.rules.retain(|rule| match rule.validate() {
+            Ok(_) => true,
+            Err(err) => {
+                tracing::error!("Ignored the saved rule '{}'. Caused by {err}", rule.name);
+                false
+            }
+        });
+
+        rules
+    }
+
+    pub fn save(&self) {
+        persistence::save(RULES_FILE, self)
+    }
+}
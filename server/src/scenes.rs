@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde_derive::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::persistence;
+use crate::ps_move::effects::{LedEffect, RumbleEffect, Transition};
+use crate::ps_move::snapshot::{LedEffectSnapshot, RumbleEffectSnapshot};
+use crate::tasks::models::EffectChangeType;
+
+const SCENES_FILE: &str = "scenes.json";
+
+/// Effects of a controller in a scene
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SceneController {
+    pub address: String,
+    pub led: LedEffectSnapshot,
+    pub rumble: RumbleEffectSnapshot,
+}
+
+impl SceneController {
+    fn to_effects(&self) -> Result<(LedEffect, RumbleEffect), String> {
+        Ok((
+            LedEffect::new(self.led.to_kind()?),
+            RumbleEffect::new(self.rumble.to_kind()?),
+        ))
+    }
+}
+
+/// Led and rumble effects of a set of controllers, restored all at once
+/// (effects in a scene don't expire)
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Scene {
+    pub name: String,
+    pub controllers: Vec<SceneController>,
+}
+
+impl Scene {
+    /// Effects of the scene's controllers (only the ones in `controllers` if specified), by bt address
+    ///
+    /// Controllers with invalid effects are left out, which loading the scenes already rules out.
+    pub fn get_effects(&self, controllers: Option<&[String]>) -> HashMap<String, (LedEffect, RumbleEffect)> {
+        self.controllers
+            .iter()
+            .filter(|controller| controllers.is_none_or(|addresses| addresses.contains(&controller.address)))
+            .filter_map(|controller| match controller.to_effects() {
+                Ok(effects) => Some((controller.address.clone(), effects)),
+                Err(err) => {
+                    tracing::error!("Invalid effects for '{}' in scene '{}'. Caused by {err}", controller.address, self.name);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        self.controllers.iter().try_for_each(|controller| {
+            controller
+                .to_effects()
+                .map(|_| ())
+                .map_err(|err| format!("Invalid effects for '{}'. {err}", controller.address))
+        })
+    }
+
+    pub fn to_effect_type(
+        &self,
+        effects: HashMap<String, (LedEffect, RumbleEffect)>,
+        transition: Option<Transition>,
+    ) -> EffectChangeType {
+        let mut effect = EffectChangeType::Scene {
+            name: self.name.clone(),
+            effects: Arc::new(effects),
+            transition,
+        };
+        // every controller shares the same clock
+        effect.start_at(Instant::now());
+
+        effect
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct Scenes {
+    pub scenes: Vec<Scene>,
+}
+
+impl Scenes {
+    /// Scenes with invalid effects (e.g. edited by hand) are dropped
+    pub fn load() -> Scenes {
+        let mut scenes: Scenes = persistence::load(SCENES_FILE);
+
+        scenes.scenes.retain(|scene| match scene.validate() {
+            Ok(_) => true,
+            Err(err) => {
+                tracing::error!("Ignored the saved scene '{}'. Caused by {err}", scene.name);
+                false
+            }
+        });
+
+        scenes
+    }
+
+    pub fn save(&self) {
+        persistence::save(SCENES_FILE, self)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Scene> {
+        self.scenes.iter().find(|scene| scene.name == name)
+    }
+}
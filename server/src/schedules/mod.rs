@@ -0,0 +1,259 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, Timelike, Utc};
+use juniper::GraphQLEnum;
+use palette::Hsv;
+use serde_derive::{Deserialize, Serialize};
+use tokio::time::Duration;
+
+use crate::persistence;
+use crate::ps_move::colors::kelvin_to_hsv;
+use crate::ps_move::effects::{LedEffect, LedEffectKind, Transition};
+use crate::schedules::sun::SunTimes;
+use crate::tasks::models::{EffectChange, EffectChangeType, EffectPriority, EffectTarget};
+
+pub mod sun;
+
+const SCHEDULES_FILE: &str = "schedules.json";
+const MINUTES_PER_DAY: i32 = 24 * 60;
+
+#[derive(GraphQLEnum, Serialize, Deserialize, PartialEq, Copy, Clone, Debug)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    pub fn from(weekday: chrono::Weekday) -> Weekday {
+        match weekday {
+            chrono::Weekday::Mon => Weekday::Monday,
+            chrono::Weekday::Tue => Weekday::Tuesday,
+            chrono::Weekday::Wed => Weekday::Wednesday,
+            chrono::Weekday::Thu => Weekday::Thursday,
+            chrono::Weekday::Fri => Weekday::Friday,
+            chrono::Weekday::Sat => Weekday::Saturday,
+            chrono::Weekday::Sun => Weekday::Sunday,
+        }
+    }
+}
+
+#[derive(GraphQLEnum, Serialize, Deserialize, PartialEq, Copy, Clone, Debug)]
+pub enum SunEvent {
+    Sunrise,
+    Sunset,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Copy, Clone, Debug)]
+pub struct Location {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// Local time of the day a schedule fires at
+#[derive(Serialize, Deserialize, PartialEq, Copy, Clone, Debug)]
+pub enum ScheduleTime {
+    Clock { hour: u32, minute: u32 },
+    Sun { event: SunEvent, offset_minutes: i32 },
+}
+
+/// Sets a static color (or turns the led off) at a time of the day
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct Schedule {
+    pub name: String,
+    pub enabled: bool,
+    pub time: ScheduleTime,
+    /// Every day if empty
+    pub days: Vec<Weekday>,
+    /// All controllers if `None`
+    pub controllers: Option<Vec<String>>,
+    pub hue: f32,
+    pub saturation: f32,
+    /// The led goes off at 0.0
+    pub value: f32,
+    pub transition_ms: Option<u64>,
+    pub duration_ms: Option<u64>,
+}
+
+impl Schedule {
+    /// Local minute of `date` the schedule fires at, `None` if it depends on the sun
+    /// and there's no location, or the sun doesn't rise/set that day
+    pub fn get_minute_of_day(&self, date: NaiveDate, location: Option<Location>) -> Option<u32> {
+        match self.time {
+            ScheduleTime::Clock { hour, minute } => Some(hour * 60 + minute),
+            ScheduleTime::Sun {
+                event,
+                offset_minutes,
+            } => {
+                let SunTimes::Regular { sunrise, sunset } = sun::get_sun_times(date, location?) else {
+                    return None;
+                };
+
+                let event = match event {
+                    SunEvent::Sunrise => sunrise,
+                    SunEvent::Sunset => sunset,
+                }
+                .with_timezone(&Local);
+
+                let minute = (event.hour() * 60 + event.minute()) as i32 + offset_minutes;
+
+                Some(minute.rem_euclid(MINUTES_PER_DAY) as u32)
+            }
+        }
+    }
+
+    pub fn is_due(&self, now: DateTime<Local>, location: Option<Location>) -> bool {
+        let date = now.date_naive();
+
+        self.enabled
+            && (self.days.is_empty() || self.days.contains(&Weekday::from(now.weekday())))
+            && self.get_minute_of_day(date, location) == Some(now.hour() * 60 + now.minute())
+    }
+
+    pub fn to_effect_change(&self) -> EffectChange {
+        let kind = if self.value > 0.0 {
+            LedEffectKind::Static {
+                hsv: Hsv::new(self.hue, self.saturation, self.value),
+            }
+        } else {
+            LedEffectKind::Off
+        };
+
+        let effect = match self.duration_ms {
+            Some(millis) => LedEffect::new_expiring(kind, Duration::from_millis(millis)),
+            None => LedEffect::new(kind),
+        };
+
+        EffectChange {
+            target: to_effect_target(&self.controllers),
+            effect: EffectChangeType::Led {
+                effect,
+                transition: self.transition_ms.map(|millis| Transition {
+                    duration: Duration::from_millis(millis),
+                    color_space: Default::default(),
+                }),
+            },
+            priority: EffectPriority::Normal,
+            source: format!("schedule '{}'", self.name),
+            automatic: true,
+        }
+    }
+}
+
+/// Shifts color temperature and brightness by time of day, following the sun
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+#[serde(default)]
+pub struct CircadianProfile {
+    pub enabled: bool,
+    /// All controllers if `None`
+    pub controllers: Option<Vec<String>>,
+    pub day_kelvin: f32,
+    pub night_kelvin: f32,
+    pub day_brightness: f32,
+    pub night_brightness: f32,
+    /// How long the shift between night and day takes, centered on sunrise/sunset
+    pub twilight_minutes: u32,
+}
+
+impl Default for CircadianProfile {
+    fn default() -> CircadianProfile {
+        CircadianProfile {
+            enabled: false,
+            controllers: None,
+            day_kelvin: 5500.0,
+            night_kelvin: 2200.0,
+            day_brightness: 0.8,
+            night_brightness: 0.1,
+            twilight_minutes: 60,
+        }
+    }
+}
+
+impl CircadianProfile {
+    pub fn get_hsv(&self, now: DateTime<Local>, location: Location) -> Hsv {
+        let daylight = sun::get_daylight(
+            now.with_timezone(&Utc),
+            now.date_naive(),
+            location,
+            self.twilight_minutes,
+        );
+
+        let kelvin = self.night_kelvin + (self.day_kelvin - self.night_kelvin) * daylight;
+        let brightness =
+            self.night_brightness + (self.day_brightness - self.night_brightness) * daylight;
+
+        kelvin_to_hsv(kelvin, brightness)
+    }
+
+    /// The lighting then follows the sun by itself, so this is only needed when the profile
+    /// (or location) changes
+    pub fn to_effect_change(&self, location: Location, transition: Duration) -> EffectChange {
+        self.to_led_change(
+            LedEffectKind::Circadian {
+                profile: Arc::new(self.clone()),
+                location,
+            },
+            transition,
+        )
+    }
+
+    /// Keeps the color of the time of day as it is, once the profile is disabled
+    pub fn to_stop_change(&self, now: DateTime<Local>, location: Location, transition: Duration) -> EffectChange {
+        self.to_led_change(
+            LedEffectKind::Static {
+                hsv: self.get_hsv(now, location),
+            },
+            transition,
+        )
+    }
+
+    fn to_led_change(&self, kind: LedEffectKind, transition: Duration) -> EffectChange {
+        EffectChange {
+            target: to_effect_target(&self.controllers),
+            effect: EffectChangeType::Led {
+                effect: LedEffect::new(kind),
+                transition: Some(Transition {
+                    duration: transition,
+                    color_space: Default::default(),
+                }),
+            },
+            // anything set by hand takes over the circadian lighting
+            priority: EffectPriority::Ambient,
+            source: String::from("circadian"),
+            automatic: true,
+        }
+    }
+}
+
+/// Recurring schedules and circadian lighting, run by the server itself
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct Schedules {
+    /// Needed for anything depending on the sun
+    pub location: Option<Location>,
+    pub schedules: Vec<Schedule>,
+    pub circadian: CircadianProfile,
+}
+
+impl Schedules {
+    pub fn load() -> Schedules {
+        persistence::load(SCHEDULES_FILE)
+    }
+
+    pub fn save(&self) {
+        persistence::save(SCHEDULES_FILE, self)
+    }
+}
+
+fn to_effect_target(controllers: &Option<Vec<String>>) -> EffectTarget {
+    match controllers {
+        Some(bt_addresses) => EffectTarget::Only {
+            bt_addresses: bt_addresses.clone(),
+        },
+        None => EffectTarget::All,
+    }
+}
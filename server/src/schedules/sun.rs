@@ -0,0 +1,96 @@
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+
+use crate::schedules::Location;
+
+/// Julian day of 2000-01-01 12:00 UTC
+const J2000: f64 = 2451545.0;
+/// Julian day of 1970-01-01 00:00 UTC
+const UNIX_EPOCH_JULIAN_DAY: f64 = 2440587.5;
+const SECONDS_PER_DAY: f64 = 86400.0;
+/// Altitude of the sun's center at sunrise/sunset, accounting for refraction and its radius
+const SUN_ALTITUDE: f64 = -0.833;
+const EARTH_TILT: f64 = 23.4397;
+
+pub enum SunTimes {
+    Regular {
+        sunrise: DateTime<Utc>,
+        sunset: DateTime<Utc>,
+    },
+    /// The sun doesn't set that day
+    PolarDay,
+    /// The sun doesn't rise that day
+    PolarNight,
+}
+
+/// Computes sunrise and sunset around `date` (sunrise equation, accurate to a few minutes)
+pub fn get_sun_times(date: NaiveDate, location: Location) -> SunTimes {
+    // the default is 1970-01-01
+    let days_since_epoch = date
+        .signed_duration_since(NaiveDate::default())
+        .num_days() as f64;
+    let days_since_j2000 = (UNIX_EPOCH_JULIAN_DAY + 0.5 + days_since_epoch - J2000 + 0.0008).round();
+
+    let mean_solar_time = days_since_j2000 - location.longitude / 360.0;
+    let mean_anomaly = (357.5291 + 0.98560028 * mean_solar_time).rem_euclid(360.0);
+    let center = 1.9148 * sin(mean_anomaly)
+        + 0.02 * sin(2.0 * mean_anomaly)
+        + 0.0003 * sin(3.0 * mean_anomaly);
+    let ecliptic_longitude = (mean_anomaly + center + 180.0 + 102.9372).rem_euclid(360.0);
+
+    let solar_transit =
+        J2000 + mean_solar_time + 0.0053 * sin(mean_anomaly) - 0.0069 * sin(2.0 * ecliptic_longitude);
+    let declination = (sin(ecliptic_longitude) * sin(EARTH_TILT)).asin().to_degrees();
+
+    let cos_hour_angle = (sin(SUN_ALTITUDE) - sin(location.latitude) * sin(declination))
+        / (cos(location.latitude) * cos(declination));
+
+    if cos_hour_angle > 1.0 {
+        return SunTimes::PolarNight;
+    }
+    if cos_hour_angle < -1.0 {
+        return SunTimes::PolarDay;
+    }
+
+    let hour_angle = cos_hour_angle.acos().to_degrees();
+
+    match (
+        from_julian_day(solar_transit - hour_angle / 360.0),
+        from_julian_day(solar_transit + hour_angle / 360.0),
+    ) {
+        (Some(sunrise), Some(sunset)) => SunTimes::Regular { sunrise, sunset },
+        _ => SunTimes::PolarNight,
+    }
+}
+
+/// From 0.0 (night) to 1.0 (day), ramping linearly during `twilight_minutes` around
+/// sunrise and sunset
+pub fn get_daylight(now: DateTime<Utc>, date: NaiveDate, location: Location, twilight_minutes: u32) -> f32 {
+    match get_sun_times(date, location) {
+        SunTimes::Regular { sunrise, sunset } => {
+            let twilight = (twilight_minutes.max(1) * 60) as f32;
+            let ramp = |event: DateTime<Utc>| {
+                let since_event = (now - event).num_seconds() as f32;
+
+                (since_event / twilight + 0.5).clamp(0.0, 1.0)
+            };
+
+            ramp(sunrise).min(1.0 - ramp(sunset))
+        }
+        SunTimes::PolarDay => 1.0,
+        SunTimes::PolarNight => 0.0,
+    }
+}
+
+fn from_julian_day(julian_day: f64) -> Option<DateTime<Utc>> {
+    let timestamp = (julian_day - UNIX_EPOCH_JULIAN_DAY) * SECONDS_PER_DAY;
+
+    Utc.timestamp_opt(timestamp.round() as i64, 0).single()
+}
+
+fn sin(degrees: f64) -> f64 {
+    degrees.to_radians().sin()
+}
+
+fn cos(degrees: f64) -> f64 {
+    degrees.to_radians().cos()
+}
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use palette::Hsv;
+use serde_derive::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::persistence;
+use crate::ps_move::effects::{LedEffect, LedEffectKind};
+use crate::ps_move::output::OutputSettings;
+use crate::ps_move::snapshot::LedEffectSnapshot;
+
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Led effect the server shows by itself, on startup or when a controller connects
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LifecycleEffect {
+    pub led: LedEffectSnapshot,
+    /// Never expires if `None`
+    pub duration_ms: Option<u64>,
+}
+
+impl LifecycleEffect {
+    /// Off if the effect is invalid, which loading the settings already rules out
+    pub fn to_effect(&self) -> LedEffect {
+        let kind = match self.led.to_kind() {
+            Ok(kind) => kind,
+            Err(err) => {
+                tracing::error!("Invalid {} effect, turning off instead. Caused by {err}", self.led);
+                return LedEffect::off();
+            }
+        };
+
+        match self.duration_ms {
+            Some(millis) => LedEffect::new_expiring(kind, Duration::from_millis(millis)),
+            None => LedEffect::new(kind),
+        }
+    }
+}
+
+/// Server settings that can be changed at runtime and survive restarts
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct Settings {
+    pub output: OutputSettings,
+    /// By bt address, reapplied whenever the controller connects
+    pub led_pwm_frequencies: HashMap<String, u64>,
+    /// Set on every controller when the server starts, none if `None`
+    pub startup_effect: Option<LifecycleEffect>,
+    /// Set on a controller connecting while there's no other effect to join, none if `None`
+    pub connect_effect: Option<LifecycleEffect>,
+    /// By bt address, set whenever the controller connects instead of any other effect
+    pub controller_connect_effects: HashMap<String, LifecycleEffect>,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            output: OutputSettings::default(),
+            led_pwm_frequencies: HashMap::new(),
+            startup_effect: Some(LifecycleEffect {
+                led: LedEffectSnapshot::from(&LedEffectKind::new_timed_breathing(
+                    Hsv::from_components((270.0, 1.0, 0.001)),
+                    Duration::from_secs(3),
+                    0.3,
+                )),
+                duration_ms: Some(3000),
+            }),
+            connect_effect: Some(LifecycleEffect {
+                led: LedEffectSnapshot::from(&LedEffectKind::Blink {
+                    hsv: Hsv::from_components((42.0, 1.0, 0.35)),
+                    last_blink: Instant::now(),
+                    interval: Duration::from_millis(500),
+                }),
+                duration_ms: Some(1000),
+            }),
+            controller_connect_effects: HashMap::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Invalid effects (e.g. edited by hand) are dropped
+    pub fn load() -> Settings {
+        let mut settings: Settings = persistence::load(SETTINGS_FILE);
+
+        settings.startup_effect = settings
+            .startup_effect
+            .filter(|effect| is_valid(effect, "startup effect"));
+        settings.connect_effect = settings
+            .connect_effect
+            .filter(|effect| is_valid(effect, "connect effect"));
+        settings
+            .controller_connect_effects
+            .retain(|address, effect| is_valid(effect, &format!("connect effect of '{address}'")));
+
+        settings
+    }
+
+    pub fn save(&self) {
+        persistence::save(SETTINGS_FILE, self)
+    }
+}
+
+fn is_valid(effect: &LifecycleEffect, name: &str) -> bool {
+    match effect.led.to_kind() {
+        Ok(_) => true,
+        Err(err) => {
+            tracing::error!("Ignored the saved {name}. Caused by {err}");
+            false
+        }
+    }
+}
@@ -3,10 +3,10 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
-use lazy_static::lazy_static;
 use palette::Hsv;
 use tokio::runtime::Handle;
 use tokio::sync::{mpsc, Mutex};
+use tokio::sync::broadcast;
 use tokio::sync::broadcast::Receiver;
 use tokio::sync::watch::Sender;
 use tokio::task;
@@ -17,27 +17,32 @@ use crate::ControllerChange;
 use crate::monitoring::metrics::{IDLE_DURATION_METRIC, POLL_DURATION_METRIC, SCHEDULED_DURATION_METRIC};
 use crate::ps_move::api::PsMoveApi;
 use crate::ps_move::controller::PsMoveController;
-use crate::ps_move::effects::{LedEffect, LedEffectKind};
+use crate::ps_move::effects::LedEffect;
+use crate::bindings::Bindings;
+use crate::rules::Rules;
+use crate::scenes::Scenes;
+use crate::schedules::Schedules;
+use crate::settings::Settings;
 use crate::tasks::{
-    controller_update, controllers_list_update, effects_update, ip_discovery, mutations_handler,
+    controller_update, controllers_list_update, effects_update, ip_discovery, mutations_handler, scheduler,
+    schedules_update,
 };
 use crate::tasks::models::EffectChange;
+use crate::tasks::rules_update::RulesEngine;
+use crate::tasks::scheduler::ScheduledEffects;
 
-lazy_static! {
-    static ref ON_STARTUP_EFFECT: LedEffect = LedEffect::new_expiring(
-        LedEffectKind::new_timed_breathing(
-            Hsv::from_components((270.0, 1.0, 0.001)),
-            Duration::from_secs(3),
-            0.3
-        ),
-        Duration::from_secs(3)
-    );
-}
-
+#[allow(clippy::too_many_arguments)]
 pub async fn run_move(
     effect_rx: Receiver<EffectChange>,
     ctrl_tx: Sender<ControllerChange>,
     controllers: &Arc<Mutex<Vec<PsMoveController>>>,
+    settings: &Arc<Mutex<Settings>>,
+    effect_tx: &Arc<broadcast::Sender<EffectChange>>,
+    scheduled_effects: &Arc<Mutex<ScheduledEffects>>,
+    schedules: &Arc<Mutex<Schedules>>,
+    scenes: &Arc<Mutex<Scenes>>,
+    rules: &Arc<Mutex<Rules>>,
+    bindings: &Arc<Mutex<Bindings>>,
 ) -> ShutdownCommand {
     let monitors = Monitors {
         effects_update: TaskMonitor::new(),
@@ -47,7 +52,11 @@ pub async fn run_move(
 
     let api = PsMoveApi::new();
     let shutdown_flag = Arc::new(AtomicBool::new(false));
-    let initial_effect = Arc::new(Mutex::new(InitialLedState::from(ON_STARTUP_EFFECT.clone())));
+    let startup_effect = match &settings.lock().await.startup_effect {
+        Some(effect) => effect.to_effect(),
+        None => LedEffect::off(),
+    };
+    let initial_effect = Arc::new(Mutex::new(InitialLedState::from(startup_effect)));
     let (send, recv) = mpsc::channel::<()>(1);
 
     {
@@ -61,13 +70,18 @@ pub async fn run_move(
         ));
     }
 
+    tokio::spawn(scheduler::run(scheduled_effects.clone(), effect_tx.clone()));
+    tokio::spawn(schedules_update::run(schedules.clone(), effect_tx.clone()));
+
     tokio::spawn(monitors.effects_update.instrument(effects_update::run(
         controllers.clone(),
         initial_effect.clone(),
+        bindings.clone(),
     )));
 
     {
         let controllers = controllers.clone();
+        let settings = settings.clone();
         let shutdown_signal = ShutdownSignal::new(&send, &shutdown_flag);
         let monitor = monitors.controllers_list.clone();
 
@@ -77,12 +91,14 @@ pub async fn run_move(
                 api,
                 shutdown_signal,
                 initial_effect,
+                settings,
             )))
         });
     }
 
     {
         let controllers = controllers.clone();
+        let rules_engine = RulesEngine::new(rules.clone(), scenes.clone(), effect_tx.clone());
         let shutdown_signal = ShutdownSignal::new(&send, &shutdown_flag);
         let monitor = monitors.controller_update.clone();
 
@@ -90,6 +106,7 @@ pub async fn run_move(
             Handle::current().block_on(monitor.instrument(controller_update::run(
                 controllers,
                 ctrl_tx,
+                rules_engine,
                 shutdown_signal,
             )))
         });
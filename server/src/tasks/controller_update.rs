@@ -4,17 +4,19 @@ use std::time::Duration;
 use tokio::sync::Mutex;
 use tokio::sync::watch::Sender;
 use tokio::time;
-use tokio::time::MissedTickBehavior;
+use tokio::time::{Instant, MissedTickBehavior};
 
 use crate::ControllerChange;
 use crate::ps_move::controller::PsMoveController;
 use crate::spawn_tasks::ShutdownSignal;
+use crate::tasks::rules_update::RulesEngine;
 
 const INTERVAL_DURATION: Duration = Duration::from_millis(10);
 
 pub async fn run(
     controllers: Arc<Mutex<Vec<PsMoveController>>>,
     ctrl_tx: Sender<ControllerChange>,
+    mut rules_engine: RulesEngine,
     mut shutdown_signal: ShutdownSignal,
 ) {
     let mut interval = time::interval(INTERVAL_DURATION);
@@ -58,5 +60,10 @@ pub async fn run(
             });
 
         controllers.retain(|c| !failed_addresses.contains(&c.bt_address));
+
+        let events = rules_engine.collect_events(&controllers, Instant::now());
+
+        drop(controllers);
+        rules_engine.run_rules(&events).await;
     }
 }
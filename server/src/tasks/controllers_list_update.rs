@@ -1,36 +1,26 @@
 use std::sync::Arc;
 use std::time::Duration;
 
-use palette::Hsv;
 use tokio::sync::Mutex;
 use tokio::time;
-use tokio::time::{Instant, MissedTickBehavior};
+use tokio::time::MissedTickBehavior;
 use crate::monitoring::metrics::CONNECTED_DEVICES_METRIC;
 
 use crate::ps_move::api::PsMoveApi;
 use crate::ps_move::controller::PsMoveController;
-use crate::ps_move::effects::{LedEffect, LedEffectKind};
+use crate::ps_move::effects::LedEffect;
 use crate::ps_move::models::{ConnectionType, ControllerInfo};
+use crate::settings::Settings;
 use crate::spawn_tasks::{InitialLedState, ShutdownSignal};
 
 const INTERVAL_DURATION: Duration = Duration::from_millis(500);
 
-fn get_on_connected_effect() -> LedEffect {
-    LedEffect::new_expiring(
-        LedEffectKind::Blink {
-            hsv: Hsv::from_components((42.0, 1.0, 0.35)),
-            last_blink: Instant::now(),
-            interval: Duration::from_millis(500),
-        },
-        Duration::from_secs(1),
-    )
-}
-
 pub async fn run(
     controllers: Arc<Mutex<Vec<PsMoveController>>>,
     mut api: PsMoveApi,
     mut shutdown_signal: ShutdownSignal,
     initial_state: Arc<Mutex<InitialLedState>>,
+    settings: Arc<Mutex<Settings>>,
 ) {
     let mut interval = time::interval(INTERVAL_DURATION);
 
@@ -67,25 +57,53 @@ pub async fn run(
         remove_disconnected_controllers(&mut controllers, &list_result.disconnected);
 
         let initial_state = initial_state.lock().await;
+        let settings = settings.lock().await;
 
         new_controllers.into_iter().for_each(|mut controller| {
+            controller.color_output = settings.output.for_controller(&controller.bt_address);
+
+            if let Some(frequency) = settings.led_pwm_frequencies.get(&controller.bt_address) {
+                if let Err(err) = controller.set_led_pwm_frequency(*frequency) {
+                    tracing::error!(
+                        "Couldn't reapply led PWM frequency on '{}'. Caused by {err}",
+                        controller.bt_address
+                    );
+                }
+            }
+
             let initial_effect = initial_state.effect.clone();
+            let own_effect = settings.controller_connect_effects.get(&controller.bt_address);
 
-            let effect = if initial_effect.is_off() {
+            let (effect, hsv) = if let Some(own_effect) = own_effect {
+                tracing::info!(
+                    "Setting own on connected effect on '{}'.",
+                    controller.bt_address
+                );
+                let effect = own_effect.to_effect();
+                let hsv = effect.kind.get_initial_hsv();
+
+                (effect, hsv)
+            } else if initial_effect.is_off() {
                 tracing::info!(
                     "Setting on connected effect on '{}'.",
                     controller.bt_address
                 );
-                get_on_connected_effect()
+                let effect = settings
+                    .connect_effect
+                    .as_ref()
+                    .map_or_else(LedEffect::off, |effect| effect.to_effect());
+                let hsv = effect.kind.get_initial_hsv();
+
+                (effect, hsv)
             } else {
                 tracing::info!(
                     "Setting current effect on '{}'. ({initial_effect})",
                     controller.bt_address
                 );
-                initial_effect
+                (initial_effect, initial_state.hsv)
             };
 
-            controller.set_led_effect_with_hsv(effect, initial_state.hsv);
+            controller.set_led_effect_with_hsv(effect, hsv);
             add_connected_controllers(&mut controllers, controller);
         });
 
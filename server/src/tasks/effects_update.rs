@@ -1,12 +1,15 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration};
 
+use rand::thread_rng;
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
 use tokio::time;
-use tokio::time::MissedTickBehavior;
+use tokio::time::{Instant, MissedTickBehavior};
 
-use crate::ps_move::controller::PsMoveController;
+use crate::bindings::Bindings;
+use crate::ps_move::controller::{Modulation, PsMoveController};
 use crate::spawn_tasks::InitialLedState;
 
 pub const INTERVAL_DURATION: Duration = Duration::from_millis(10);
@@ -14,20 +17,34 @@ pub const INTERVAL_DURATION: Duration = Duration::from_millis(10);
 pub async fn run(
     controllers: Arc<Mutex<Vec<PsMoveController>>>,
     initial_state: Arc<Mutex<InitialLedState>>,
+    bindings: Arc<Mutex<Bindings>>,
 ) -> JoinHandle<()> {
     let mut interval = time::interval(INTERVAL_DURATION);
 
     interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
+    let mut last_tick = Instant::now();
+
     loop {
-        interval.tick().await;
+        let tick = interval.tick().await;
+        let elapsed = tick.saturating_duration_since(last_tick);
+        last_tick = tick;
 
         {
+            let bindings = bindings.lock().await;
             let mut controllers = controllers.lock().await;
+            let mut modulations = get_modulations(&bindings, &controllers);
 
             controllers.iter_mut().for_each(|controller| {
-                controller.transform_led();
-                controller.transform_rumble();
+                controller.modulation = modulations
+                    .remove(&controller.bt_address)
+                    .unwrap_or_default();
+
+                for _ in 0..controller.advance_timeline(elapsed) {
+                    controller.transform_sequence();
+                    controller.transform_led();
+                    controller.transform_rumble();
+                }
             });
         }
 
@@ -35,6 +52,36 @@ pub async fn run(
         let current_hsv = initial_state.hsv;
         let effect = &mut initial_state.effect;
 
-        initial_state.hsv = effect.kind.get_updated_hsv(current_hsv);
+        initial_state.hsv = effect
+            .kind
+            .get_updated_hsv(current_hsv, Instant::now(), &mut thread_rng());
+    }
+}
+
+/// Reads the sources of the enabled bindings, by address of the controllers they drive
+fn get_modulations(
+    bindings: &Bindings,
+    controllers: &[PsMoveController],
+) -> HashMap<String, Modulation> {
+    let mut modulations = HashMap::<String, Modulation>::new();
+
+    for binding in bindings.bindings.iter().filter(|binding| binding.enabled) {
+        let sources = controllers.iter().filter(|controller| {
+            binding
+                .controller
+                .as_ref()
+                .is_none_or(|address| *address == controller.bt_address)
+        });
+
+        for source in sources {
+            let value = binding.get_output(binding.source.read(source));
+
+            for target in binding.get_targets(&source.bt_address) {
+                let modulation = modulations.entry(String::from(target)).or_default();
+                binding.parameter.set(modulation, value);
+            }
+        }
     }
+
+    modulations
 }
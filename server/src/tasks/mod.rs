@@ -3,4 +3,7 @@ pub mod controllers_list_update;
 pub mod effects_update;
 pub mod ip_discovery;
 pub mod mutations_handler;
+pub mod rules_update;
+pub mod scheduler;
+pub mod schedules_update;
 pub mod models;
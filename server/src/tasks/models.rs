@@ -1,12 +1,18 @@
 use core::fmt;
+use std::collections::HashMap;
 use std::string::String;
+use std::sync::Arc;
 use std::vec::Vec;
 
 use juniper::{GraphQLEnum, GraphQLObject};
+use palette::Hsv;
+use serde_derive::{Deserialize, Serialize};
 use strum_macros::Display;
+use tokio::time::Instant;
 
-use crate::ps_move::effects::{LedEffect, RumbleEffect};
+use crate::ps_move::effects::{LedEffect, LedEffectPatch, RumbleEffect, Transition};
 use crate::ps_move::models::ButtonState;
+use crate::ps_move::sequence::EffectSequence;
 
 #[derive(Clone)]
 pub enum EffectTarget {
@@ -16,17 +22,115 @@ pub enum EffectTarget {
 
 #[derive(Clone)]
 pub enum EffectChangeType {
-    RevertLed,
-    Led { effect: LedEffect },
+    RevertLed { transition: Option<Transition> },
+    Led { effect: LedEffect, transition: Option<Transition> },
     Rumble { effect: RumbleEffect },
+    /// Changes parameters of the running led effect, keeping its phase
+    PatchLed { patch: LedEffectPatch },
+    /// Led and rumble on one timeline, applied together
+    Combined {
+        led: LedEffect,
+        rumble: RumbleEffect,
+        transition: Option<Transition>,
+    },
+    /// A different effect per controller (by bt address), sharing the same clock
+    LedGroup {
+        effects: Arc<HashMap<String, LedEffect>>,
+        transition: Option<Transition>,
+    },
+    /// Its first item starts at `start`, the next ones following on from it
+    Sequence {
+        sequence: Arc<EffectSequence>,
+        start: Instant,
+    },
+    /// Led and rumble effects per controller (by bt address), sharing the same clock
+    Scene {
+        name: String,
+        effects: Arc<HashMap<String, (LedEffect, RumbleEffect)>>,
+        transition: Option<Transition>,
+    },
+    /// Takes hue and saturation from the colors, keeping the running effects otherwise
+    Recolor {
+        colors: Arc<Vec<Hsv>>,
+        transition: Option<Transition>,
+    },
+    StopSequence,
+    SkipSequenceItem,
+    /// Drops the effects of the change's priority, resuming lower ones
+    Release,
+    /// Moves back through the history of each controller, re-applying the effects there
+    Undo { steps: usize },
+    /// Moves to an entry of the controller's history, re-applying the effects there
+    JumpToHistory { id: u32 },
+    /// Holds the timeline of the effects, freezing the led and rumble where they are
+    Pause,
+    /// Continues the timeline from where it was paused
+    Resume,
+    /// Runs the timeline of the effects `speed` times faster than real time
+    Speed { speed: f32 },
+}
+
+impl EffectChangeType {
+    /// Moves the effects to begin at `start`, instead of when they were created
+    pub fn start_at(&mut self, start: Instant) {
+        match self {
+            EffectChangeType::Led { effect, .. } => effect.start_at(start),
+            EffectChangeType::Rumble { effect } => effect.start_at(start),
+            EffectChangeType::Combined { led, rumble, .. } => {
+                led.start_at(start);
+                rumble.start_at(start);
+            }
+            EffectChangeType::LedGroup { effects, .. } => Arc::make_mut(effects)
+                .values_mut()
+                .for_each(|effect| effect.start_at(start)),
+            EffectChangeType::Scene { effects, .. } => Arc::make_mut(effects)
+                .values_mut()
+                .for_each(|(led, rumble)| {
+                    led.start_at(start);
+                    rumble.start_at(start);
+                }),
+            EffectChangeType::Sequence { start: sequence_start, .. } => *sequence_start = start,
+            EffectChangeType::RevertLed { .. }
+            | EffectChangeType::PatchLed { .. }
+            | EffectChangeType::Recolor { .. }
+            | EffectChangeType::StopSequence
+            | EffectChangeType::SkipSequenceItem
+            | EffectChangeType::Release
+            | EffectChangeType::Undo { .. }
+            | EffectChangeType::JumpToHistory { .. }
+            | EffectChangeType::Pause
+            | EffectChangeType::Resume
+            | EffectChangeType::Speed { .. } => {}
+        }
+    }
 }
 
 impl fmt::Display for EffectChangeType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            EffectChangeType::RevertLed => { write!(f, "RevertLed") }
-            EffectChangeType::Led { effect } => { write!(f, "Led::{}", &effect.kind) }
+            EffectChangeType::RevertLed { .. } => { write!(f, "RevertLed") }
+            EffectChangeType::Led { effect, .. } => { write!(f, "Led::{}", &effect.kind) }
             EffectChangeType::Rumble { effect } => { write!(f, "Rumble::{effect}") }
+            EffectChangeType::PatchLed { .. } => { write!(f, "PatchLed") }
+            EffectChangeType::Combined { led, rumble, .. } => {
+                write!(f, "Combined ({led}, {rumble})")
+            }
+            EffectChangeType::LedGroup { effects, .. } => {
+                write!(f, "LedGroup ({} controllers)", effects.len())
+            }
+            EffectChangeType::Sequence { sequence, .. } => { write!(f, "{sequence}") }
+            EffectChangeType::Scene { name, .. } => { write!(f, "Scene '{name}'") }
+            EffectChangeType::Recolor { colors, .. } => {
+                write!(f, "Recolor ({} colors)", colors.len())
+            }
+            EffectChangeType::StopSequence => { write!(f, "StopSequence") }
+            EffectChangeType::SkipSequenceItem => { write!(f, "SkipSequenceItem") }
+            EffectChangeType::Release => { write!(f, "Release") }
+            EffectChangeType::Undo { steps } => { write!(f, "Undo ({steps} steps)") }
+            EffectChangeType::JumpToHistory { id } => { write!(f, "JumpToHistory ({id})") }
+            EffectChangeType::Pause => { write!(f, "Pause") }
+            EffectChangeType::Resume => { write!(f, "Resume") }
+            EffectChangeType::Speed { speed } => { write!(f, "Speed (x{speed})") }
         }
     }
 }
@@ -35,9 +139,27 @@ impl fmt::Display for EffectChangeType {
 pub struct EffectChange {
     pub target: EffectTarget,
     pub effect: EffectChangeType,
+    pub priority: EffectPriority,
+    /// Who made the change (a client, a schedule...), kept in the history of controllers
+    pub source: String,
+    /// Made by the server itself (a schedule, a rule...), so its repeated changes are merged
+    /// in the history
+    pub automatic: bool,
+}
+
+/// An effect can't replace one with a higher priority while it's active,
+/// it's kept instead and resumes once the higher ones are gone
+#[derive(GraphQLEnum, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Copy, Clone, Debug, Display, Default)]
+pub enum EffectPriority {
+    /// Also what's left once every effect expired
+    Ambient,
+    #[default]
+    Normal,
+    Notification,
+    Alarm,
 }
 
-#[derive(GraphQLEnum, Eq, PartialEq, Hash, Copy, Clone, Debug, Display)]
+#[derive(GraphQLEnum, Serialize, Deserialize, Eq, PartialEq, Hash, Copy, Clone, Debug, Display)]
 pub enum Button {
     Cross,
     Square,
@@ -3,9 +3,13 @@ use std::sync::Arc;
 use tokio::sync::broadcast::Receiver;
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
+use tokio::time::Instant;
 
 use crate::{EffectChange, EffectChangeType, EffectTarget};
+use crate::tasks::models::EffectPriority;
 use crate::ps_move::controller::PsMoveController;
+use crate::ps_move::effects::{LedEffect, RumbleEffect};
+use crate::ps_move::history::{HistoryEntry, HistoryState};
 use crate::spawn_tasks::InitialLedState;
 
 pub async fn run(
@@ -19,22 +23,28 @@ pub async fn run(
                 let mut controllers = controllers.lock().await;
                 let target = effect_change.target;
                 let effect = effect_change.effect;
+                let priority = effect_change.priority;
+                let source = effect_change.source;
+                let automatic = effect_change.automatic;
 
                 match target {
                     EffectTarget::All => {
                         tracing::info!("Setting effect '{effect}' for all controllers");
-                        controllers.iter_mut().for_each(|controller| {
-                            mutate_controller_effect(controller, effect.clone());
+                        controllers.iter_mut().enumerate().for_each(|(index, controller)| {
+                            mutate_controller_effect(controller, index, effect.clone(), priority, &source, automatic);
                             tracing::debug!(
                                 "Controller '{}' set to {effect}",
                                 controller.bt_address
                             );
                         });
 
-                        if let EffectChangeType::Led { effect } = effect {
+                        if let EffectChangeType::Led { effect, .. } = effect {
                             let mut initial_state = initial_state.lock().await;
                             *initial_state = InitialLedState::from(effect.clone());
                             tracing::debug!("Set '{effect}' as initial effect.");
+                        } else if let EffectChangeType::Recolor { colors, .. } = effect {
+                            let mut initial_state = initial_state.lock().await;
+                            initial_state.effect.kind.recolor(&colors, 0);
                         }
                     }
                     EffectTarget::Only { bt_addresses } => {
@@ -42,7 +52,7 @@ pub async fn run(
                             "Setting effect '{effect}' for {} controllers only",
                             bt_addresses.len()
                         );
-                        bt_addresses.iter().for_each(|bt_address| {
+                        bt_addresses.iter().enumerate().for_each(|(index, bt_address)| {
                             controllers
                                 .iter_mut()
                                 .find(|controller| controller.bt_address == *bt_address)
@@ -53,7 +63,7 @@ pub async fn run(
                                     );
                                     },
                                     |controller| {
-                                        mutate_controller_effect(controller, effect.clone());
+                                        mutate_controller_effect(controller, index, effect.clone(), priority, &source, automatic);
                                         tracing::info!(
                                                 "Controller '{}' set to {effect}",
                                                 controller.bt_address
@@ -71,10 +81,247 @@ pub async fn run(
     }
 }
 
-fn mutate_controller_effect(controller: &mut PsMoveController, effect: EffectChangeType) {
+/// `index` is the position of the controller within the target
+///
+/// Only changes actually shown go in the history, not the ones kept behind a higher priority.
+fn mutate_controller_effect(
+    controller: &mut PsMoveController,
+    index: usize,
+    effect: EffectChangeType,
+    priority: EffectPriority,
+    source: &str,
+    automatic: bool,
+) {
+    let history_effect = get_history_effect(controller, &effect);
+
+    update_scene(controller, &effect);
+
+    if apply_controller_effect(controller, index, effect, priority) {
+        if let Some(history_effect) = history_effect {
+            controller.history.record(source, automatic, priority, history_effect);
+        }
+    }
+}
+
+/// Keeps track of the scene the controller shows, until another led effect replaces it
+fn update_scene(controller: &mut PsMoveController, effect: &EffectChangeType) {
+    match effect {
+        EffectChangeType::Scene { name, effects, .. } if effects.contains_key(&controller.bt_address) => {
+            controller.scene = Some(name.clone())
+        }
+        EffectChangeType::LedGroup { effects, .. } if effects.contains_key(&controller.bt_address) => {
+            controller.scene = None
+        }
+        EffectChangeType::Led { .. }
+        | EffectChangeType::RevertLed { .. }
+        | EffectChangeType::Combined { .. }
+        | EffectChangeType::Sequence { .. }
+        | EffectChangeType::Undo { .. }
+        | EffectChangeType::JumpToHistory { .. } => controller.scene = None,
+        _ => {}
+    }
+}
+
+/// The change as it applies to this controller, `None` if it doesn't go in the history
+fn get_history_effect(
+    controller: &PsMoveController,
+    effect: &EffectChangeType,
+) -> Option<EffectChangeType> {
     match effect {
-        EffectChangeType::RevertLed => { controller.revert_led_effect() }
-        EffectChangeType::Led { effect } => controller.set_led_effect(effect),
-        EffectChangeType::Rumble { effect } => controller.set_rumble_effect(effect),
+        EffectChangeType::Led { .. }
+        | EffectChangeType::Rumble { .. }
+        | EffectChangeType::Combined { .. }
+        | EffectChangeType::Sequence { .. } => Some(effect.clone()),
+        EffectChangeType::LedGroup {
+            effects,
+            transition,
+        } => effects
+            .get(&controller.bt_address)
+            .map(|effect| EffectChangeType::Led {
+                effect: effect.clone(),
+                transition: *transition,
+            }),
+        EffectChangeType::Scene {
+            effects,
+            transition,
+            ..
+        } => effects
+            .get(&controller.bt_address)
+            .map(|(led, rumble)| EffectChangeType::Combined {
+                led: led.clone(),
+                rumble: *rumble,
+                transition: *transition,
+            }),
+        _ => None,
+    }
+}
+
+/// Whether effects of `priority` can replace the led and rumble, so the history can be restored
+fn can_restore_history(controller: &PsMoveController, priority: EffectPriority) -> bool {
+    let shown_priority = controller.leds.priority.max(controller.rumbles.priority);
+
+    if priority < shown_priority {
+        tracing::warn!(
+            "Can't restore the history of '{}' over its {shown_priority} effects with {priority} priority",
+            controller.bt_address
+        );
+        return false;
+    }
+    true
+}
+
+/// Re-applies the entries of a history state from now on, turning a channel without any off
+/// (with `priority`)
+fn restore_history(controller: &mut PsMoveController, state: HistoryState, priority: EffectPriority) {
+    let led_id = state.led.as_ref().map(|entry| entry.id);
+
+    match state.led {
+        Some(entry) => apply_history_entry(controller, entry),
+        None => {
+            controller.set_prioritized_led_effect(LedEffect::off(), priority, None);
+        }
+    }
+
+    match state.rumble {
+        // already applied along with the led
+        Some(entry) if Some(entry.id) == led_id => {}
+        Some(entry) => apply_history_entry(controller, entry),
+        None => {
+            controller.set_prioritized_rumble_effect(RumbleEffect::off(), priority);
+        }
     }
 }
+
+fn apply_history_entry(controller: &mut PsMoveController, entry: HistoryEntry) {
+    let mut effect = entry.effect;
+
+    tracing::info!(
+        "Restoring '{effect}' of '{}' from history entry {}",
+        controller.bt_address,
+        entry.id
+    );
+    effect.start_at(Instant::now());
+    apply_controller_effect(controller, 0, effect, entry.priority);
+}
+
+/// Returns `false` if the effects were only kept behind a higher priority (or ignored)
+fn apply_controller_effect(
+    controller: &mut PsMoveController,
+    index: usize,
+    effect: EffectChangeType,
+    priority: EffectPriority,
+) -> bool {
+    match effect {
+        EffectChangeType::RevertLed { transition } => {
+            if priority < controller.leds.priority {
+                tracing::warn!(
+                    "Can't revert {} led effect of '{}' with {priority} priority",
+                    controller.leds.priority,
+                    controller.bt_address
+                );
+                return false;
+            }
+
+            controller.stop_sequence_of(priority);
+            controller.start_led_transition(transition);
+            controller.revert_led_effect();
+        }
+        EffectChangeType::Led { effect, transition } => {
+            controller.stop_sequence_of(priority);
+            return controller.set_prioritized_led_effect(effect, priority, transition);
+        }
+        EffectChangeType::Rumble { effect } => {
+            controller.stop_sequence_of(priority);
+            return controller.set_prioritized_rumble_effect(effect, priority);
+        }
+        EffectChangeType::Combined {
+            led,
+            rumble,
+            transition,
+        } => {
+            controller.stop_sequence_of(priority);
+            let led_shown = controller.set_prioritized_led_effect(led, priority, transition);
+            let rumble_shown = controller.set_prioritized_rumble_effect(rumble, priority);
+
+            return led_shown || rumble_shown;
+        }
+        EffectChangeType::LedGroup {
+            effects,
+            transition,
+        } => {
+            let Some(effect) = effects.get(&controller.bt_address) else {
+                return false;
+            };
+
+            controller.stop_sequence_of(priority);
+            return controller.set_prioritized_led_effect(effect.clone(), priority, transition);
+        }
+        EffectChangeType::Scene {
+            effects,
+            transition,
+            ..
+        } => {
+            let Some((led, rumble)) = effects.get(&controller.bt_address) else {
+                return false;
+            };
+
+            controller.stop_sequence_of(priority);
+            let led_shown = controller.set_prioritized_led_effect(led.clone(), priority, transition);
+            let rumble_shown = controller.set_prioritized_rumble_effect(*rumble, priority);
+
+            return led_shown || rumble_shown;
+        }
+        EffectChangeType::Sequence { sequence, start } => {
+            return controller.set_sequence(sequence, priority, start);
+        }
+        EffectChangeType::Recolor { colors, transition } => {
+            controller.start_led_transition(transition);
+            controller.recolor(&colors, index);
+        }
+        EffectChangeType::StopSequence => controller.stop_sequence(),
+        EffectChangeType::SkipSequenceItem => controller.skip_sequence_item(),
+        EffectChangeType::Release => {
+            controller.stop_sequence_of(priority);
+            controller.release_priority(priority);
+        }
+        EffectChangeType::Undo { steps } => {
+            if !can_restore_history(controller, priority) {
+                return false;
+            }
+
+            let state = controller.history.undo(steps);
+            restore_history(controller, state, priority);
+        }
+        EffectChangeType::PatchLed { patch } => {
+            if priority < controller.leds.priority {
+                tracing::warn!(
+                    "Can't patch {} led effect of '{}' with {priority} priority",
+                    controller.leds.priority,
+                    controller.bt_address
+                );
+                return false;
+            }
+
+            if !controller.patch_led_effect(&patch) {
+                tracing::warn!(
+                    "None of the patched parameters apply to '{}' of '{}'",
+                    controller.led_effect,
+                    controller.bt_address
+                );
+            }
+        }
+        EffectChangeType::Pause => controller.pause(),
+        EffectChangeType::Resume => controller.resume(),
+        EffectChangeType::Speed { speed } => controller.set_speed(speed),
+        EffectChangeType::JumpToHistory { .. } if !can_restore_history(controller, priority) => return false,
+        EffectChangeType::JumpToHistory { id } => match controller.history.jump_to(id) {
+            Some(state) => restore_history(controller, state, priority),
+            None => tracing::warn!(
+                "No history entry {id} on '{}'",
+                controller.bt_address
+            ),
+        },
+    }
+
+    true
+}
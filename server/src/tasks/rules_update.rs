@@ -0,0 +1,386 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Local;
+use reqwest::header::CONTENT_TYPE;
+use tokio::sync::broadcast::Sender;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+use crate::ps_move::controller::PsMoveController;
+use crate::ps_move::effects::{LedEffect, RumbleEffect, Transition};
+use crate::ps_move::models::{BatteryLevel, ButtonState};
+use crate::rules::{ButtonGesture, ControllerEvent, Rule, RuleAction, Rules};
+use crate::scenes::Scenes;
+use crate::tasks::models::{Button, EffectChange, EffectChangeType, EffectTarget};
+
+const HOLD_DURATION: Duration = Duration::from_millis(800);
+/// Longest time between two presses for them to be a double press
+const DOUBLE_PRESS_INTERVAL: Duration = Duration::from_millis(400);
+/// Smallest change of the trigger or of the motion reported as an event, below it's only noise
+const ANALOG_STEP: f32 = 0.05;
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+const BUTTONS: [Button; 8] = [
+    Button::Cross,
+    Button::Square,
+    Button::Circle,
+    Button::Triangle,
+    Button::Move,
+    Button::Start,
+    Button::Select,
+    Button::Trigger,
+];
+
+#[derive(Default)]
+struct ButtonTracker {
+    down_since: Option<Instant>,
+    /// So a hold only fires once per press
+    held: bool,
+    /// Of the last press which wasn't part of a double press
+    last_press: Option<Instant>,
+}
+
+/// What was last seen of a controller, to tell what changed since
+struct ControllerTracker {
+    buttons: HashMap<Button, ButtonTracker>,
+    /// The last reported values, not the last read ones
+    trigger: f32,
+    motion: f32,
+    battery: BatteryLevel,
+}
+
+impl ControllerTracker {
+    fn new(controller: &PsMoveController) -> ControllerTracker {
+        ControllerTracker {
+            buttons: HashMap::new(),
+            trigger: get_trigger(controller),
+            motion: get_motion(controller),
+            battery: controller.battery,
+        }
+    }
+
+    fn update(&mut self, controller: &PsMoveController, now: Instant, events: &mut Vec<ControllerEvent>) {
+        let changed_buttons = controller.get_changed_buttons();
+
+        for button in BUTTONS {
+            let tracker = self.buttons.entry(button).or_default();
+
+            match changed_buttons.get(&button) {
+                Some(ButtonState::Pressed) => {
+                    tracker.down_since = Some(now);
+                    tracker.held = false;
+                    events.push(ControllerEvent::Button { button, gesture: ButtonGesture::Press });
+
+                    match tracker.last_press {
+                        Some(last) if now - last <= DOUBLE_PRESS_INTERVAL => {
+                            tracker.last_press = None;
+                            events.push(ControllerEvent::Button { button, gesture: ButtonGesture::DoublePress });
+                        }
+                        _ => tracker.last_press = Some(now),
+                    }
+                }
+                Some(ButtonState::Released) => {
+                    if tracker.down_since.take().is_some() {
+                        events.push(ControllerEvent::Button { button, gesture: ButtonGesture::Release });
+                    }
+                }
+                None => match tracker.down_since {
+                    Some(since) if !tracker.held && now - since >= HOLD_DURATION => {
+                        tracker.held = true;
+                        events.push(ControllerEvent::Button { button, gesture: ButtonGesture::Hold });
+                    }
+                    _ => {}
+                },
+            }
+        }
+
+        let trigger = get_trigger(controller);
+        if (trigger - self.trigger).abs() >= ANALOG_STEP {
+            events.push(ControllerEvent::Trigger { from: self.trigger, to: trigger });
+            self.trigger = trigger;
+        }
+
+        let motion = get_motion(controller);
+        if (motion - self.motion).abs() >= ANALOG_STEP {
+            events.push(ControllerEvent::Motion { from: self.motion, to: motion });
+            self.motion = motion;
+        }
+
+        if controller.battery != self.battery {
+            // it only becomes known, it didn't change
+            if self.battery != BatteryLevel::Unknown {
+                events.push(ControllerEvent::Battery { level: controller.battery });
+            }
+            self.battery = controller.battery;
+        }
+    }
+}
+
+/// Event of the controller at `address`
+pub struct RuleTrigger {
+    address: String,
+    event: ControllerEvent,
+}
+
+/// Derives events from the state of the controllers, running the rules reacting to them
+pub struct RulesEngine {
+    rules: Arc<Mutex<Rules>>,
+    scenes: Arc<Mutex<Scenes>>,
+    effect_tx: Arc<Sender<EffectChange>>,
+    client: reqwest::Client,
+    trackers: HashMap<String, ControllerTracker>,
+    shown_scenes: HashMap<String, Option<String>>,
+}
+
+impl RulesEngine {
+    pub fn new(
+        rules: Arc<Mutex<Rules>>,
+        scenes: Arc<Mutex<Scenes>>,
+        effect_tx: Arc<Sender<EffectChange>>,
+    ) -> RulesEngine {
+        RulesEngine {
+            rules,
+            scenes,
+            effect_tx,
+            client: reqwest::Client::builder()
+                .timeout(WEBHOOK_TIMEOUT)
+                .build()
+                .expect("Couldn't build the webhook client!"),
+            trackers: HashMap::new(),
+            shown_scenes: HashMap::new(),
+        }
+    }
+
+    /// Events since the last call, from the freshly updated `controllers`
+    pub fn collect_events(&mut self, controllers: &[PsMoveController], now: Instant) -> Vec<RuleTrigger> {
+        let mut events = Vec::new();
+
+        self.shown_scenes.clear();
+
+        for controller in controllers {
+            let address = &controller.bt_address;
+            let mut controller_events = Vec::new();
+
+            self.shown_scenes.insert(address.clone(), controller.scene.clone());
+            self.trackers
+                .entry(address.clone())
+                .or_insert_with(|| {
+                    controller_events.push(ControllerEvent::Connect);
+                    ControllerTracker::new(controller)
+                })
+                .update(controller, now, &mut controller_events);
+
+            events.extend(controller_events.into_iter().map(|event| RuleTrigger {
+                address: address.clone(),
+                event,
+            }));
+        }
+
+        self.trackers.retain(|address, _| {
+            let is_connected = controllers.iter().any(|controller| controller.bt_address == *address);
+
+            if !is_connected {
+                events.push(RuleTrigger {
+                    address: address.clone(),
+                    event: ControllerEvent::Disconnect,
+                });
+            }
+            is_connected
+        });
+
+        events
+    }
+
+    /// Runs the rules reacting to `events` (to call without the controllers locked, as it locks the rules and scenes)
+    pub async fn run_rules(&self, events: &[RuleTrigger]) {
+        if events.is_empty() {
+            return;
+        }
+
+        let mut changes = Vec::<EffectChange>::new();
+
+        {
+            let rules = self.rules.lock().await;
+            let scenes = self.scenes.lock().await;
+            let time = Local::now();
+
+            for RuleTrigger { address, event } in events {
+                let scene = self.shown_scenes.get(address).cloned().flatten();
+
+                rules
+                    .rules
+                    .iter()
+                    .filter(|rule| rule.applies(event, address, scene.as_deref(), time))
+                    .for_each(|rule| {
+                        tracing::info!("Running rule '{}' for {event} on '{address}'", rule.name);
+
+                        for action in &rule.actions {
+                            match action {
+                                RuleAction::Webhook { url } => call_webhook(&self.client, url, rule, address, event),
+                                _ => changes.extend(to_effect_change(
+                                    action,
+                                    rule,
+                                    address,
+                                    &scenes,
+                                    &self.shown_scenes,
+                                )),
+                            }
+                        }
+                    });
+            }
+        }
+
+        changes.into_iter().for_each(|change| {
+            if let Err(err) = self.effect_tx.send(change) {
+                tracing::error!("Couldn't apply effect of rule. Caused by {err}");
+            }
+        });
+    }
+}
+
+/// From 0.0 to 1.0
+fn get_trigger(controller: &PsMoveController) -> f32 {
+    controller.trigger / 255.0
+}
+
+fn get_motion(controller: &PsMoveController) -> f32 {
+    (controller.get_acceleration() - 1.0).abs()
+}
+
+fn to_effect_change(
+    action: &RuleAction,
+    rule: &Rule,
+    address: &str,
+    scenes: &Scenes,
+    shown_scenes: &HashMap<String, Option<String>>,
+) -> Option<EffectChange> {
+    let (bt_addresses, effect) = match action {
+        RuleAction::Led {
+            controllers,
+            effect,
+            duration_ms,
+            transition_ms,
+        } => {
+            let kind = to_kind(effect.to_kind(), rule)?;
+            let effect = match duration_ms {
+                Some(millis) => LedEffect::new_expiring(kind, Duration::from_millis(*millis)),
+                None => LedEffect::new(kind),
+            };
+
+            (
+                get_addresses(controllers, address),
+                EffectChangeType::Led {
+                    effect,
+                    transition: to_transition(*transition_ms),
+                },
+            )
+        }
+        RuleAction::Rumble {
+            controllers,
+            effect,
+            duration_ms,
+        } => {
+            let kind = to_kind(effect.to_kind(), rule)?;
+            let effect = match duration_ms {
+                Some(millis) => RumbleEffect::new_expiring(kind, Duration::from_millis(*millis)),
+                None => RumbleEffect::new(kind),
+            };
+
+            (get_addresses(controllers, address), EffectChangeType::Rumble { effect })
+        }
+        RuleAction::Scene { name, transition_ms } => to_scene_effect(scenes, name, *transition_ms)?,
+        RuleAction::ToggleScene {
+            name,
+            otherwise,
+            transition_ms,
+        } => {
+            let Some(scene) = scenes.get(name) else {
+                tracing::warn!("Rule '{}' toggles a non-existing scene! ('{name}')", rule.name);
+                return None;
+            };
+            let is_shown = scene.controllers.iter().any(|controller| {
+                shown_scenes.get(&controller.address).is_some_and(|shown| shown.as_ref() == Some(name))
+            });
+
+            match otherwise {
+                _ if !is_shown => to_scene_effect(scenes, name, *transition_ms)?,
+                Some(otherwise) => to_scene_effect(scenes, otherwise, *transition_ms)?,
+                None => (
+                    scene.controllers.iter().map(|controller| controller.address.clone()).collect(),
+                    EffectChangeType::Combined {
+                        led: LedEffect::off(),
+                        rumble: RumbleEffect::off(),
+                        transition: to_transition(*transition_ms),
+                    },
+                ),
+            }
+        }
+        RuleAction::Webhook { .. } => return None,
+    };
+
+    Some(EffectChange {
+        target: EffectTarget::Only { bt_addresses },
+        effect,
+        priority: rule.priority,
+        source: rule.get_source(),
+        automatic: true,
+    })
+}
+
+fn to_scene_effect(
+    scenes: &Scenes,
+    name: &str,
+    transition_ms: Option<u64>,
+) -> Option<(Vec<String>, EffectChangeType)> {
+    let Some(scene) = scenes.get(name) else {
+        tracing::warn!("A rule applies a non-existing scene! ('{name}')");
+        return None;
+    };
+    let effects = scene.get_effects(None);
+
+    Some((effects.keys().cloned().collect(), scene.to_effect_type(effects, to_transition(transition_ms))))
+}
+
+/// The controller of the event if there aren't any
+fn get_addresses(controllers: &Option<Vec<String>>, address: &str) -> Vec<String> {
+    controllers.clone().unwrap_or_else(|| vec![String::from(address)])
+}
+
+/// Invalid effects are skipped, which loading the rules already rules out
+fn to_kind<T>(kind: Result<T, String>, rule: &Rule) -> Option<T> {
+    kind.inspect_err(|err| tracing::error!("Rule '{}' has an invalid effect! ({err})", rule.name))
+        .ok()
+}
+
+fn to_transition(transition_ms: Option<u64>) -> Option<Transition> {
+    transition_ms.map(|millis| Transition {
+        duration: Duration::from_millis(millis),
+        color_space: Default::default(),
+    })
+}
+
+fn call_webhook(client: &reqwest::Client, url: &str, rule: &Rule, address: &str, event: &ControllerEvent) {
+    let request = client
+        .post(url)
+        .header(CONTENT_TYPE, "application/json")
+        .body(
+            serde_json::json!({
+                "rule": rule.name,
+                "controller": address,
+                "event": event.to_string(),
+                "time": Local::now().to_rfc3339(),
+            })
+            .to_string(),
+        );
+    let rule = rule.name.clone();
+    let url = String::from(url);
+
+    // so a slow endpoint doesn't hold back the other rules
+    tokio::spawn(async move {
+        match request.send().await.and_then(|response| response.error_for_status()) {
+            Ok(_) => tracing::debug!("Called webhook of rule '{rule}' ({url})"),
+            Err(err) => tracing::error!("Couldn't call webhook of rule '{rule}' ({url}). Caused by {err}"),
+        }
+    });
+}
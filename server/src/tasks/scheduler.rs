@@ -0,0 +1,108 @@
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use tokio::sync::broadcast::Sender;
+use tokio::sync::{Mutex, Notify};
+use tokio::time::{Duration, Instant};
+
+use crate::EffectChange;
+
+/// How long to sleep when nothing is scheduled (a new effect wakes it up anyway)
+const IDLE_DURATION: Duration = Duration::from_secs(60);
+
+pub struct ScheduledEffect {
+    pub id: u32,
+    pub start: Instant,
+    /// Wall-clock equivalent of `start`, for listing
+    pub start_time: SystemTime,
+    pub change: EffectChange,
+}
+
+/// Effect changes waiting to be applied at their start
+pub struct ScheduledEffects {
+    pending: Vec<ScheduledEffect>,
+    next_id: u32,
+    changed: Arc<Notify>,
+}
+
+impl Default for ScheduledEffects {
+    fn default() -> ScheduledEffects {
+        ScheduledEffects {
+            pending: Vec::new(),
+            next_id: 1,
+            changed: Arc::new(Notify::new()),
+        }
+    }
+}
+
+impl ScheduledEffects {
+    /// Returns the id of the scheduled effect
+    pub fn add(&mut self, start: Instant, change: EffectChange) -> u32 {
+        let id = self.next_id;
+        let start_time = SystemTime::now() + start.saturating_duration_since(Instant::now());
+
+        self.next_id += 1;
+        self.pending.push(ScheduledEffect {
+            id,
+            start,
+            start_time,
+            change,
+        });
+        self.pending.sort_by_key(|effect| effect.start);
+        self.changed.notify_one();
+
+        id
+    }
+
+    /// Returns `false` if there was no pending effect with `id`
+    pub fn cancel(&mut self, id: u32) -> bool {
+        let count = self.pending.len();
+
+        self.pending.retain(|effect| effect.id != id);
+        self.changed.notify_one();
+
+        self.pending.len() != count
+    }
+
+    /// Sorted by start
+    pub fn pending(&self) -> &[ScheduledEffect] {
+        &self.pending
+    }
+
+    fn take_due(&mut self, now: Instant) -> Vec<ScheduledEffect> {
+        let due_count = self.pending.iter().take_while(|effect| effect.start <= now).count();
+
+        self.pending.drain(..due_count).collect()
+    }
+}
+
+pub async fn run(scheduled: Arc<Mutex<ScheduledEffects>>, effect_tx: Arc<Sender<EffectChange>>) {
+    let changed = scheduled.lock().await.changed.clone();
+
+    loop {
+        let (due, next_start) = {
+            let mut scheduled = scheduled.lock().await;
+            let due = scheduled.take_due(Instant::now());
+
+            (due, scheduled.pending.first().map(|effect| effect.start))
+        };
+
+        due.into_iter().for_each(|mut effect| {
+            // the effects begin exactly at their start, no matter when they're processed
+            effect.change.effect.start_at(effect.start);
+
+            tracing::info!("Applying scheduled effect {} ({})", effect.id, effect.change.effect);
+
+            if let Err(err) = effect_tx.send(effect.change) {
+                tracing::error!("Couldn't apply scheduled effect {}. Caused by {err}", effect.id);
+            }
+        });
+
+        let wake_up = next_start.unwrap_or_else(|| Instant::now() + IDLE_DURATION);
+
+        tokio::select! {
+            _ = tokio::time::sleep_until(wake_up) => {}
+            _ = changed.notified() => {}
+        }
+    }
+}
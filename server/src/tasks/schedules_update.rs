@@ -0,0 +1,82 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{Local, NaiveDate, Timelike};
+use tokio::sync::broadcast::Sender;
+use tokio::sync::Mutex;
+use tokio::time;
+use tokio::time::MissedTickBehavior;
+
+use crate::EffectChange;
+use crate::schedules::{CircadianProfile, Location, Schedules};
+
+const INTERVAL_DURATION: Duration = Duration::from_secs(1);
+/// Transition when the circadian profile is enabled, changed or disabled
+const CIRCADIAN_CHANGE_TRANSITION: Duration = Duration::from_secs(2);
+
+pub async fn run(schedules: Arc<Mutex<Schedules>>, effect_tx: Arc<Sender<EffectChange>>) {
+    let mut interval = time::interval(INTERVAL_DURATION);
+    let mut last_minute: Option<(NaiveDate, u32)> = None;
+    let mut last_circadian: Option<(CircadianProfile, Location)> = None;
+
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    loop {
+        interval.tick().await;
+
+        let now = Local::now();
+        let minute = (now.date_naive(), now.hour() * 60 + now.minute());
+        let mut changes = Vec::<EffectChange>::new();
+
+        {
+            let schedules = schedules.lock().await;
+
+            // each minute is only checked once, so a schedule fires once
+            if last_minute != Some(minute) {
+                last_minute = Some(minute);
+
+                schedules
+                    .schedules
+                    .iter()
+                    .filter(|schedule| schedule.is_due(now, schedules.location))
+                    .for_each(|schedule| {
+                        tracing::info!("Running schedule '{}'", schedule.name);
+                        changes.push(schedule.to_effect_change());
+                    });
+            }
+
+            // the circadian effect follows the time of day by itself, it's only replaced on changes
+            let circadian = schedules
+                .location
+                .filter(|_| schedules.circadian.enabled)
+                .map(|location| (schedules.circadian.clone(), location));
+
+            if circadian != last_circadian {
+                match (&last_circadian, &circadian) {
+                    // controllers left out of the new profile keep their color too
+                    (Some((last, location)), Some((profile, _))) if last.controllers != profile.controllers => {
+                        changes.push(last.to_stop_change(now, *location, CIRCADIAN_CHANGE_TRANSITION));
+                    }
+                    (Some((last, location)), None) => {
+                        tracing::info!("Stopping circadian lighting");
+                        changes.push(last.to_stop_change(now, *location, CIRCADIAN_CHANGE_TRANSITION));
+                    }
+                    _ => {}
+                }
+
+                if let Some((profile, location)) = &circadian {
+                    tracing::info!("Applying circadian lighting");
+                    changes.push(profile.to_effect_change(*location, CIRCADIAN_CHANGE_TRANSITION));
+                }
+
+                last_circadian = circadian;
+            }
+        }
+
+        changes.into_iter().for_each(|change| {
+            if let Err(err) = effect_tx.send(change) {
+                tracing::error!("Couldn't apply scheduled change. Caused by {err}");
+            }
+        });
+    }
+}